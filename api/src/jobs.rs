@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use vocolo_database::osu::Player;
+
+use crate::routes::AppState;
+
+/// Seconds between refresh runs, overridden by a non-zero `PLAYER_REFRESH_INTERVAL`
+const DEFAULT_PLAYER_REFRESH_INTERVAL: u64 = 60 * 60;
+
+/// Age after which a cached player profile gets refreshed
+const PLAYER_PROFILE_MAX_AGE_HOURS: i64 = 24;
+
+/// Periodically refreshes the cached osu! profiles that went stale
+pub fn spawn_player_refresh(state: AppState) {
+    let interval = std::env::var("PLAYER_REFRESH_INTERVAL")
+        .ok()
+        .and_then(|interval| interval.parse().ok())
+        // A zero period would make the ticker panic
+        .filter(|interval| *interval > 0)
+        .unwrap_or(DEFAULT_PLAYER_REFRESH_INTERVAL);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+
+        loop {
+            ticker.tick().await;
+
+            match Player::refresh_stale(
+                &state.db,
+                &state.osu_api,
+                chrono::Duration::hours(PLAYER_PROFILE_MAX_AGE_HOURS),
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(count) => info!("Refreshed {} osu! player profiles", count),
+                Err(error) => warn!("Failed to refresh osu! player profiles: {}", error),
+            }
+        }
+    });
+}
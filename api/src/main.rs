@@ -3,6 +3,7 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::routes::AppState;
 
+mod jobs;
 mod routes;
 
 #[tokio::main]
@@ -18,12 +19,25 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let permissions =
+        vocolo_internal::Permissions::from_env().expect("Failed to configure permissions");
+
     let db = vocolo_database::connect_to_db()
         .await
         .expect("Failed to connect to database");
+    vocolo_database::run_migrations(&db, &permissions)
+        .await
+        .expect("Failed to migrate database");
 
     let host = std::env::var("HOST_ADDRESS").unwrap_or("0.0.0.0:8080".to_owned());
-    let state = AppState { db };
+    let osu_api = vocolo_internal::OsuApiClient::from_env();
+    let state = AppState {
+        db,
+        permissions,
+        osu_api,
+    };
+
+    jobs::spawn_player_refresh(state.clone());
 
     info!("Starting server at {}", &host);
     let app = routes::init(state);
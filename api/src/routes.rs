@@ -3,6 +3,7 @@ use axum::{
 };
 
 use vocolo_database::Database;
+use vocolo_internal::{OsuApiClient, Permissions};
 
 mod v1;
 
@@ -18,6 +19,8 @@ pub fn init(state: AppState) -> Router {
 #[derive(FromRef, Clone)]
 pub struct AppState {
     pub db: Database,
+    pub permissions: Permissions,
+    pub osu_api: OsuApiClient,
 }
 
 impl FromRef<AppState> for () {
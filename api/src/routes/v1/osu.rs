@@ -2,13 +2,20 @@ use axum::Router;
 
 use super::AppState;
 
+mod invites;
 mod mappools;
 mod matchups;
+mod permissions;
+mod players;
+mod rounds;
 mod tournaments;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
+        .nest("/invites", invites::routes())
         .nest("/mappools", mappools::routes())
         .nest("/matches", matchups::routes())
+        .nest("/players", players::routes())
+        .nest("/rounds", rounds::routes())
         .nest("/tournaments", tournaments::routes())
 }
@@ -0,0 +1,48 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+
+use vocolo_database::{osu::TeamInvite, Database};
+use vocolo_internal::{Result, UserConnections};
+use vocolo_models::v1;
+
+use crate::routes::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(invite_list))
+        .route("/:invite_id/accept", post(invite_accept))
+        .route("/:invite_id/decline", post(invite_decline))
+}
+
+/// Pending invites of the logged in player
+pub async fn invite_list(
+    State(db): State<Database>,
+    connections: UserConnections,
+) -> Result<Json<Vec<v1::osu::TeamInvite>>> {
+    let invites = TeamInvite::list_pending_for_player(&db, connections.osu.id).await?;
+
+    Ok(Json(invites.into_iter().map(|v| v.into()).collect()))
+}
+
+pub async fn invite_accept(
+    State(db): State<Database>,
+    Path(invite_id): Path<String>,
+    connections: UserConnections,
+) -> Result<Json<v1::osu::TeamInvite>> {
+    let invite = TeamInvite::respond(&db, &invite_id, connections.osu.id, true).await?;
+
+    Ok(Json(invite.into()))
+}
+
+pub async fn invite_decline(
+    State(db): State<Database>,
+    Path(invite_id): Path<String>,
+    connections: UserConnections,
+) -> Result<Json<v1::osu::TeamInvite>> {
+    let invite = TeamInvite::respond(&db, &invite_id, connections.osu.id, false).await?;
+
+    Ok(Json(invite.into()))
+}
@@ -1,17 +1,25 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
 use axum_garde::WithValidation;
 
-use vocolo_database::{osu::Mappool, Database};
-use vocolo_internal::{Result, UserConnections};
+use vocolo_database::{
+    osu::{Mappool, Tournament},
+    Database, FAKE_OID,
+};
+use vocolo_internal::{Authorization, Error, Namespace, Relation, Result};
 use vocolo_models::v1;
 
 use crate::routes::AppState;
 
+use super::{
+    permissions::{grant_tuple, list_grants, revoke_grants},
+    tournaments::authorize_tournament,
+};
+
 pub fn routes() -> Router<AppState> {
     Router::new().route("/", post(mappool_create)).nest(
         "/:mappool_id",
@@ -25,10 +33,39 @@ pub fn routes() -> Router<AppState> {
             .route(
                 "/maps/:pos",
                 post(mappool_map_add).delete(mappool_map_remove),
+            )
+            .route(
+                "/permissions",
+                get(mappool_permission_list).post(mappool_permission_grant),
+            )
+            .route(
+                "/permissions/:user_id/:role",
+                delete(mappool_permission_revoke),
             ),
     )
 }
 
+/// Fetches the mappool and checks the user manages it, either through its tournament or directly
+pub async fn authorize_mappool(
+    auth: &Authorization,
+    db: &Database,
+    mappool_id: &str,
+) -> Result<Mappool> {
+    let mappool = Mappool::fetch(db, mappool_id).await?;
+    let tournament_id = mappool.tournament_id.to_hex();
+    let mappool_id = mappool.id.unwrap_or(FAKE_OID).to_hex();
+
+    auth.permissions
+        .require_any(&[
+            auth.tuple(Namespace::Tournament, &tournament_id, Relation::Organizer),
+            auth.tuple(Namespace::Tournament, &tournament_id, Relation::Mappooler),
+            auth.tuple(Namespace::Mappool, &mappool_id, Relation::Mappooler),
+        ])
+        .await?;
+
+    Ok(mappool)
+}
+
 pub async fn mappool_fetch(
     State(db): State<Database>,
     Path(mappool_id): Path<String>,
@@ -39,27 +76,36 @@ pub async fn mappool_fetch(
 }
 
 pub async fn mappool_create(
-    connections: UserConnections,
+    auth: Authorization,
     State(db): State<Database>,
     WithValidation(data): WithValidation<Json<v1::osu::CreateMappoolRequest>>,
 ) -> Result<StatusCode> {
     let data = data.into_inner();
 
-    let mappool: Mappool = data.into();
+    let tournament = authorize_tournament(
+        &auth,
+        &db,
+        &data.tournament_id,
+        &[Relation::Organizer, Relation::Mappooler],
+    )
+    .await?;
+
+    let mut mappool: Mappool = data.into();
+    mappool.tournament_id = tournament.id.unwrap_or(FAKE_OID);
     mappool.create(&db).await?;
 
     Ok(StatusCode::CREATED)
 }
 
 pub async fn mappool_update(
-    connections: UserConnections,
+    auth: Authorization,
     State(db): State<Database>,
     Path(mappool_id): Path<String>,
     WithValidation(data): WithValidation<Json<v1::osu::UpdateMappoolRequest>>,
 ) -> Result<()> {
     let data = data.into_inner();
 
-    let mappool = Mappool::fetch(&db, &mappool_id).await?;
+    authorize_mappool(&auth, &db, &mappool_id).await?;
 
     let mappool = data.into();
     Mappool::update(&db, &mappool_id, &mappool).await?;
@@ -68,23 +114,29 @@ pub async fn mappool_update(
 }
 
 pub async fn mappool_remove(
-    connections: UserConnections,
+    auth: Authorization,
     State(db): State<Database>,
     Path(mappool_id): Path<String>,
 ) -> Result<StatusCode> {
+    let mappool = authorize_mappool(&auth, &db, &mappool_id).await?;
+    let mappool_id = mappool.id.unwrap_or(FAKE_OID).to_hex();
+
     Mappool::delete(&db, &mappool_id).await?;
+    revoke_grants(&auth.permissions, Namespace::Mappool, &mappool_id).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
 pub async fn mappool_map_add(
-    connections: UserConnections,
+    auth: Authorization,
     State(db): State<Database>,
     Path(mappool_id): Path<String>,
     WithValidation(data): WithValidation<Json<v1::osu::AddMappoolMapRequest>>,
 ) -> Result<StatusCode> {
     let data = data.into_inner();
 
+    authorize_mappool(&auth, &db, &mappool_id).await?;
+
     let maps = data.maps.into_iter().map(|map| map.into()).collect();
     Mappool::add_maps(&db, &mappool_id, maps).await?;
 
@@ -92,11 +144,100 @@ pub async fn mappool_map_add(
 }
 
 pub async fn mappool_map_remove(
-    connections: UserConnections,
+    auth: Authorization,
     State(db): State<Database>,
     Path((mappool_id, pos)): Path<(String, i32)>,
 ) -> Result<StatusCode> {
+    authorize_mappool(&auth, &db, &mappool_id).await?;
+
     Mappool::delete_map(&db, &mappool_id, &pos).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// Fetches the mappool and checks the user organizes its tournament
+async fn authorize_mappool_organizer(
+    auth: &Authorization,
+    db: &Database,
+    mappool_id: &str,
+) -> Result<Mappool> {
+    let mappool = Mappool::fetch(db, mappool_id).await?;
+
+    authorize_tournament(
+        auth,
+        db,
+        &mappool.tournament_id.to_hex(),
+        &[Relation::Organizer],
+    )
+    .await?;
+
+    Ok(mappool)
+}
+
+pub async fn mappool_permission_list(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(mappool_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::PermissionGrant>>> {
+    let mappool = authorize_mappool_organizer(&auth, &db, &mappool_id).await?;
+    let mappool_id = mappool.id.unwrap_or(FAKE_OID).to_hex();
+
+    let grants = list_grants(&auth.permissions, Namespace::Mappool, &mappool_id).await?;
+
+    Ok(Json(grants))
+}
+
+pub async fn mappool_permission_grant(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(mappool_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::GrantPermissionRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::PermissionGrant>)> {
+    let data = data.into_inner();
+    check_mappool_role(&data.role)?;
+
+    let mappool = authorize_mappool_organizer(&auth, &db, &mappool_id).await?;
+    let mappool_id = mappool.id.unwrap_or(FAKE_OID).to_hex();
+
+    Tournament::check_staff_not_player(&db, &mappool.tournament_id.to_hex(), data.user_id).await?;
+
+    let tuple = grant_tuple(
+        Namespace::Mappool,
+        &mappool_id,
+        data.user_id,
+        data.role.clone(),
+    );
+    auth.permissions.grant(&tuple).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(v1::osu::PermissionGrant {
+            user_id: data.user_id,
+            role: data.role,
+        }),
+    ))
+}
+
+pub async fn mappool_permission_revoke(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((mappool_id, user_id, role)): Path<(String, i32, v1::osu::TournamentStaffRole)>,
+) -> Result<StatusCode> {
+    check_mappool_role(&role)?;
+
+    let mappool = authorize_mappool_organizer(&auth, &db, &mappool_id).await?;
+    let mappool_id = mappool.id.unwrap_or(FAKE_OID).to_hex();
+
+    let tuple = grant_tuple(Namespace::Mappool, &mappool_id, user_id, role);
+    auth.permissions.revoke(&tuple).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Mappool grants are only checked for the mappooler relation, so no other role is stored
+fn check_mappool_role(role: &v1::osu::TournamentStaffRole) -> Result<()> {
+    match role {
+        v1::osu::TournamentStaffRole::Mappooler => Ok(()),
+        _ => Err(Error::InvalidMappoolRole),
+    }
+}
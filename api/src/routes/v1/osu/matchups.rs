@@ -1,36 +1,76 @@
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
-use axum::routing::{patch, post};
+use axum::routing::{patch, post, put};
 use axum::{Json, Router};
 use axum_garde::WithValidation;
 
 use vocolo_database::{
-    osu::{Matchup, PartialMatchup},
+    osu::{Matchup, MatchupResult, PartialMatchup},
     Database,
 };
-use vocolo_internal::Result;
+use vocolo_internal::{
+    AbstractOsuApi, Authorization, Error, OsuApiClient, OsuMatch, Relation, Result,
+};
 use vocolo_models::v1;
 
 use crate::routes::AppState;
 
+use super::tournaments::authorize_tournament;
+
 pub fn routes() -> Router<AppState> {
     Router::new().route("/", post(matchup_create)).nest(
         "/:match_id",
-        Router::new().route(
-            "/",
-            patch(matchup_update)
-                .get(matchup_fetch)
-                .delete(matchup_delete),
-        ),
+        Router::new()
+            .route(
+                "/",
+                patch(matchup_update)
+                    .get(matchup_fetch)
+                    .delete(matchup_delete),
+            )
+            .route(
+                "/result",
+                put(matchup_result_record).delete(matchup_result_clear),
+            )
+            .route("/maps", post(matchup_map_add))
+            .route(
+                "/maps/:index",
+                patch(matchup_map_update).delete(matchup_map_remove),
+            )
+            .route("/maps/:index/scores", put(matchup_map_scores_record))
+            .route("/import", post(matchup_import))
+            .route("/import/:osu_match_id", post(matchup_import_fetch)),
     )
 }
 
+/// Fetches the matchup and checks the user holds any of `relations` on its tournament
+pub async fn authorize_matchup(
+    auth: &Authorization,
+    db: &Database,
+    match_id: &str,
+    relations: &[Relation],
+) -> Result<Matchup> {
+    let matchup = Matchup::fetch(db, match_id).await?;
+
+    authorize_tournament(auth, db, &matchup.tournament_id.to_hex(), relations).await?;
+
+    Ok(matchup)
+}
+
 pub async fn matchup_create(
+    auth: Authorization,
     State(db): State<Database>,
     WithValidation(data): WithValidation<Json<v1::osu::CreateMatchupRequest>>,
 ) -> Result<Json<v1::osu::CreateMatchupResponse>> {
     let data = data.into_inner();
 
+    authorize_tournament(
+        &auth,
+        &db,
+        &data.tournament_id.to_hex(),
+        &[Relation::Organizer],
+    )
+    .await?;
+
     let matchup: Matchup = data.into();
     let id = matchup.create(&db).await?;
 
@@ -38,16 +78,22 @@ pub async fn matchup_create(
 }
 
 pub async fn matchup_update(
+    auth: Authorization,
     State(db): State<Database>,
     Path(match_id): Path<String>,
     WithValidation(data): WithValidation<Json<v1::osu::UpdateMatchupRequest>>,
 ) -> Result<()> {
     let data = data.into_inner();
 
-    let matchup = PartialMatchup {
-        date: data.date,
-        ..Default::default()
-    };
+    authorize_matchup(
+        &auth,
+        &db,
+        &match_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    let matchup: PartialMatchup = data.into();
 
     Matchup::update(&db, &match_id, &matchup).await?;
 
@@ -64,12 +110,201 @@ pub async fn matchup_fetch(
 }
 
 pub async fn matchup_delete(
+    auth: Authorization,
     State(db): State<Database>,
     Path(match_id): Path<String>,
 ) -> Result<StatusCode> {
+    authorize_matchup(&auth, &db, &match_id, &[Relation::Organizer]).await?;
+
     Matchup::delete(&db, &match_id).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
-pub async fn matchup_map_add() {}
+pub async fn matchup_result_record(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(match_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::RecordMatchupResultRequest>>,
+) -> Result<Json<v1::osu::Matchup>> {
+    let data = data.into_inner();
+
+    authorize_matchup(
+        &auth,
+        &db,
+        &match_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    let result: MatchupResult = data.into();
+    let matchup = Matchup::record_result(&db, &match_id, result).await?;
+
+    Ok(Json(matchup.into()))
+}
+
+pub async fn matchup_result_clear(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(match_id): Path<String>,
+) -> Result<Json<v1::osu::Matchup>> {
+    authorize_matchup(&auth, &db, &match_id, &[Relation::Organizer]).await?;
+
+    let matchup = Matchup::clear_result(&db, &match_id).await?;
+
+    Ok(Json(matchup.into()))
+}
+
+pub async fn matchup_map_add(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(match_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::RecordMatchupMapRequest>>,
+) -> Result<Json<v1::osu::Matchup>> {
+    let data = data.into_inner();
+
+    authorize_matchup(
+        &auth,
+        &db,
+        &match_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    let matchup = Matchup::add_map(
+        &db,
+        &match_id,
+        data.map_type.into(),
+        data.team.into(),
+        data.map_id,
+    )
+    .await?;
+
+    Ok(Json(matchup.into()))
+}
+
+pub async fn matchup_map_update(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((match_id, index)): Path<(String, usize)>,
+    WithValidation(data): WithValidation<Json<v1::osu::UpdateMatchupMapRequest>>,
+) -> Result<Json<v1::osu::Matchup>> {
+    let data = data.into_inner();
+
+    authorize_matchup(
+        &auth,
+        &db,
+        &match_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    let matchup = Matchup::update_map(
+        &db,
+        &match_id,
+        index,
+        data.map_type.map(|map_type| map_type.into()),
+        data.team.map(|team| team.into()),
+        data.map_id,
+    )
+    .await?;
+
+    Ok(Json(matchup.into()))
+}
+
+pub async fn matchup_map_remove(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((match_id, index)): Path<(String, usize)>,
+) -> Result<Json<v1::osu::Matchup>> {
+    authorize_matchup(
+        &auth,
+        &db,
+        &match_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    let matchup = Matchup::remove_map(&db, &match_id, index).await?;
+
+    Ok(Json(matchup.into()))
+}
+
+pub async fn matchup_map_scores_record(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((match_id, index)): Path<(String, usize)>,
+    WithValidation(data): WithValidation<Json<v1::osu::RecordMatchupMapScoresRequest>>,
+) -> Result<Json<v1::osu::Matchup>> {
+    let data = data.into_inner();
+
+    authorize_matchup(
+        &auth,
+        &db,
+        &match_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    let matchup = Matchup::record_map_scores(
+        &db,
+        &match_id,
+        index,
+        data.team_red_scores
+            .into_iter()
+            .map(|score| score.into())
+            .collect(),
+        data.team_blue_scores
+            .into_iter()
+            .map(|score| score.into())
+            .collect(),
+    )
+    .await?;
+
+    Ok(Json(matchup.into()))
+}
+
+/// Imports scores from an osu! API v2 `/matches/{id}` payload
+pub async fn matchup_import(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(match_id): Path<String>,
+    Json(osu_match): Json<OsuMatch>,
+) -> Result<Json<v1::osu::ImportMatchupScoresResponse>> {
+    authorize_matchup(
+        &auth,
+        &db,
+        &match_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    let import = Matchup::import_scores(&db, &match_id, &osu_match).await?;
+
+    Ok(Json(import.into()))
+}
+
+/// Fetches the osu! multiplayer match and imports its scores
+pub async fn matchup_import_fetch(
+    auth: Authorization,
+    State(db): State<Database>,
+    State(osu_api): State<OsuApiClient>,
+    Path((match_id, osu_match_id)): Path<(String, i64)>,
+) -> Result<Json<v1::osu::ImportMatchupScoresResponse>> {
+    authorize_matchup(
+        &auth,
+        &db,
+        &match_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    let osu_match = osu_api
+        .fetch_osu_match(osu_match_id)
+        .await?
+        .ok_or(Error::UnknownOsuMatch)?;
+
+    let import = Matchup::import_scores(&db, &match_id, &osu_match).await?;
+
+    Ok(Json(import.into()))
+}
@@ -0,0 +1,60 @@
+use vocolo_database::osu::TournamentStaffRole;
+use vocolo_internal::{Namespace, Permissions, RelationQuery, RelationTuple, Result};
+use vocolo_models::v1;
+
+pub async fn list_grants(
+    permissions: &Permissions,
+    namespace: Namespace,
+    object: &str,
+) -> Result<Vec<v1::osu::PermissionGrant>> {
+    let tuples = permissions
+        .list(&RelationQuery {
+            namespace: Some(namespace),
+            object: Some(object.to_string()),
+            ..Default::default()
+        })
+        .await?;
+
+    let grants = tuples
+        .into_iter()
+        .filter_map(|tuple| {
+            Some(v1::osu::PermissionGrant {
+                user_id: tuple.subject_id.parse().ok()?,
+                role: TournamentStaffRole::from(tuple.relation).into(),
+            })
+        })
+        .collect();
+
+    Ok(grants)
+}
+
+/// Revokes every grant on the object, once the object itself is gone
+pub async fn revoke_grants(
+    permissions: &Permissions,
+    namespace: Namespace,
+    object: &str,
+) -> Result<()> {
+    let tuples = permissions
+        .list(&RelationQuery {
+            namespace: Some(namespace),
+            object: Some(object.to_string()),
+            ..Default::default()
+        })
+        .await?;
+
+    for tuple in tuples {
+        permissions.revoke(&tuple).await?;
+    }
+
+    Ok(())
+}
+
+pub fn grant_tuple(
+    namespace: Namespace,
+    object: &str,
+    user_id: i32,
+    role: v1::osu::TournamentStaffRole,
+) -> RelationTuple {
+    let role: TournamentStaffRole = role.into();
+    RelationTuple::new(namespace, object, role.into(), user_id)
+}
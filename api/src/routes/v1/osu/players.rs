@@ -0,0 +1,25 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+
+use vocolo_database::{osu::Player, Database};
+use vocolo_internal::Result;
+use vocolo_models::v1;
+
+use crate::routes::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/:player_id", get(player_fetch))
+}
+
+/// Cached osu! profile of the player
+pub async fn player_fetch(
+    State(db): State<Database>,
+    Path(player_id): Path<i32>,
+) -> Result<Json<v1::osu::Player>> {
+    let player = Player::fetch(&db, player_id).await?;
+
+    Ok(Json(player.into()))
+}
@@ -0,0 +1,270 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use axum_garde::WithValidation;
+
+use vocolo_database::{
+    osu::{Group, GroupOptions, Mappool, Matchup, QualifierScore, Round, Tiebreaker},
+    str_to_oid, Database, FAKE_OID,
+};
+use vocolo_internal::{Authorization, Error, Relation, Result};
+use vocolo_models::v1;
+
+use crate::routes::AppState;
+
+use super::tournaments::authorize_tournament;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", post(round_create)).nest(
+        "/:round_id",
+        Router::new()
+            .route(
+                "/",
+                get(round_fetch).patch(round_update).delete(round_delete),
+            )
+            .route("/mappool", get(round_mappool_fetch))
+            .route("/matches", get(round_matchup_list))
+            .route("/groups", get(round_group_list).post(round_group_generate))
+            .route("/groups/next", post(round_group_next))
+            .route("/standings", get(round_standings_fetch))
+            .route(
+                "/qualifier-scores",
+                get(round_qualifier_score_list).put(round_qualifier_score_submit),
+            )
+            .route("/seeding", get(round_seeding_fetch)),
+    )
+}
+
+/// Fetches the round and checks the user holds any of `relations` on its tournament
+pub async fn authorize_round(
+    auth: &Authorization,
+    db: &Database,
+    round_id: &str,
+    relations: &[Relation],
+) -> Result<Round> {
+    let round = Round::fetch(db, round_id).await?;
+
+    authorize_tournament(auth, db, &round.tournament_id.to_hex(), relations).await?;
+
+    Ok(round)
+}
+
+pub async fn round_fetch(
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+) -> Result<Json<v1::osu::Round>> {
+    let round = Round::fetch(&db, &round_id).await?;
+
+    Ok(Json(round.into()))
+}
+
+pub async fn round_create(
+    auth: Authorization,
+    State(db): State<Database>,
+    WithValidation(data): WithValidation<Json<v1::osu::CreateRoundRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::CreateRoundResponse>)> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &data.tournament_id, &[Relation::Organizer]).await?;
+
+    let mut round: Round = data.into();
+    round.tournament_id = tournament.id.unwrap_or(FAKE_OID);
+    let id = round.create(&db).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(v1::osu::CreateRoundResponse { id }),
+    ))
+}
+
+pub async fn round_update(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::UpdateRoundRequest>>,
+) -> Result<()> {
+    let data = data.into_inner();
+
+    authorize_round(&auth, &db, &round_id, &[Relation::Organizer]).await?;
+
+    let round = data.into();
+    Round::update(&db, &round_id, &round).await?;
+
+    Ok(())
+}
+
+pub async fn round_delete(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+) -> Result<StatusCode> {
+    authorize_round(&auth, &db, &round_id, &[Relation::Organizer]).await?;
+
+    Round::delete(&db, &round_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn round_mappool_fetch(
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+) -> Result<Json<v1::osu::Mappool>> {
+    let round = Round::fetch(&db, &round_id).await?;
+    let mappool = Mappool::fetch_by_round(&db, &round.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(mappool.into()))
+}
+
+pub async fn round_matchup_list(
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::Matchup>>> {
+    let round = Round::fetch(&db, &round_id).await?;
+    let matchups = Matchup::list_by_round(&db, &round.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(matchups.into_iter().map(|v| v.into()).collect()))
+}
+
+pub async fn round_group_list(
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::Group>>> {
+    let round = Round::fetch(&db, &round_id).await?;
+    let groups = Group::list(&db, &round.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(groups.into_iter().map(|v| v.into()).collect()))
+}
+
+pub async fn round_group_generate(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::GenerateGroupsRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::GenerateGroupsResponse>)> {
+    let data = data.into_inner();
+
+    let round = authorize_round(&auth, &db, &round_id, &[Relation::Organizer]).await?;
+    let round_id = round.id.unwrap_or(FAKE_OID).to_hex();
+
+    let options: GroupOptions = (&data).into();
+    let seeds = data
+        .seeds
+        .map(|seeds| seeds.iter().map(|id| str_to_oid(id)).collect());
+    let dry_run = data.dry_run.unwrap_or(false);
+
+    let (groups, matchups) = Group::generate(&db, &round_id, seeds, &options, dry_run).await?;
+
+    let status = if dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+
+    Ok((
+        status,
+        Json(v1::osu::GenerateGroupsResponse {
+            groups: groups.into_iter().map(|v| v.into()).collect(),
+            matches: matchups.into_iter().map(|v| v.into()).collect(),
+        }),
+    ))
+}
+
+/// Pairs the next Swiss round of every group in the round
+pub async fn round_group_next(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<v1::osu::Matchup>>)> {
+    let round = authorize_round(&auth, &db, &round_id, &[Relation::Organizer]).await?;
+
+    let matchups = Group::next_swiss_round(&db, &round.id.unwrap_or(FAKE_OID).to_hex()).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(matchups.into_iter().map(|v| v.into()).collect()),
+    ))
+}
+
+pub async fn round_standings_fetch(
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+    Query(query): Query<v1::osu::StandingsQuery>,
+) -> Result<Json<Vec<v1::osu::GroupStandings>>> {
+    let tiebreakers = match query.tiebreakers {
+        Some(tiebreakers) => tiebreakers
+            .split(',')
+            .map(|v| {
+                v1::osu::Tiebreaker::parse(v.trim())
+                    .map(|v| v.into())
+                    .ok_or(Error::InvalidTiebreaker)
+            })
+            .collect::<Result<Vec<Tiebreaker>>>()?,
+        None => Tiebreaker::defaults(),
+    };
+
+    let round = Round::fetch(&db, &round_id).await?;
+    let groups = Group::list(&db, &round.id.unwrap_or(FAKE_OID)).await?;
+
+    let mut standings = vec![];
+    for group in groups {
+        let group_standings = group.standings(&db, &tiebreakers).await?;
+
+        standings.push(v1::osu::GroupStandings {
+            group_id: group.id.unwrap_or(FAKE_OID),
+            name: group.name,
+            standings: group_standings.into_iter().map(|v| v.into()).collect(),
+        });
+    }
+
+    Ok(Json(standings))
+}
+
+pub async fn round_qualifier_score_list(
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::QualifierScore>>> {
+    let round = Round::fetch(&db, &round_id).await?;
+    let scores = QualifierScore::list(&db, &round.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(scores.into_iter().map(|v| v.into()).collect()))
+}
+
+pub async fn round_qualifier_score_submit(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::SubmitQualifierScoreRequest>>,
+) -> Result<()> {
+    let data = data.into_inner();
+
+    authorize_round(
+        &auth,
+        &db,
+        &round_id,
+        &[Relation::Organizer, Relation::Referee],
+    )
+    .await?;
+
+    QualifierScore::submit(&db, &round_id, data.into()).await?;
+
+    Ok(())
+}
+
+pub async fn round_seeding_fetch(
+    State(db): State<Database>,
+    Path(round_id): Path<String>,
+    Query(query): Query<v1::osu::SeedingQuery>,
+) -> Result<Json<v1::osu::SeedingResponse>> {
+    let method = query.method.unwrap_or(v1::osu::SeedingMethod::ZSum);
+
+    let seeds = QualifierScore::seeding(&db, &round_id, method.into()).await?;
+
+    Ok(Json(v1::osu::SeedingResponse {
+        method,
+        seeds: seeds.into_iter().map(|v| v.into()).collect(),
+    }))
+}
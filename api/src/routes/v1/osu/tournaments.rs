@@ -1,32 +1,65 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     routing::{get, post},
     Json, Router,
 };
 use axum_garde::WithValidation;
 
-use vocolo_database::{osu::Tournament, Database};
-use vocolo_internal::{Result, UserConnections};
+use vocolo_database::{
+    osu::{Mappool, Round, Tournament, TournamentQuery, TournamentStaff, TournamentStaffRole},
+    Database, FAKE_OID,
+};
+use vocolo_internal::{Authorization, Namespace, Relation, Result};
 use vocolo_models::v1;
 
-use crate::routes::AppState;
+use crate::routes::{v1::osu::permissions::revoke_grants, AppState};
 
+mod bracket;
+mod invite_codes;
+mod lobbies;
+mod permissions;
 mod staff;
+mod substitutions;
 mod teams;
 
 pub fn routes() -> Router<AppState> {
-    Router::new().route("/", post(tournament_create)).nest(
-        "/:tournament_id",
-        Router::new()
-            .route(
-                "/",
-                get(tournament_fetch)
-                    .patch(tournament_update)
-                    .delete(tournament_delete),
-            )
-            .merge(teams::routes()),
-    )
+    Router::new()
+        .route("/", get(tournament_list).post(tournament_create))
+        .nest(
+            "/:tournament_id",
+            Router::new()
+                .route(
+                    "/",
+                    get(tournament_fetch)
+                        .patch(tournament_update)
+                        .delete(tournament_delete),
+                )
+                .route("/transitions", get(tournament_transitions))
+                .route("/rounds", get(tournament_round_list))
+                .route("/transition", post(tournament_transition))
+                .merge(teams::routes())
+                .merge(bracket::routes())
+                .nest("/invite-codes", invite_codes::routes())
+                .nest("/lobbies", lobbies::routes())
+                .nest("/staff", staff::routes())
+                .nest("/substitutions", substitutions::routes())
+                .nest("/permissions", permissions::routes()),
+        )
+}
+
+pub async fn tournament_list(
+    State(db): State<Database>,
+    WithValidation(query): WithValidation<Query<v1::osu::ListTournamentQuery>>,
+) -> Result<Json<v1::osu::ListTournamentResponse>> {
+    let query: TournamentQuery = query.into_inner().into();
+
+    let (tournaments, next_cursor) = Tournament::list(&db, &query).await?;
+
+    Ok(Json(v1::osu::ListTournamentResponse {
+        tournaments: tournaments.into_iter().map(|v| v.into()).collect(),
+        next_cursor,
+    }))
 }
 
 pub async fn tournament_fetch(
@@ -38,15 +71,38 @@ pub async fn tournament_fetch(
     Ok(Json(tournament.into()))
 }
 
+/// Fetches the tournament and checks the user holds any of `relations` on it
+pub async fn authorize_tournament(
+    auth: &Authorization,
+    db: &Database,
+    tournament_id: &str,
+    relations: &[Relation],
+) -> Result<Tournament> {
+    let tournament = Tournament::fetch(db, tournament_id).await?;
+    let id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    auth.require(Namespace::Tournament, &id, relations).await?;
+
+    Ok(tournament)
+}
+
 pub async fn tournament_create(
-    connections: UserConnections,
+    auth: Authorization,
     State(db): State<Database>,
     WithValidation(data): WithValidation<Json<v1::osu::CreateTournamentRequest>>,
 ) -> Result<(StatusCode, Json<v1::osu::CreateTournamentResponse>)> {
     let data = data.into_inner();
 
-    let tournament: Tournament = data.into();
+    let mut tournament: Tournament = data.into();
+    tournament.staff = Some(vec![TournamentStaff {
+        id: auth.connections.osu.id,
+        roles: vec![TournamentStaffRole::Organizer],
+    }]);
+
     let id = tournament.create(&db).await?;
+    auth.permissions
+        .grant(&auth.tuple(Namespace::Tournament, &id.to_hex(), Relation::Organizer))
+        .await?;
 
     Ok((
         StatusCode::CREATED,
@@ -59,13 +115,17 @@ pub async fn tournament_create(
 }
 
 pub async fn tournament_update(
-    connections: UserConnections,
+    auth: Authorization,
     State(db): State<Database>,
     Path(tournament_id): Path<String>,
     WithValidation(data): WithValidation<Json<v1::osu::UpdateTournamentRequest>>,
 ) -> Result<()> {
     let data = data.into_inner();
 
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
     let tournament = data.into();
     Tournament::update(&db, &tournament_id, &tournament).await?;
 
@@ -73,11 +133,69 @@ pub async fn tournament_update(
 }
 
 pub async fn tournament_delete(
-    connections: UserConnections,
+    auth: Authorization,
     State(db): State<Database>,
     Path(tournament_id): Path<String>,
 ) -> Result<StatusCode> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_oid = tournament.id.unwrap_or(FAKE_OID);
+    let tournament_id = tournament_oid.to_hex();
+
+    // The mappools go with the tournament, list them first to revoke their grants after
+    let mappools = Mappool::list_by_tournament(&db, &tournament_oid).await?;
+
     Tournament::delete(&db, &tournament_id).await?;
 
+    revoke_grants(&auth.permissions, Namespace::Tournament, &tournament_id).await?;
+    for mappool in mappools {
+        let mappool_id = mappool.id.unwrap_or(FAKE_OID).to_hex();
+        revoke_grants(&auth.permissions, Namespace::Mappool, &mappool_id).await?;
+    }
+
     Ok(StatusCode::NO_CONTENT)
 }
+
+pub async fn tournament_transitions(
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<v1::osu::TournamentTransitionsResponse>> {
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+
+    Ok(Json(v1::osu::TournamentTransitionsResponse {
+        stage: tournament.stage.into(),
+        next_stages: tournament
+            .stage
+            .next_stages()
+            .iter()
+            .map(|stage| (*stage).into())
+            .collect(),
+    }))
+}
+
+pub async fn tournament_transition(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::TransitionTournamentRequest>>,
+) -> Result<Json<v1::osu::Tournament>> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    let tournament = Tournament::transition(&db, &tournament_id, data.stage.into()).await?;
+
+    Ok(Json(tournament.into()))
+}
+
+pub async fn tournament_round_list(
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::Round>>> {
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    let rounds = Round::list(&db, &tournament.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(rounds.into_iter().map(|v| v.into()).collect()))
+}
@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use axum_garde::WithValidation;
+
+use vocolo_database::{
+    osu::{BracketOptions, Matchup, QualifierScore, Round},
+    str_to_oid, Database, FAKE_OID,
+};
+use vocolo_internal::{Authorization, Error, Relation, Result};
+use vocolo_models::v1;
+
+use crate::routes::AppState;
+
+use super::authorize_tournament;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/bracket", post(tournament_bracket_generate))
+}
+
+pub async fn tournament_bracket_generate(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::GenerateBracketRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::GenerateBracketResponse>)> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    let options: BracketOptions = (&data).into();
+    let seeds = match (data.seeds, data.seeding) {
+        (Some(seeds), _) => Some(seeds.iter().map(|id| str_to_oid(id)).collect()),
+        (None, Some(seeding)) => {
+            let round = Round::fetch(&db, &seeding.round_id).await?;
+            if Some(round.tournament_id) != tournament.id {
+                return Err(Error::UnknownRound);
+            }
+
+            let seeds = QualifierScore::seeding(&db, &seeding.round_id, seeding.method.into())
+                .await?
+                .into_iter()
+                .map(|seed| seed.team_id)
+                .collect();
+            Some(seeds)
+        }
+        (None, None) => None,
+    };
+    let dry_run = data.dry_run.unwrap_or(false);
+
+    let matchups = Matchup::generate_bracket(&db, &tournament_id, seeds, &options, dry_run).await?;
+
+    let status = if dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+
+    Ok((
+        status,
+        Json(v1::osu::GenerateBracketResponse {
+            matches: matchups.into_iter().map(|v| v.into()).collect(),
+        }),
+    ))
+}
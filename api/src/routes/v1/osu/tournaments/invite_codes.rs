@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use axum_garde::WithValidation;
+
+use vocolo_database::{osu::InviteCode, Database, FAKE_OID};
+use vocolo_internal::{Authorization, Relation, Result};
+use vocolo_models::v1;
+
+use crate::routes::AppState;
+
+use super::authorize_tournament;
+
+const DEFAULT_INVITE_CODE_COUNT: usize = 1;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(invite_code_list).post(invite_code_generate))
+        .route("/:code", delete(invite_code_delete))
+}
+
+pub async fn invite_code_list(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::InviteCode>>> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    let codes = InviteCode::list(&db, &tournament.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(codes.into_iter().map(|v| v.into()).collect()))
+}
+
+pub async fn invite_code_generate(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::GenerateInviteCodesRequest>>,
+) -> Result<(StatusCode, Json<Vec<v1::osu::InviteCode>>)> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    let codes = InviteCode::generate(
+        &db,
+        &tournament.id.unwrap_or(FAKE_OID),
+        auth.connections.osu.id,
+        data.count.unwrap_or(DEFAULT_INVITE_CODE_COUNT),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(codes.into_iter().map(|v| v.into()).collect()),
+    ))
+}
+
+pub async fn invite_code_delete(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, code)): Path<(String, String)>,
+) -> Result<StatusCode> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    InviteCode::delete(&db, &tournament.id.unwrap_or(FAKE_OID), &code).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
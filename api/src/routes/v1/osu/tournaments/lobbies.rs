@@ -0,0 +1,161 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use axum_garde::WithValidation;
+
+use vocolo_database::{
+    osu::{QualifierLobby, Tournament},
+    Database, FAKE_OID,
+};
+use vocolo_internal::{Authorization, Error, Relation, Result, UserConnections};
+use vocolo_models::v1;
+
+use crate::routes::AppState;
+
+use super::authorize_tournament;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(lobby_list).post(lobby_create))
+        .route(
+            "/:lobby_id",
+            get(lobby_fetch).patch(lobby_update).delete(lobby_delete),
+        )
+        .route("/:lobby_id/signup", post(lobby_sign_up).delete(lobby_leave))
+        .route("/:lobby_id/referee", put(lobby_referee_assign))
+}
+
+/// Fetches the lobby, making sure it belongs to the tournament
+async fn fetch_lobby(
+    db: &Database,
+    tournament: &Tournament,
+    lobby_id: &str,
+) -> Result<QualifierLobby> {
+    let lobby = QualifierLobby::fetch(db, lobby_id).await?;
+
+    if Some(lobby.tournament_id) != tournament.id {
+        return Err(Error::UnknownQualifierLobby);
+    }
+
+    Ok(lobby)
+}
+
+pub async fn lobby_list(
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::QualifierLobby>>> {
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    let lobbies = QualifierLobby::list(&db, &tournament.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(lobbies.into_iter().map(|v| v.into()).collect()))
+}
+
+pub async fn lobby_fetch(
+    State(db): State<Database>,
+    Path((tournament_id, lobby_id)): Path<(String, String)>,
+) -> Result<Json<v1::osu::QualifierLobby>> {
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    let lobby = fetch_lobby(&db, &tournament, &lobby_id).await?;
+
+    Ok(Json(lobby.into()))
+}
+
+pub async fn lobby_create(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::CreateQualifierLobbyRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::CreateQualifierLobbyResponse>)> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    let mut lobby: QualifierLobby = data.into();
+    lobby.tournament_id = tournament.id.unwrap_or(FAKE_OID);
+    let id = lobby.create(&db).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(v1::osu::CreateQualifierLobbyResponse { id }),
+    ))
+}
+
+pub async fn lobby_update(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, lobby_id)): Path<(String, String)>,
+    WithValidation(data): WithValidation<Json<v1::osu::UpdateQualifierLobbyRequest>>,
+) -> Result<()> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    fetch_lobby(&db, &tournament, &lobby_id).await?;
+
+    let lobby = data.into();
+    QualifierLobby::update(&db, &lobby_id, &lobby).await?;
+
+    Ok(())
+}
+
+pub async fn lobby_delete(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, lobby_id)): Path<(String, String)>,
+) -> Result<StatusCode> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    fetch_lobby(&db, &tournament, &lobby_id).await?;
+
+    QualifierLobby::delete(&db, &lobby_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Signs the captain's team up for the lobby, moving it out of any other lobby
+pub async fn lobby_sign_up(
+    State(db): State<Database>,
+    Path((tournament_id, lobby_id)): Path<(String, String)>,
+    captain_connections: UserConnections,
+) -> Result<Json<v1::osu::QualifierLobby>> {
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    fetch_lobby(&db, &tournament, &lobby_id).await?;
+
+    let lobby = QualifierLobby::sign_up(&db, &lobby_id, captain_connections.osu.id).await?;
+
+    Ok(Json(lobby.into()))
+}
+
+pub async fn lobby_leave(
+    State(db): State<Database>,
+    Path((tournament_id, lobby_id)): Path<(String, String)>,
+    captain_connections: UserConnections,
+) -> Result<StatusCode> {
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    fetch_lobby(&db, &tournament, &lobby_id).await?;
+
+    QualifierLobby::leave(&db, &lobby_id, captain_connections.osu.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn lobby_referee_assign(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, lobby_id)): Path<(String, String)>,
+    WithValidation(data): WithValidation<Json<v1::osu::AssignRefereeRequest>>,
+) -> Result<()> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    fetch_lobby(&db, &tournament, &lobby_id).await?;
+
+    QualifierLobby::assign_referee(&db, &lobby_id, data.referee).await?;
+
+    Ok(())
+}
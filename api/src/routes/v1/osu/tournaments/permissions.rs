@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use axum_garde::WithValidation;
+
+use vocolo_database::{
+    osu::{Tournament, TournamentStaffRole},
+    Database, FAKE_OID,
+};
+use vocolo_internal::{Authorization, Namespace, Relation, Result};
+use vocolo_models::v1;
+
+use crate::routes::{v1::osu::permissions::list_grants, AppState};
+
+use super::{authorize_tournament, staff::sync_staff_permissions};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(tournament_permission_list).post(tournament_permission_grant),
+        )
+        .route("/:user_id/:role", delete(tournament_permission_revoke))
+}
+
+pub async fn tournament_permission_list(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::PermissionGrant>>> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    let grants = list_grants(&auth.permissions, Namespace::Tournament, &tournament_id).await?;
+
+    Ok(Json(grants))
+}
+
+/// Grants a role through the staff roster, which the relation tuples mirror
+pub async fn tournament_permission_grant(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::GrantPermissionRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::PermissionGrant>)> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    let (previous_roles, staff) =
+        Tournament::grant_staff_role(&db, &tournament_id, data.user_id, data.role.clone().into())
+            .await?;
+    sync_staff_permissions(
+        &auth,
+        &db,
+        &tournament_id,
+        staff.id,
+        &previous_roles,
+        &staff.roles,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(v1::osu::PermissionGrant {
+            user_id: data.user_id,
+            role: data.role,
+        }),
+    ))
+}
+
+pub async fn tournament_permission_revoke(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, user_id, role)): Path<(String, i32, v1::osu::TournamentStaffRole)>,
+) -> Result<StatusCode> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    let role: TournamentStaffRole = role.into();
+    let previous_roles = Tournament::revoke_staff_role(&db, &tournament_id, user_id, &role).await?;
+
+    let roles = previous_roles
+        .iter()
+        .filter(|r| **r != role)
+        .cloned()
+        .collect::<Vec<_>>();
+    sync_staff_permissions(&auth, &db, &tournament_id, user_id, &previous_roles, &roles).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,22 +1,168 @@
 use axum::{
-    routing::{get, post},
-    Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, patch},
+    Json, Router,
 };
+use axum_garde::WithValidation;
+use tracing::warn;
 
-use vocolo_internal::Result;
+use vocolo_database::{
+    osu::{Tournament, TournamentStaff, TournamentStaffRole},
+    Database, FAKE_OID,
+};
+use vocolo_internal::{Authorization, Namespace, Relation, RelationTuple, Result};
+use vocolo_models::v1;
 
 use crate::routes::AppState;
 
+use super::authorize_tournament;
+
 pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/", get(tournament_staff_list))
-        .route("/register", post(tournament_staff_register))
+        .route("/", get(tournament_staff_list).post(tournament_staff_add))
+        .route(
+            "/:staff_id",
+            patch(tournament_staff_update).delete(tournament_staff_remove),
+        )
+}
+
+pub async fn tournament_staff_list(
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::TournamentStaff>>> {
+    let staff = Tournament::get_staff(&db, &tournament_id).await?;
+    let staff: Vec<v1::osu::TournamentStaff> = staff.into_iter().map(|v| v.into()).collect();
+
+    Ok(Json(staff))
+}
+
+pub async fn tournament_staff_add(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+    WithValidation(data): WithValidation<Json<v1::osu::AddTournamentStaffRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::TournamentStaff>)> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    let staff: TournamentStaff = data.into();
+    let staff = Tournament::add_staff(&db, &tournament_id, staff).await?;
+    sync_staff_permissions(&auth, &db, &tournament_id, staff.id, &[], &staff.roles).await?;
+
+    Ok((StatusCode::CREATED, Json(staff.into())))
 }
 
-pub async fn tournament_staff_list() -> Result<()> {
-    unimplemented!()
+pub async fn tournament_staff_update(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, staff_id)): Path<(String, i32)>,
+    WithValidation(data): WithValidation<Json<v1::osu::UpdateTournamentStaffRequest>>,
+) -> Result<Json<v1::osu::TournamentStaff>> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    let previous_roles = Tournament::get_staff(&db, &tournament_id)
+        .await?
+        .into_iter()
+        .find(|s| s.id == staff_id)
+        .map(|s| s.roles)
+        .unwrap_or_default();
+
+    let roles = data.roles.into_iter().map(|role| role.into()).collect();
+    let staff = Tournament::update_staff(&db, &tournament_id, staff_id, roles).await?;
+    sync_staff_permissions(
+        &auth,
+        &db,
+        &tournament_id,
+        staff_id,
+        &previous_roles,
+        &staff.roles,
+    )
+    .await?;
+
+    Ok(Json(staff.into()))
 }
 
-pub async fn tournament_staff_register() {
-    unimplemented!()
+pub async fn tournament_staff_remove(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, staff_id)): Path<(String, i32)>,
+) -> Result<StatusCode> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    let previous_roles = Tournament::get_staff(&db, &tournament_id)
+        .await?
+        .into_iter()
+        .find(|s| s.id == staff_id)
+        .map(|s| s.roles)
+        .unwrap_or_default();
+
+    Tournament::remove_staff(&db, &tournament_id, staff_id).await?;
+    sync_staff_permissions(&auth, &db, &tournament_id, staff_id, &previous_roles, &[]).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Mirrors a staff member's roles into relation tuples on the tournament. The roster
+/// change is undone when the tuples fail to sync rather than left out of step with them
+pub async fn sync_staff_permissions(
+    auth: &Authorization,
+    db: &Database,
+    tournament_id: &str,
+    staff_id: i32,
+    previous_roles: &[TournamentStaffRole],
+    roles: &[TournamentStaffRole],
+) -> Result<()> {
+    let Err(error) =
+        sync_relation_tuples(auth, tournament_id, staff_id, previous_roles, roles).await
+    else {
+        return Ok(());
+    };
+
+    // Tuples synced before the failure go back too, the restart backfill grants any that fail
+    if let Err(error) =
+        sync_relation_tuples(auth, tournament_id, staff_id, roles, previous_roles).await
+    {
+        warn!("Failed to restore the permissions of staff {staff_id} in {tournament_id}: {error}");
+    }
+
+    Tournament::restore_staff(db, tournament_id, staff_id, previous_roles).await?;
+
+    Err(error)
+}
+
+async fn sync_relation_tuples(
+    auth: &Authorization,
+    tournament_id: &str,
+    staff_id: i32,
+    previous_roles: &[TournamentStaffRole],
+    roles: &[TournamentStaffRole],
+) -> Result<()> {
+    let tuple = |role: &TournamentStaffRole| {
+        RelationTuple::new(
+            Namespace::Tournament,
+            tournament_id,
+            role.clone().into(),
+            staff_id,
+        )
+    };
+
+    for role in previous_roles.iter().filter(|r| !roles.contains(r)) {
+        auth.permissions.revoke(&tuple(role)).await?;
+    }
+
+    for role in roles.iter().filter(|r| !previous_roles.contains(r)) {
+        auth.permissions.grant(&tuple(role)).await?;
+    }
+
+    Ok(())
 }
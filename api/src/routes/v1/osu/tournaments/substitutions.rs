@@ -0,0 +1,71 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+
+use vocolo_database::{osu::Substitution, Database, FAKE_OID};
+use vocolo_internal::{Authorization, Relation, Result};
+use vocolo_models::v1;
+
+use crate::routes::AppState;
+
+use super::authorize_tournament;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(substitution_list))
+        .route("/:substitution_id/approve", post(substitution_approve))
+        .route("/:substitution_id/deny", post(substitution_deny))
+}
+
+pub async fn substitution_list(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::Substitution>>> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    let substitutions = Substitution::list(&db, &tournament.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(substitutions.into_iter().map(|v| v.into()).collect()))
+}
+
+pub async fn substitution_approve(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, substitution_id)): Path<(String, String)>,
+) -> Result<Json<v1::osu::Substitution>> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    let substitution = Substitution::approve(
+        &db,
+        &tournament.id.unwrap_or(FAKE_OID),
+        &substitution_id,
+        auth.connections.osu.id,
+    )
+    .await?;
+
+    Ok(Json(substitution.into()))
+}
+
+pub async fn substitution_deny(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, substitution_id)): Path<(String, String)>,
+) -> Result<Json<v1::osu::Substitution>> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    let substitution = Substitution::deny(
+        &db,
+        &tournament.id.unwrap_or(FAKE_OID),
+        &substitution_id,
+        auth.connections.osu.id,
+    )
+    .await?;
+
+    Ok(Json(substitution.into()))
+}
@@ -1,21 +1,62 @@
 use axum::{
     extract::{Path, State},
-    routing::{get, post},
+    http::StatusCode,
+    routing::{delete, get, post},
     Json, Router,
 };
 use axum_garde::WithValidation;
 
-use vocolo_database::{osu::Tournament, osu::TournamentTeam, Database};
-use vocolo_internal::{Result, UserConnections};
+use vocolo_database::{
+    osu::{Player, PlayerSnapshot, Substitution, TeamInvite, Tournament, TournamentTeam},
+    str_to_oid, Database, FAKE_OID,
+};
+use vocolo_internal::{
+    Authorization, Error, Namespace, OsuApiClient, Relation, Result, UserConnections,
+};
 use vocolo_models::v1;
 
 use crate::routes::AppState;
 
+use super::authorize_tournament;
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/teams", get(tournament_team_list))
         .route("/players", get(tournament_player_list))
         .route("/register", post(tournament_register))
+        .route("/register/solo", post(tournament_register_player))
+        .route("/screening", get(tournament_screening))
+        .route("/snapshots", get(tournament_snapshot_list))
+        .route(
+            "/teams/:team_id",
+            get(tournament_team_fetch)
+                .patch(tournament_team_update)
+                .delete(tournament_team_withdraw),
+        )
+        .route(
+            "/teams/:team_id/players/:player_id",
+            delete(tournament_team_player_remove),
+        )
+        .route(
+            "/teams/:team_id/captain",
+            post(tournament_team_captain_transfer),
+        )
+        .route(
+            "/teams/:team_id/disqualify",
+            post(tournament_team_disqualify),
+        )
+        .route(
+            "/teams/:team_id/invites",
+            get(tournament_team_invite_list).post(tournament_team_invite_create),
+        )
+        .route(
+            "/teams/:team_id/invites/:invite_id",
+            delete(tournament_team_invite_revoke),
+        )
+        .route(
+            "/teams/:team_id/substitutions",
+            post(tournament_team_substitution_request),
+        )
 }
 
 pub async fn tournament_team_list(
@@ -38,22 +79,288 @@ pub async fn tournament_player_list(
     Ok(Json(v1::osu::ListPlayerResponse { players }))
 }
 
+/// Registers the logged in player to a solo tournament
+pub async fn tournament_register_player(
+    State(db): State<Database>,
+    State(osu_api): State<OsuApiClient>,
+    Path(tournament_id): Path<String>,
+    connections: UserConnections,
+    WithValidation(data): WithValidation<Json<v1::osu::RegisterTournamentPlayerRequest>>,
+) -> Result<Json<v1::osu::RegisterTournamentTeamResponse>> {
+    let data = data.into_inner();
+    let player_id = connections.osu.id;
+
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    tournament.check_registration(&db, &[player_id]).await?;
+    Player::refresh(&db, &osu_api, &tournament.mode, &[player_id]).await?;
+
+    let entry = Tournament::register_player(
+        &db,
+        &tournament_id,
+        player_id,
+        &connections.osu.username,
+        data.invite_code.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(v1::osu::RegisterTournamentTeamResponse {
+        id: entry.id,
+    }))
+}
+
+pub async fn tournament_screening(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::TeamScreening>>> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    let screening = tournament.screen(&db).await?;
+
+    Ok(Json(screening.into_iter().map(|v| v.into()).collect()))
+}
+
+/// Player profiles as they were when each player joined a roster
+pub async fn tournament_snapshot_list(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path(tournament_id): Path<String>,
+) -> Result<Json<Vec<v1::osu::PlayerSnapshot>>> {
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+
+    let snapshots = PlayerSnapshot::list(&db, &tournament.id.unwrap_or(FAKE_OID)).await?;
+
+    Ok(Json(snapshots.into_iter().map(|v| v.into()).collect()))
+}
+
 pub async fn tournament_register(
     State(db): State<Database>,
+    State(osu_api): State<OsuApiClient>,
     Path(tournament_id): Path<String>,
     captain_connections: UserConnections,
     WithValidation(data): WithValidation<Json<v1::osu::RegisterTournamentTeamRequest>>,
 ) -> Result<Json<v1::osu::RegisterTournamentTeamResponse>> {
     let data = data.into_inner();
     let captain_id = captain_connections.osu.id;
+    let invitees = data.players.clone().unwrap_or_default();
+    let invite_code = data.invite_code.clone();
+
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    tournament.check_registration(&db, &[captain_id]).await?;
+    Player::refresh(
+        &db,
+        &osu_api,
+        &tournament.mode,
+        &[&[captain_id], invitees.as_slice()].concat(),
+    )
+    .await?;
 
     let mut team: TournamentTeam = data.into();
     team.captain = captain_id;
     team.players.push(captain_id);
 
-    let team = Tournament::register_team(&db, &tournament_id, team).await?;
+    let team =
+        Tournament::register_team(&db, &tournament_id, team, &invitees, invite_code.as_deref())
+            .await?;
 
     Ok(Json(v1::osu::RegisterTournamentTeamResponse {
         id: team.id,
     }))
 }
+
+/// Fetches the team, letting its captain or an organizer of the tournament through.
+/// Returns the canonical tournament id along with the team
+async fn authorize_team(
+    auth: &Authorization,
+    db: &Database,
+    tournament_id: &str,
+    team_id: &str,
+) -> Result<(String, TournamentTeam)> {
+    let tournament = Tournament::fetch(db, tournament_id).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+    let team = Tournament::fetch_team(db, &tournament_id, &str_to_oid(team_id)).await?;
+
+    if team.captain != auth.connections.osu.id {
+        auth.require(
+            Namespace::Tournament,
+            &tournament_id,
+            &[Relation::Organizer],
+        )
+        .await?;
+    }
+
+    Ok((tournament_id, team))
+}
+
+pub async fn tournament_team_fetch(
+    State(db): State<Database>,
+    Path((tournament_id, team_id)): Path<(String, String)>,
+) -> Result<Json<v1::osu::TournamentTeam>> {
+    let team = Tournament::fetch_team(&db, &tournament_id, &str_to_oid(&team_id)).await?;
+
+    Ok(Json(team.into()))
+}
+
+pub async fn tournament_team_update(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, team_id)): Path<(String, String)>,
+    WithValidation(data): WithValidation<Json<v1::osu::UpdateTournamentTeamRequest>>,
+) -> Result<()> {
+    let data = data.into_inner();
+
+    let (tournament_id, team) = authorize_team(&auth, &db, &tournament_id, &team_id).await?;
+    Tournament::rename_team(&db, &tournament_id, &team.id, data.name).await?;
+
+    Ok(())
+}
+
+pub async fn tournament_team_withdraw(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, team_id)): Path<(String, String)>,
+) -> Result<StatusCode> {
+    let (tournament_id, team) = authorize_team(&auth, &db, &tournament_id, &team_id).await?;
+    Tournament::withdraw_team(&db, &tournament_id, &team.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Removes a player from the team, players may also remove themselves to leave it
+pub async fn tournament_team_player_remove(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, team_id, player_id)): Path<(String, String, i32)>,
+) -> Result<Json<v1::osu::TournamentTeam>> {
+    let (tournament_id, team) = if player_id == auth.connections.osu.id {
+        let tournament = Tournament::fetch(&db, &tournament_id).await?;
+        let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+        let team = Tournament::fetch_team(&db, &tournament_id, &str_to_oid(&team_id)).await?;
+        (tournament_id, team)
+    } else {
+        authorize_team(&auth, &db, &tournament_id, &team_id).await?
+    };
+
+    let team = Tournament::remove_team_player(&db, &tournament_id, &team.id, player_id).await?;
+
+    Ok(Json(team.into()))
+}
+
+pub async fn tournament_team_captain_transfer(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, team_id)): Path<(String, String)>,
+    WithValidation(data): WithValidation<Json<v1::osu::TransferCaptainRequest>>,
+) -> Result<()> {
+    let data = data.into_inner();
+
+    let (tournament_id, team) = authorize_team(&auth, &db, &tournament_id, &team_id).await?;
+    Tournament::transfer_captain(&db, &tournament_id, &team.id, data.player_id).await?;
+
+    Ok(())
+}
+
+pub async fn tournament_team_disqualify(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, team_id)): Path<(String, String)>,
+    WithValidation(data): WithValidation<Json<v1::osu::DisqualifyTournamentTeamRequest>>,
+) -> Result<()> {
+    let data = data.into_inner();
+
+    let tournament =
+        authorize_tournament(&auth, &db, &tournament_id, &[Relation::Organizer]).await?;
+    let tournament_id = tournament.id.unwrap_or(FAKE_OID).to_hex();
+
+    Tournament::disqualify_team(
+        &db,
+        &tournament_id,
+        &str_to_oid(&team_id),
+        data.disqualified.unwrap_or(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn tournament_team_invite_list(
+    auth: Authorization,
+    State(db): State<Database>,
+    Path((tournament_id, team_id)): Path<(String, String)>,
+) -> Result<Json<Vec<v1::osu::TeamInvite>>> {
+    let (_, team) = authorize_team(&auth, &db, &tournament_id, &team_id).await?;
+    let invites = TeamInvite::list_for_team(&db, &team.id).await?;
+
+    Ok(Json(invites.into_iter().map(|v| v.into()).collect()))
+}
+
+pub async fn tournament_team_invite_create(
+    State(db): State<Database>,
+    State(osu_api): State<OsuApiClient>,
+    Path((tournament_id, team_id)): Path<(String, String)>,
+    captain_connections: UserConnections,
+    WithValidation(data): WithValidation<Json<v1::osu::CreateTeamInviteRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::TeamInvite>)> {
+    let data = data.into_inner();
+
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    Player::refresh(&db, &osu_api, &tournament.mode, &[data.player_id]).await?;
+
+    let invite = TeamInvite::create(
+        &db,
+        &tournament_id,
+        &str_to_oid(&team_id),
+        captain_connections.osu.id,
+        data.player_id,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(invite.into())))
+}
+
+pub async fn tournament_team_invite_revoke(
+    State(db): State<Database>,
+    Path((tournament_id, team_id, invite_id)): Path<(String, String, String)>,
+    captain_connections: UserConnections,
+) -> Result<StatusCode> {
+    let tournament = Tournament::fetch(&db, &tournament_id).await?;
+    let invite = TeamInvite::fetch(&db, &invite_id).await?;
+
+    if Some(invite.tournament_id) != tournament.id || invite.team_id != str_to_oid(&team_id) {
+        return Err(Error::UnknownInvite);
+    }
+
+    TeamInvite::revoke(&db, &invite_id, captain_connections.osu.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn tournament_team_substitution_request(
+    State(db): State<Database>,
+    State(osu_api): State<OsuApiClient>,
+    Path((tournament_id, team_id)): Path<(String, String)>,
+    captain_connections: UserConnections,
+    WithValidation(data): WithValidation<Json<v1::osu::CreateSubstitutionRequest>>,
+) -> Result<(StatusCode, Json<v1::osu::Substitution>)> {
+    let data = data.into_inner();
+
+    if let Some(player_in) = data.player_in {
+        let tournament = Tournament::fetch(&db, &tournament_id).await?;
+        Player::refresh(&db, &osu_api, &tournament.mode, &[player_in]).await?;
+    }
+
+    let substitution = Substitution::request(
+        &db,
+        &tournament_id,
+        &str_to_oid(&team_id),
+        captain_connections.osu.id,
+        data.player_out,
+        data.player_in,
+        data.reason,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(substitution.into())))
+}
@@ -1 +1,2 @@
+mod permission;
 pub mod v1;
@@ -0,0 +1,37 @@
+use vocolo_internal::Relation;
+
+use crate::osu::TournamentStaffRole;
+
+impl From<TournamentStaffRole> for Relation {
+    fn from(role: TournamentStaffRole) -> Self {
+        match &role {
+            TournamentStaffRole::Organizer => Relation::Organizer,
+            TournamentStaffRole::Mappooler => Relation::Mappooler,
+            TournamentStaffRole::Mapper => Relation::Mapper,
+            TournamentStaffRole::Testplayer => Relation::Testplayer,
+            TournamentStaffRole::Referee => Relation::Referee,
+            TournamentStaffRole::Streamer => Relation::Streamer,
+            TournamentStaffRole::Commentator => Relation::Commentator,
+            TournamentStaffRole::Staff => Relation::Staff,
+            TournamentStaffRole::Designer => Relation::Designer,
+            TournamentStaffRole::Developer => Relation::Developer,
+        }
+    }
+}
+
+impl From<Relation> for TournamentStaffRole {
+    fn from(relation: Relation) -> Self {
+        match &relation {
+            Relation::Organizer => TournamentStaffRole::Organizer,
+            Relation::Mappooler => TournamentStaffRole::Mappooler,
+            Relation::Mapper => TournamentStaffRole::Mapper,
+            Relation::Testplayer => TournamentStaffRole::Testplayer,
+            Relation::Referee => TournamentStaffRole::Referee,
+            Relation::Streamer => TournamentStaffRole::Streamer,
+            Relation::Commentator => TournamentStaffRole::Commentator,
+            Relation::Staff => TournamentStaffRole::Staff,
+            Relation::Designer => TournamentStaffRole::Designer,
+            Relation::Developer => TournamentStaffRole::Developer,
+        }
+    }
+}
@@ -36,11 +36,45 @@ impl From<crate::osu::Tournament> for Tournament {
             slug: db_tournament.slug,
             name: db_tournament.name,
             mode: db_tournament.mode.into(),
+            stage: db_tournament.stage.into(),
             invite_only: db_tournament.invite_only,
             min_team_size: db_tournament.min_team_size,
             max_team_size: db_tournament.max_team_size,
             registration_start_date: db_tournament.registration_start_date,
             registration_end_date: db_tournament.registration_end_date,
+            roster_lock_date: db_tournament.roster_lock_date,
+            eligibility: db_tournament.eligibility.map(|rules| rules.into()),
+            scoring: db_tournament.scoring.map(|rules| rules.into()),
+        }
+    }
+}
+
+impl From<crate::osu::TournamentStage> for TournamentStage {
+    fn from(db_stage: crate::osu::TournamentStage) -> Self {
+        match &db_stage {
+            crate::osu::TournamentStage::Draft => TournamentStage::Draft,
+            crate::osu::TournamentStage::Announced => TournamentStage::Announced,
+            crate::osu::TournamentStage::Registration => TournamentStage::Registration,
+            crate::osu::TournamentStage::Screening => TournamentStage::Screening,
+            crate::osu::TournamentStage::Qualifiers => TournamentStage::Qualifiers,
+            crate::osu::TournamentStage::Bracket => TournamentStage::Bracket,
+            crate::osu::TournamentStage::Finished => TournamentStage::Finished,
+            crate::osu::TournamentStage::Cancelled => TournamentStage::Cancelled,
+        }
+    }
+}
+
+impl From<TournamentStage> for crate::osu::TournamentStage {
+    fn from(stage: TournamentStage) -> Self {
+        match &stage {
+            TournamentStage::Draft => crate::osu::TournamentStage::Draft,
+            TournamentStage::Announced => crate::osu::TournamentStage::Announced,
+            TournamentStage::Registration => crate::osu::TournamentStage::Registration,
+            TournamentStage::Screening => crate::osu::TournamentStage::Screening,
+            TournamentStage::Qualifiers => crate::osu::TournamentStage::Qualifiers,
+            TournamentStage::Bracket => crate::osu::TournamentStage::Bracket,
+            TournamentStage::Finished => crate::osu::TournamentStage::Finished,
+            TournamentStage::Cancelled => crate::osu::TournamentStage::Cancelled,
         }
     }
 }
@@ -52,6 +86,54 @@ impl From<crate::osu::TournamentTeam> for TournamentTeam {
             name: db_tournament_team.name,
             captain: db_tournament_team.captain,
             players: db_tournament_team.players,
+            disqualified: db_tournament_team.disqualified,
+        }
+    }
+}
+
+impl From<crate::osu::TournamentStaff> for TournamentStaff {
+    fn from(db_tournament_staff: crate::osu::TournamentStaff) -> Self {
+        Self {
+            id: db_tournament_staff.id,
+            roles: db_tournament_staff
+                .roles
+                .into_iter()
+                .map(|role| role.into())
+                .collect(),
+        }
+    }
+}
+
+impl From<crate::osu::TournamentStaffRole> for TournamentStaffRole {
+    fn from(db_role: crate::osu::TournamentStaffRole) -> Self {
+        match &db_role {
+            crate::osu::TournamentStaffRole::Organizer => TournamentStaffRole::Organizer,
+            crate::osu::TournamentStaffRole::Mappooler => TournamentStaffRole::Mappooler,
+            crate::osu::TournamentStaffRole::Mapper => TournamentStaffRole::Mapper,
+            crate::osu::TournamentStaffRole::Testplayer => TournamentStaffRole::Testplayer,
+            crate::osu::TournamentStaffRole::Referee => TournamentStaffRole::Referee,
+            crate::osu::TournamentStaffRole::Streamer => TournamentStaffRole::Streamer,
+            crate::osu::TournamentStaffRole::Commentator => TournamentStaffRole::Commentator,
+            crate::osu::TournamentStaffRole::Staff => TournamentStaffRole::Staff,
+            crate::osu::TournamentStaffRole::Designer => TournamentStaffRole::Designer,
+            crate::osu::TournamentStaffRole::Developer => TournamentStaffRole::Developer,
+        }
+    }
+}
+
+impl From<TournamentStaffRole> for crate::osu::TournamentStaffRole {
+    fn from(role: TournamentStaffRole) -> Self {
+        match &role {
+            TournamentStaffRole::Organizer => crate::osu::TournamentStaffRole::Organizer,
+            TournamentStaffRole::Mappooler => crate::osu::TournamentStaffRole::Mappooler,
+            TournamentStaffRole::Mapper => crate::osu::TournamentStaffRole::Mapper,
+            TournamentStaffRole::Testplayer => crate::osu::TournamentStaffRole::Testplayer,
+            TournamentStaffRole::Referee => crate::osu::TournamentStaffRole::Referee,
+            TournamentStaffRole::Streamer => crate::osu::TournamentStaffRole::Streamer,
+            TournamentStaffRole::Commentator => crate::osu::TournamentStaffRole::Commentator,
+            TournamentStaffRole::Staff => crate::osu::TournamentStaffRole::Staff,
+            TournamentStaffRole::Designer => crate::osu::TournamentStaffRole::Designer,
+            TournamentStaffRole::Developer => crate::osu::TournamentStaffRole::Developer,
         }
     }
 }
@@ -69,12 +151,16 @@ impl From<CreateTournamentRequest> for crate::osu::Tournament {
             slug: dto.slug.clone(),
             name: dto.name.clone(),
             mode: dto.mode.into(),
+            stage: crate::osu::TournamentStage::Draft,
             invite_only: dto.invite_only.unwrap_or(false),
             min_team_size: dto.min_team_size,
             max_team_size: dto.max_team_size,
             registration_start_date: dto.registration_start_date.unwrap_or(now),
             registration_end_date: dto.registration_end_date.unwrap_or(now),
-            teams: None,
+            roster_lock_date: dto.roster_lock_date,
+            eligibility: dto.eligibility.map(|rules| rules.into()),
+            scoring: dto.scoring.map(|rules| rules.into()),
+            staff: None,
         }
     }
 }
@@ -90,18 +176,70 @@ impl From<UpdateTournamentRequest> for crate::osu::PartialTournament {
             max_team_size: dto.max_team_size,
             registration_start_date: dto.registration_start_date,
             registration_end_date: dto.registration_end_date,
+            roster_lock_date: dto.roster_lock_date,
+            eligibility: dto.eligibility.map(|rules| rules.into()),
+            scoring: dto.scoring.map(|rules| rules.into()),
             ..Default::default()
         }
     }
 }
 
+impl From<TournamentRegistrationStatus> for crate::osu::TournamentRegistrationStatus {
+    fn from(status: TournamentRegistrationStatus) -> Self {
+        match &status {
+            TournamentRegistrationStatus::Upcoming => {
+                crate::osu::TournamentRegistrationStatus::Upcoming
+            }
+            TournamentRegistrationStatus::Open => crate::osu::TournamentRegistrationStatus::Open,
+            TournamentRegistrationStatus::Closed => {
+                crate::osu::TournamentRegistrationStatus::Closed
+            }
+        }
+    }
+}
+
+impl From<TournamentSort> for crate::osu::TournamentSort {
+    fn from(sort: TournamentSort) -> Self {
+        match &sort {
+            TournamentSort::Newest => crate::osu::TournamentSort::Newest,
+            TournamentSort::Oldest => crate::osu::TournamentSort::Oldest,
+            TournamentSort::Name => crate::osu::TournamentSort::Name,
+            TournamentSort::RegistrationStart => crate::osu::TournamentSort::RegistrationStart,
+            TournamentSort::RegistrationEnd => crate::osu::TournamentSort::RegistrationEnd,
+        }
+    }
+}
+
+impl From<ListTournamentQuery> for crate::osu::TournamentQuery {
+    fn from(dto: ListTournamentQuery) -> Self {
+        Self {
+            mode: dto.mode.map(|mode| mode.into()),
+            registration: dto.registration.map(|status| status.into()),
+            search: dto.search,
+            sort: dto.sort.unwrap_or_default().into(),
+            cursor: dto.cursor,
+            limit: dto.limit.unwrap_or(vocolo_models::DEFAULT_PAGE_SIZE),
+        }
+    }
+}
+
 impl From<RegisterTournamentTeamRequest> for crate::osu::TournamentTeam {
     fn from(dto: RegisterTournamentTeamRequest) -> Self {
         Self {
             id: ObjectId::new(),
             name: dto.name.clone(),
             captain: 0,
-            players: dto.players.unwrap_or(vec![]),
+            players: vec![],
+            disqualified: false,
+        }
+    }
+}
+
+impl From<AddTournamentStaffRequest> for crate::osu::TournamentStaff {
+    fn from(dto: AddTournamentStaffRequest) -> Self {
+        Self {
+            id: dto.id,
+            roles: dto.roles.into_iter().map(|role| role.into()).collect(),
         }
     }
 }
@@ -114,6 +252,8 @@ impl From<crate::osu::Mappool> for Mappool {
     fn from(db_mappool: crate::osu::Mappool) -> Self {
         Self {
             id: db_mappool.id.unwrap_or(FAKE_OID),
+            tournament_id: db_mappool.tournament_id,
+            round_id: db_mappool.round_id,
             mappack_link: db_mappool.mappack_link,
             maps: db_mappool.maps.into_iter().map(|map| map.into()).collect(),
         }
@@ -147,6 +287,7 @@ impl From<CreateMappoolRequest> for crate::osu::Mappool {
         Self {
             id: None,
             tournament_id: str_to_oid(&dto.tournament_id),
+            round_id: dto.round_id.map(|id| str_to_oid(&id)),
             private: dto.private.unwrap(),
             mappack_link: "".to_string(),
             maps: vec![],
@@ -169,13 +310,140 @@ impl From<UpdateMappoolRequest> for crate::osu::PartialMappool {
 
 impl From<crate::osu::Matchup> for Matchup {
     fn from(db_matchup: crate::osu::Matchup) -> Self {
+        let (team_red_score, team_blue_score) = db_matchup.map_score();
+
         Self {
             id: db_matchup.id.unwrap_or(FAKE_OID),
             tournament_id: db_matchup.tournament_id,
+            round_id: db_matchup.round_id,
             date: db_matchup.date,
             team_red: db_matchup.team_red,
             team_blue: db_matchup.team_blue,
+            first_team: db_matchup.first_team.map(|team| team.into()),
             maps: db_matchup.maps.into_iter().map(|map| map.into()).collect(),
+            bracket: db_matchup.bracket.map(|bracket| bracket.into()),
+            group: db_matchup.group.map(|group| group.into()),
+            winner_to: db_matchup.winner_to.map(|slot| slot.into()),
+            loser_to: db_matchup.loser_to.map(|slot| slot.into()),
+            red_from: db_matchup.red_from.map(|feeder| feeder.into()),
+            blue_from: db_matchup.blue_from.map(|feeder| feeder.into()),
+            team_red_score,
+            team_blue_score,
+            result: db_matchup.result.map(|result| result.into()),
+        }
+    }
+}
+
+impl From<crate::osu::MatchupBracket> for MatchupBracket {
+    fn from(db_bracket: crate::osu::MatchupBracket) -> Self {
+        Self {
+            side: db_bracket.side.into(),
+            round: db_bracket.round,
+            position: db_bracket.position,
+        }
+    }
+}
+
+impl From<crate::osu::MatchupGroup> for MatchupGroup {
+    fn from(db_group: crate::osu::MatchupGroup) -> Self {
+        Self {
+            group_id: db_group.group_id,
+            round: db_group.round,
+        }
+    }
+}
+
+impl From<crate::osu::BracketSide> for BracketSide {
+    fn from(db_side: crate::osu::BracketSide) -> Self {
+        match &db_side {
+            crate::osu::BracketSide::Winners => BracketSide::Winners,
+            crate::osu::BracketSide::Losers => BracketSide::Losers,
+            crate::osu::BracketSide::GrandFinals => BracketSide::GrandFinals,
+        }
+    }
+}
+
+impl From<crate::osu::MatchupSlot> for MatchupSlot {
+    fn from(db_slot: crate::osu::MatchupSlot) -> Self {
+        Self {
+            matchup_id: db_slot.matchup_id,
+            team: db_slot.team.into(),
+        }
+    }
+}
+
+impl From<crate::osu::MatchupTeam> for MatchupTeam {
+    fn from(db_team: crate::osu::MatchupTeam) -> Self {
+        match &db_team {
+            crate::osu::MatchupTeam::Red => MatchupTeam::Red,
+            crate::osu::MatchupTeam::Blue => MatchupTeam::Blue,
+        }
+    }
+}
+
+impl From<MatchupTeam> for crate::osu::MatchupTeam {
+    fn from(team: MatchupTeam) -> Self {
+        match &team {
+            MatchupTeam::Red => crate::osu::MatchupTeam::Red,
+            MatchupTeam::Blue => crate::osu::MatchupTeam::Blue,
+        }
+    }
+}
+
+impl From<crate::osu::MatchupFeeder> for MatchupFeeder {
+    fn from(db_feeder: crate::osu::MatchupFeeder) -> Self {
+        Self {
+            matchup_id: db_feeder.matchup_id,
+            outcome: db_feeder.outcome.into(),
+        }
+    }
+}
+
+impl From<crate::osu::MatchupOutcome> for MatchupOutcome {
+    fn from(db_outcome: crate::osu::MatchupOutcome) -> Self {
+        match &db_outcome {
+            crate::osu::MatchupOutcome::Winner => MatchupOutcome::Winner,
+            crate::osu::MatchupOutcome::Loser => MatchupOutcome::Loser,
+        }
+    }
+}
+
+impl From<crate::osu::MatchupResult> for MatchupResult {
+    fn from(db_result: crate::osu::MatchupResult) -> Self {
+        Self {
+            winner: db_result.winner.into(),
+            kind: db_result.kind.into(),
+            team_red_score: db_result.team_red_score,
+            team_blue_score: db_result.team_blue_score,
+        }
+    }
+}
+
+impl From<crate::osu::MatchupResultKind> for MatchupResultKind {
+    fn from(db_kind: crate::osu::MatchupResultKind) -> Self {
+        match &db_kind {
+            crate::osu::MatchupResultKind::Played => MatchupResultKind::Played,
+            crate::osu::MatchupResultKind::Walkover => MatchupResultKind::Walkover,
+            crate::osu::MatchupResultKind::Forfeit => MatchupResultKind::Forfeit,
+        }
+    }
+}
+
+impl From<MatchupResultKind> for crate::osu::MatchupResultKind {
+    fn from(kind: MatchupResultKind) -> Self {
+        match &kind {
+            MatchupResultKind::Played => crate::osu::MatchupResultKind::Played,
+            MatchupResultKind::Walkover => crate::osu::MatchupResultKind::Walkover,
+            MatchupResultKind::Forfeit => crate::osu::MatchupResultKind::Forfeit,
+        }
+    }
+}
+
+impl From<BracketFormat> for crate::osu::BracketFormat {
+    fn from(format: BracketFormat) -> Self {
+        match &format {
+            BracketFormat::SingleElimination => crate::osu::BracketFormat::SingleElimination,
+            BracketFormat::DoubleElimination => crate::osu::BracketFormat::DoubleElimination,
         }
     }
 }
@@ -185,13 +453,14 @@ impl From<crate::osu::MatchupMap> for MatchupMap {
         Self {
             map_id: db_matchup_map.map_id,
             map_type: db_matchup_map.map_type.into(),
-            team: Default::default(),
+            team: db_matchup_map.team,
             team_red_scores: db_matchup_map
                 .team_red_scores
                 .map(|v| v.into_iter().map(|score| score.into()).collect()),
             team_blue_scores: db_matchup_map
                 .team_blue_scores
                 .map(|v| v.into_iter().map(|score| score.into()).collect()),
+            winner: db_matchup_map.winner.map(|team| team.into()),
         }
     }
 }
@@ -222,6 +491,112 @@ impl From<crate::osu::MatchupMapScore> for MatchupMapScore {
             player: db_matchup_map_score.player,
             mods: db_matchup_map_score.mods,
             score: db_matchup_map_score.score,
+            accuracy: db_matchup_map_score.accuracy,
+            max_combo: db_matchup_map_score.max_combo,
+            misses: db_matchup_map_score.misses,
+            judgements: db_matchup_map_score
+                .judgements
+                .map(|judgements| judgements.into()),
+        }
+    }
+}
+
+impl From<MatchupMapScore> for crate::osu::MatchupMapScore {
+    fn from(matchup_map_score: MatchupMapScore) -> Self {
+        Self {
+            player: matchup_map_score.player,
+            mods: matchup_map_score.mods,
+            score: matchup_map_score.score,
+            accuracy: matchup_map_score.accuracy,
+            max_combo: matchup_map_score.max_combo,
+            misses: matchup_map_score.misses,
+            judgements: matchup_map_score
+                .judgements
+                .map(|judgements| judgements.into()),
+        }
+    }
+}
+
+impl From<crate::osu::ScoreJudgements> for ScoreJudgements {
+    fn from(db_judgements: crate::osu::ScoreJudgements) -> Self {
+        Self {
+            count_geki: db_judgements.count_geki,
+            count_300: db_judgements.count_300,
+            count_katu: db_judgements.count_katu,
+            count_100: db_judgements.count_100,
+            count_50: db_judgements.count_50,
+            count_miss: db_judgements.count_miss,
+        }
+    }
+}
+
+impl From<ScoreJudgements> for crate::osu::ScoreJudgements {
+    fn from(judgements: ScoreJudgements) -> Self {
+        Self {
+            count_geki: judgements.count_geki,
+            count_300: judgements.count_300,
+            count_katu: judgements.count_katu,
+            count_100: judgements.count_100,
+            count_50: judgements.count_50,
+            count_miss: judgements.count_miss,
+        }
+    }
+}
+
+impl From<crate::osu::ScoringRules> for ScoringRules {
+    fn from(db_rules: crate::osu::ScoringRules) -> Self {
+        Self {
+            win_condition: db_rules.win_condition.into(),
+            version: db_rules.version.into(),
+        }
+    }
+}
+
+impl From<ScoringRules> for crate::osu::ScoringRules {
+    fn from(rules: ScoringRules) -> Self {
+        Self {
+            win_condition: rules.win_condition.into(),
+            version: rules.version.into(),
+        }
+    }
+}
+
+impl From<crate::osu::WinCondition> for WinCondition {
+    fn from(db_condition: crate::osu::WinCondition) -> Self {
+        match &db_condition {
+            crate::osu::WinCondition::Score => WinCondition::Score,
+            crate::osu::WinCondition::Accuracy => WinCondition::Accuracy,
+            crate::osu::WinCondition::Combo => WinCondition::Combo,
+            crate::osu::WinCondition::Misses => WinCondition::Misses,
+        }
+    }
+}
+
+impl From<WinCondition> for crate::osu::WinCondition {
+    fn from(condition: WinCondition) -> Self {
+        match &condition {
+            WinCondition::Score => crate::osu::WinCondition::Score,
+            WinCondition::Accuracy => crate::osu::WinCondition::Accuracy,
+            WinCondition::Combo => crate::osu::WinCondition::Combo,
+            WinCondition::Misses => crate::osu::WinCondition::Misses,
+        }
+    }
+}
+
+impl From<crate::osu::ScoringVersion> for ScoringVersion {
+    fn from(db_version: crate::osu::ScoringVersion) -> Self {
+        match &db_version {
+            crate::osu::ScoringVersion::ScoreV1 => ScoringVersion::ScoreV1,
+            crate::osu::ScoringVersion::ScoreV2 => ScoringVersion::ScoreV2,
+        }
+    }
+}
+
+impl From<ScoringVersion> for crate::osu::ScoringVersion {
+    fn from(version: ScoringVersion) -> Self {
+        match &version {
+            ScoringVersion::ScoreV1 => crate::osu::ScoringVersion::ScoreV1,
+            ScoringVersion::ScoreV2 => crate::osu::ScoringVersion::ScoreV2,
         }
     }
 }
@@ -235,10 +610,55 @@ impl From<CreateMatchupRequest> for crate::osu::Matchup {
         Self {
             id: None,
             tournament_id: dto.tournament_id,
+            round_id: dto.round_id,
             date: dto.date,
             team_red: dto.team_red,
             team_blue: dto.team_blue,
+            first_team: None,
             maps: vec![],
+            bracket: None,
+            group: None,
+            winner_to: None,
+            loser_to: None,
+            red_from: None,
+            blue_from: None,
+            result: None,
+        }
+    }
+}
+
+impl From<&GenerateBracketRequest> for crate::osu::BracketOptions {
+    fn from(dto: &GenerateBracketRequest) -> Self {
+        Self {
+            format: dto.format.into(),
+            grand_finals_reset: dto.grand_finals_reset.unwrap_or(false),
+            date: dto.date,
+        }
+    }
+}
+
+impl From<RecordMatchupResultRequest> for crate::osu::MatchupResult {
+    fn from(dto: RecordMatchupResultRequest) -> Self {
+        Self {
+            winner: dto.winner.into(),
+            kind: dto
+                .kind
+                .map(|kind| kind.into())
+                .unwrap_or(crate::osu::MatchupResultKind::Played),
+            team_red_score: dto.team_red_score.unwrap_or_default(),
+            team_blue_score: dto.team_blue_score.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<crate::osu::MatchupImport> for ImportMatchupScoresResponse {
+    fn from(db_import: crate::osu::MatchupImport) -> Self {
+        Self {
+            matchup: db_import.matchup.into(),
+            imported_maps: db_import.imported_maps,
+            unknown_maps: db_import.unknown_maps,
+            unknown_players: db_import.unknown_players,
+            mismatched_scoring: db_import.mismatched_scoring,
         }
     }
 }
@@ -246,7 +666,500 @@ impl From<CreateMatchupRequest> for crate::osu::Matchup {
 impl From<UpdateMatchupRequest> for crate::osu::PartialMatchup {
     fn from(dto: UpdateMatchupRequest) -> Self {
         Self {
+            round_id: dto.round_id,
             date: dto.date,
+            first_team: dto.first_team.map(|team| team.into()),
+            ..Default::default()
+        }
+    }
+}
+
+//
+// Group
+//
+
+impl From<crate::osu::Group> for Group {
+    fn from(db_group: crate::osu::Group) -> Self {
+        Self {
+            id: db_group.id.unwrap_or(FAKE_OID),
+            tournament_id: db_group.tournament_id,
+            round_id: db_group.round_id,
+            name: db_group.name,
+            format: db_group.format.into(),
+            teams: db_group.teams,
+            swiss_rounds: db_group.swiss_rounds,
+        }
+    }
+}
+
+impl From<crate::osu::GroupFormat> for GroupFormat {
+    fn from(db_format: crate::osu::GroupFormat) -> Self {
+        match &db_format {
+            crate::osu::GroupFormat::RoundRobin => GroupFormat::RoundRobin,
+            crate::osu::GroupFormat::Swiss => GroupFormat::Swiss,
+        }
+    }
+}
+
+impl From<GroupFormat> for crate::osu::GroupFormat {
+    fn from(format: GroupFormat) -> Self {
+        match &format {
+            GroupFormat::RoundRobin => crate::osu::GroupFormat::RoundRobin,
+            GroupFormat::Swiss => crate::osu::GroupFormat::Swiss,
+        }
+    }
+}
+
+impl From<crate::osu::Standing> for Standing {
+    fn from(db_standing: crate::osu::Standing) -> Self {
+        Self {
+            team_id: db_standing.team_id,
+            position: db_standing.position,
+            played: db_standing.played,
+            wins: db_standing.wins,
+            losses: db_standing.losses,
+            map_wins: db_standing.map_wins,
+            map_losses: db_standing.map_losses,
+        }
+    }
+}
+
+impl From<Tiebreaker> for crate::osu::Tiebreaker {
+    fn from(tiebreaker: Tiebreaker) -> Self {
+        match &tiebreaker {
+            Tiebreaker::MatchWins => crate::osu::Tiebreaker::MatchWins,
+            Tiebreaker::MapDifferential => crate::osu::Tiebreaker::MapDifferential,
+            Tiebreaker::HeadToHead => crate::osu::Tiebreaker::HeadToHead,
+        }
+    }
+}
+
+//
+// Group DTO
+//
+
+impl From<&GenerateGroupsRequest> for crate::osu::GroupOptions {
+    fn from(dto: &GenerateGroupsRequest) -> Self {
+        Self {
+            format: dto.format.into(),
+            group_count: dto.group_count,
+            swiss_rounds: dto.swiss_rounds,
+        }
+    }
+}
+
+//
+// Qualifier lobby
+//
+
+impl From<crate::osu::QualifierLobby> for QualifierLobby {
+    fn from(db_lobby: crate::osu::QualifierLobby) -> Self {
+        Self {
+            id: db_lobby.id.unwrap_or(FAKE_OID),
+            tournament_id: db_lobby.tournament_id,
+            round_id: db_lobby.round_id,
+            name: db_lobby.name,
+            date: db_lobby.date,
+            signup_deadline: db_lobby.signup_deadline,
+            capacity: db_lobby.capacity,
+            teams: db_lobby.teams,
+            referee: db_lobby.referee,
+        }
+    }
+}
+
+impl From<crate::osu::QualifierScore> for QualifierScore {
+    fn from(db_score: crate::osu::QualifierScore) -> Self {
+        Self {
+            team_id: db_score.team_id,
+            map_id: db_score.map_id,
+            scores: db_score.scores.into_iter().map(|v| v.into()).collect(),
+        }
+    }
+}
+
+impl From<crate::osu::Seed> for Seed {
+    fn from(db_seed: crate::osu::Seed) -> Self {
+        Self {
+            team_id: db_seed.team_id,
+            seed: db_seed.seed,
+            value: db_seed.value,
+        }
+    }
+}
+
+impl From<SeedingMethod> for crate::osu::SeedingMethod {
+    fn from(method: SeedingMethod) -> Self {
+        match &method {
+            SeedingMethod::SumOfScores => crate::osu::SeedingMethod::SumOfScores,
+            SeedingMethod::ZSum => crate::osu::SeedingMethod::ZSum,
+            SeedingMethod::Percentile => crate::osu::SeedingMethod::Percentile,
+            SeedingMethod::RankSum => crate::osu::SeedingMethod::RankSum,
+        }
+    }
+}
+
+//
+// Qualifier lobby DTO
+//
+
+impl From<CreateQualifierLobbyRequest> for crate::osu::QualifierLobby {
+    fn from(dto: CreateQualifierLobbyRequest) -> Self {
+        Self {
+            id: None,
+            tournament_id: FAKE_OID,
+            round_id: dto.round_id,
+            name: dto.name,
+            date: dto.date,
+            signup_deadline: dto.signup_deadline.unwrap_or(dto.date),
+            capacity: dto.capacity,
+            teams: vec![],
+            referee: None,
+        }
+    }
+}
+
+impl From<UpdateQualifierLobbyRequest> for crate::osu::PartialQualifierLobby {
+    fn from(dto: UpdateQualifierLobbyRequest) -> Self {
+        Self {
+            name: dto.name,
+            date: dto.date,
+            signup_deadline: dto.signup_deadline,
+            capacity: dto.capacity,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<SubmitQualifierScoreRequest> for crate::osu::QualifierScore {
+    fn from(dto: SubmitQualifierScoreRequest) -> Self {
+        Self {
+            id: None,
+            tournament_id: FAKE_OID,
+            round_id: FAKE_OID,
+            team_id: dto.team_id,
+            map_id: dto.map_id,
+            scores: dto.scores.into_iter().map(|v| v.into()).collect(),
+        }
+    }
+}
+
+//
+// Invite
+//
+
+impl From<crate::osu::TeamInvite> for TeamInvite {
+    fn from(db_invite: crate::osu::TeamInvite) -> Self {
+        Self {
+            id: db_invite.id.unwrap_or(FAKE_OID),
+            tournament_id: db_invite.tournament_id,
+            team_id: db_invite.team_id,
+            player_id: db_invite.player_id,
+            invited_by: db_invite.invited_by,
+            status: db_invite.status.into(),
+            created_at: db_invite.created_at,
+        }
+    }
+}
+
+impl From<crate::osu::InviteStatus> for InviteStatus {
+    fn from(db_status: crate::osu::InviteStatus) -> Self {
+        match &db_status {
+            crate::osu::InviteStatus::Pending => InviteStatus::Pending,
+            crate::osu::InviteStatus::Accepted => InviteStatus::Accepted,
+            crate::osu::InviteStatus::Declined => InviteStatus::Declined,
+            crate::osu::InviteStatus::Revoked => InviteStatus::Revoked,
+        }
+    }
+}
+
+impl From<crate::osu::InviteCode> for InviteCode {
+    fn from(db_code: crate::osu::InviteCode) -> Self {
+        Self {
+            code: db_code.code,
+            created_by: db_code.created_by,
+            created_at: db_code.created_at,
+            used_by: db_code.used_by,
+        }
+    }
+}
+
+//
+// Player
+//
+
+impl From<crate::osu::Player> for Player {
+    fn from(db_player: crate::osu::Player) -> Self {
+        Self {
+            id: db_player.id,
+            username: db_player.username,
+            country: db_player.country,
+            badges: db_player.badges,
+            stats: db_player.stats.into_iter().map(|v| v.into()).collect(),
+            updated_at: db_player.updated_at,
+        }
+    }
+}
+
+impl From<crate::osu::PlayerStats> for PlayerStats {
+    fn from(db_stats: crate::osu::PlayerStats) -> Self {
+        Self {
+            mode: db_stats.mode.into(),
+            global_rank: db_stats.global_rank,
+            country_rank: db_stats.country_rank,
+            pp: db_stats.pp,
+        }
+    }
+}
+
+impl From<crate::osu::PlayerSnapshot> for PlayerSnapshot {
+    fn from(db_snapshot: crate::osu::PlayerSnapshot) -> Self {
+        Self {
+            tournament_id: db_snapshot.tournament_id,
+            player: db_snapshot.player.into(),
+            taken_at: db_snapshot.taken_at,
+        }
+    }
+}
+
+//
+// Eligibility
+//
+
+impl From<crate::osu::EligibilityRules> for EligibilityRules {
+    fn from(db_rules: crate::osu::EligibilityRules) -> Self {
+        Self {
+            min_rank: db_rules.min_rank,
+            max_rank: db_rules.max_rank,
+            badge_weighted: db_rules.badge_weighted,
+            countries: db_rules.countries,
+        }
+    }
+}
+
+impl From<EligibilityRules> for crate::osu::EligibilityRules {
+    fn from(rules: EligibilityRules) -> Self {
+        Self {
+            min_rank: rules.min_rank,
+            max_rank: rules.max_rank,
+            badge_weighted: rules.badge_weighted,
+            countries: rules
+                .countries
+                .into_iter()
+                .map(|country| country.to_uppercase())
+                .collect(),
+        }
+    }
+}
+
+impl From<crate::osu::IneligibilityReason> for IneligibilityReason {
+    fn from(db_reason: crate::osu::IneligibilityReason) -> Self {
+        match db_reason {
+            crate::osu::IneligibilityReason::MissingProfile => IneligibilityReason::MissingProfile,
+            crate::osu::IneligibilityReason::Unranked => IneligibilityReason::Unranked,
+            crate::osu::IneligibilityReason::RankAboveRange { rank, min_rank } => {
+                IneligibilityReason::RankAboveRange { rank, min_rank }
+            }
+            crate::osu::IneligibilityReason::RankBelowRange { rank, max_rank } => {
+                IneligibilityReason::RankBelowRange { rank, max_rank }
+            }
+            crate::osu::IneligibilityReason::CountryNotAllowed { country } => {
+                IneligibilityReason::CountryNotAllowed { country }
+            }
+        }
+    }
+}
+
+impl From<crate::osu::PlayerEligibility> for PlayerEligibility {
+    fn from(db_eligibility: crate::osu::PlayerEligibility) -> Self {
+        Self {
+            player_id: db_eligibility.player_id,
+            eligible: db_eligibility.is_eligible(),
+            reasons: db_eligibility
+                .reasons
+                .into_iter()
+                .map(|v| v.into())
+                .collect(),
+        }
+    }
+}
+
+impl From<crate::osu::TeamScreening> for TeamScreening {
+    fn from(db_screening: crate::osu::TeamScreening) -> Self {
+        Self {
+            team_id: db_screening.team_id,
+            eligible: db_screening
+                .players
+                .iter()
+                .all(|player| player.is_eligible()),
+            players: db_screening.players.into_iter().map(|v| v.into()).collect(),
+        }
+    }
+}
+
+//
+// Substitution
+//
+
+impl From<crate::osu::Substitution> for Substitution {
+    fn from(db_substitution: crate::osu::Substitution) -> Self {
+        Self {
+            id: db_substitution.id.unwrap_or(FAKE_OID),
+            tournament_id: db_substitution.tournament_id,
+            team_id: db_substitution.team_id,
+            player_out: db_substitution.player_out,
+            player_in: db_substitution.player_in,
+            reason: db_substitution.reason,
+            requested_by: db_substitution.requested_by,
+            status: db_substitution.status.into(),
+            created_at: db_substitution.created_at,
+            resolved_by: db_substitution.resolved_by,
+        }
+    }
+}
+
+impl From<crate::osu::SubstitutionStatus> for SubstitutionStatus {
+    fn from(db_status: crate::osu::SubstitutionStatus) -> Self {
+        match &db_status {
+            crate::osu::SubstitutionStatus::Pending => SubstitutionStatus::Pending,
+            crate::osu::SubstitutionStatus::Approved => SubstitutionStatus::Approved,
+            crate::osu::SubstitutionStatus::Denied => SubstitutionStatus::Denied,
+        }
+    }
+}
+
+//
+// Round
+//
+
+impl From<crate::osu::Round> for Round {
+    fn from(db_round: crate::osu::Round) -> Self {
+        Self {
+            id: db_round.id.unwrap_or(FAKE_OID),
+            tournament_id: db_round.tournament_id,
+            name: db_round.name,
+            kind: db_round.kind.into(),
+            order: db_round.order,
+            best_of: db_round.best_of,
+            ban_count: db_round.ban_count,
+            pick_ban_sequence: db_round.pick_ban_sequence.map(|sequence| sequence.into()),
+            scoring: db_round.scoring.map(|rules| rules.into()),
+            start_date: db_round.start_date,
+            end_date: db_round.end_date,
+        }
+    }
+}
+
+impl From<crate::osu::RoundKind> for RoundKind {
+    fn from(db_kind: crate::osu::RoundKind) -> Self {
+        match &db_kind {
+            crate::osu::RoundKind::Qualifiers => RoundKind::Qualifiers,
+            crate::osu::RoundKind::Groups => RoundKind::Groups,
+            crate::osu::RoundKind::Bracket => RoundKind::Bracket,
+        }
+    }
+}
+
+impl From<RoundKind> for crate::osu::RoundKind {
+    fn from(kind: RoundKind) -> Self {
+        match &kind {
+            RoundKind::Qualifiers => crate::osu::RoundKind::Qualifiers,
+            RoundKind::Groups => crate::osu::RoundKind::Groups,
+            RoundKind::Bracket => crate::osu::RoundKind::Bracket,
+        }
+    }
+}
+
+impl From<crate::osu::PickBanSequence> for PickBanSequence {
+    fn from(db_sequence: crate::osu::PickBanSequence) -> Self {
+        Self {
+            steps: db_sequence
+                .steps
+                .into_iter()
+                .map(|step| step.into())
+                .collect(),
+        }
+    }
+}
+
+impl From<PickBanSequence> for crate::osu::PickBanSequence {
+    fn from(sequence: PickBanSequence) -> Self {
+        Self {
+            steps: sequence.steps.into_iter().map(|step| step.into()).collect(),
+        }
+    }
+}
+
+impl From<crate::osu::PickBanStep> for PickBanStep {
+    fn from(db_step: crate::osu::PickBanStep) -> Self {
+        Self {
+            action: db_step.action.into(),
+            team: db_step.team.into(),
+        }
+    }
+}
+
+impl From<PickBanStep> for crate::osu::PickBanStep {
+    fn from(step: PickBanStep) -> Self {
+        Self {
+            action: step.action.into(),
+            team: step.team.into(),
+        }
+    }
+}
+
+impl From<crate::osu::SequenceTeam> for SequenceTeam {
+    fn from(db_team: crate::osu::SequenceTeam) -> Self {
+        match &db_team {
+            crate::osu::SequenceTeam::A => SequenceTeam::A,
+            crate::osu::SequenceTeam::B => SequenceTeam::B,
+        }
+    }
+}
+
+impl From<SequenceTeam> for crate::osu::SequenceTeam {
+    fn from(team: SequenceTeam) -> Self {
+        match &team {
+            SequenceTeam::A => crate::osu::SequenceTeam::A,
+            SequenceTeam::B => crate::osu::SequenceTeam::B,
+        }
+    }
+}
+
+//
+// Round DTO
+//
+
+impl From<CreateRoundRequest> for crate::osu::Round {
+    fn from(dto: CreateRoundRequest) -> Self {
+        Self {
+            id: None,
+            tournament_id: str_to_oid(&dto.tournament_id),
+            name: dto.name,
+            kind: dto.kind.into(),
+            order: dto.order,
+            best_of: dto.best_of,
+            ban_count: dto.ban_count,
+            pick_ban_sequence: dto.pick_ban_sequence.map(|sequence| sequence.into()),
+            scoring: dto.scoring.map(|rules| rules.into()),
+            start_date: dto.start_date,
+            end_date: dto.end_date,
+        }
+    }
+}
+
+impl From<UpdateRoundRequest> for crate::osu::PartialRound {
+    fn from(dto: UpdateRoundRequest) -> Self {
+        Self {
+            name: dto.name,
+            kind: dto.kind.map(|kind| kind.into()),
+            order: dto.order,
+            best_of: dto.best_of,
+            ban_count: dto.ban_count,
+            pick_ban_sequence: dto.pick_ban_sequence.map(|sequence| sequence.into()),
+            scoring: dto.scoring.map(|rules| rules.into()),
+            start_date: dto.start_date,
+            end_date: dto.end_date,
             ..Default::default()
         }
     }
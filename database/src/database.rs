@@ -1,4 +1,5 @@
 use bson::{doc, oid::ObjectId, Document};
+use chrono::Utc;
 use futures::StreamExt;
 use mongodb::{
     options::{CountOptions, FindOneOptions, FindOptions},
@@ -8,9 +9,14 @@ use mongodb::{
 use serde::{de::DeserializeOwned, Serialize};
 use tracing::info;
 
-use vocolo_internal::Result;
+use vocolo_internal::{Error, Permissions, Result};
 
-use crate::to_vocolo_error;
+use crate::{
+    osu::{
+        AbstractOsuGroup, AbstractOsuMappool, AbstractOsuMatchup, AbstractOsuTournament, Tournament,
+    },
+    to_vocolo_error,
+};
 
 pub async fn connect_to_db() -> Result<Database> {
     info!("Connecting to MongoDB");
@@ -24,6 +30,52 @@ pub async fn connect_to_db() -> Result<Database> {
     Ok(mongodb)
 }
 
+/// Brings the database, and the permissions derived from it, up to date with the current models
+pub async fn run_migrations(db: &Database, permissions: &Permissions) -> Result<()> {
+    db.ensure_osu_tournament_team_indexes().await?;
+    db.ensure_osu_matchup_indexes().await?;
+    db.ensure_osu_group_indexes().await?;
+    db.ensure_osu_mappool_indexes().await?;
+
+    let (inserted, matched) = db.migrate_osu_embedded_teams().await?;
+    if inserted > 0 {
+        info!(
+            "Moved {} embedded teams into their own collection",
+            inserted
+        );
+    }
+    if matched > 0 {
+        info!(
+            "Found {} embedded teams already moved by an earlier run",
+            matched
+        );
+    }
+
+    // Teams are only read from their collection, so serving before every team moved would
+    // hide the ones left behind
+    if db.check_osu_embedded_teams_remaining().await? {
+        return Err(Error::Internal(
+            "Some tournaments still have embedded teams, fix the warnings above and restart"
+                .to_string(),
+        ));
+    }
+
+    let stages = db.backfill_osu_tournament_stages(&Utc::now()).await?;
+    if stages > 0 {
+        info!(
+            "Derived the stage of {} tournaments from their registration",
+            stages
+        );
+    }
+
+    let grants = Tournament::backfill_staff_permissions(db, permissions).await?;
+    if grants > 0 {
+        info!("Granted {} missing staff permissions", grants);
+    }
+
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct Database(pub Client, pub String);
 
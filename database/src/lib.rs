@@ -1,5 +1,6 @@
 #![allow(async_fn_in_trait)]
 use bson::oid::ObjectId;
+use mongodb::error::{ErrorKind, WriteFailure};
 
 mod bridge;
 pub use bridge::*;
@@ -12,6 +13,20 @@ pub(crate) fn to_vocolo_error(source: mongodb::error::Error) -> vocolo_internal:
     vocolo_internal::Error::Database(source.to_string())
 }
 
+/// Whether the write was rejected by a unique index
+pub(crate) fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    const DUPLICATE_KEY: i32 = 11000;
+
+    match error.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(error)) => error.code == DUPLICATE_KEY,
+        ErrorKind::BulkWrite(failure) => failure
+            .write_errors
+            .as_ref()
+            .is_some_and(|errors| errors.iter().any(|error| error.code == DUPLICATE_KEY)),
+        _ => false,
+    }
+}
+
 pub const FAKE_OID: ObjectId = ObjectId::from_bytes([0; 12]);
 
 pub fn str_to_oid(id: &str) -> ObjectId {
@@ -3,7 +3,20 @@ use crate::database::Database;
 pub mod osu;
 
 pub trait AbstractDatabase:
-    Sync + Send + osu::AbstractOsuTournament + osu::AbstractOsuMatchup + osu::AbstractOsuMappool
+    Sync
+    + Send
+    + osu::AbstractOsuTournament
+    + osu::AbstractOsuMatchup
+    + osu::AbstractOsuMappool
+    + osu::AbstractOsuRound
+    + osu::AbstractOsuGroup
+    + osu::AbstractOsuQualifierLobby
+    + osu::AbstractOsuQualifierScore
+    + osu::AbstractOsuTeamInvite
+    + osu::AbstractOsuInviteCode
+    + osu::AbstractOsuSubstitution
+    + osu::AbstractOsuPlayer
+    + osu::AbstractOsuPlayerSnapshot
 {
 }
 
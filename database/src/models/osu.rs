@@ -1,5 +1,17 @@
 use serde::{Deserialize, Serialize};
 
+mod bracket;
+pub use bracket::*;
+mod eligibility;
+pub use eligibility::*;
+mod group;
+pub use group::*;
+mod group_ops;
+pub use group_ops::*;
+mod invite_code;
+pub use invite_code::*;
+mod invite_code_ops;
+pub use invite_code_ops::*;
 mod mappool;
 pub use mappool::*;
 mod mappool_ops;
@@ -8,10 +20,48 @@ mod matchup;
 pub use matchup::*;
 mod matchup_ops;
 pub use matchup_ops::*;
+mod pick_ban;
+pub use pick_ban::*;
+mod player;
+pub use player::*;
+mod player_ops;
+pub use player_ops::*;
+mod player_snapshot;
+pub use player_snapshot::*;
+mod player_snapshot_ops;
+pub use player_snapshot_ops::*;
+mod qualifier_lobby;
+pub use qualifier_lobby::*;
+mod qualifier_lobby_ops;
+pub use qualifier_lobby_ops::*;
+mod qualifier_score;
+pub use qualifier_score::*;
+mod qualifier_score_ops;
+pub use qualifier_score_ops::*;
+mod round;
+pub use round::*;
+mod round_ops;
+pub use round_ops::*;
+mod scoring;
+pub use scoring::*;
+mod seeding;
+pub use seeding::*;
+mod standings;
+pub use standings::*;
+mod substitution;
+pub use substitution::*;
+mod substitution_ops;
+pub use substitution_ops::*;
+mod team_invite;
+pub use team_invite::*;
+mod team_invite_ops;
+pub use team_invite_ops::*;
 mod tournament;
 pub use tournament::*;
 mod tournament_ops;
 pub use tournament_ops::*;
+mod tournament_stage;
+pub use tournament_stage::*;
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum GameMode {
@@ -0,0 +1,632 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{
+    AbstractOsuMatchup, BracketSide, Matchup, MatchupBracket, MatchupFeeder, MatchupOutcome,
+    MatchupSlot, MatchupTeam, Round, RoundKind, Tournament,
+};
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BracketFormat {
+    SingleElimination,
+    DoubleElimination,
+}
+
+#[derive(Debug, Clone)]
+pub struct BracketOptions {
+    pub format: BracketFormat,
+    pub grand_finals_reset: bool,
+    pub date: DateTime<Utc>,
+}
+
+/// Where a team in a planned matchup comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Seed(usize),
+    WinnerOf(usize),
+    LoserOf(usize),
+    Bye,
+}
+
+struct Node {
+    bracket: MatchupBracket,
+    red: Source,
+    blue: Source,
+}
+
+impl Matchup {
+    /// Generates an elimination bracket from `seeds`, best seed first, storing it unless `dry_run`.
+    /// Each depth of the bracket is played in the next bracket round of the tournament
+    pub async fn generate_bracket(
+        db: &Database,
+        tournament_id: &str,
+        seeds: Option<Vec<ObjectId>>,
+        options: &BracketOptions,
+        dry_run: bool,
+    ) -> Result<Vec<Matchup>> {
+        let tournament = Tournament::fetch(db, tournament_id).await?;
+        let tournament_oid = tournament.id.ok_or(Error::UnknownTournament)?;
+
+        if tournament.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let seeds = Tournament::resolve_seeds(db, tournament_id, seeds).await?;
+
+        if seeds.len() < 2 {
+            return Err(Error::NotEnoughTeams);
+        }
+
+        if db
+            .check_osu_tournament_bracket_exists(&tournament_oid)
+            .await?
+        {
+            return Err(Error::AlreadyExists(
+                "bracket already generated".to_string(),
+            ));
+        }
+
+        let rounds = Round::list(db, &tournament_oid)
+            .await?
+            .into_iter()
+            .filter(|round| round.kind == RoundKind::Bracket)
+            .collect::<Vec<_>>();
+
+        let depth = bracket_depth(seeds.len(), options);
+        if rounds.len() < depth {
+            return Err(Error::NotEnoughBracketRounds(depth));
+        }
+
+        let matchups = plan_bracket(&tournament_oid, &seeds, &rounds, options);
+
+        // A concurrent request may have stored its bracket since the check above
+        if !dry_run && !db.insert_osu_bracket(&matchups).await? {
+            return Err(Error::AlreadyExists(
+                "bracket already generated".to_string(),
+            ));
+        }
+
+        Ok(matchups)
+    }
+}
+
+/// Rounds needed to play the bracket, a week per winners round plus one for grand finals
+pub fn bracket_depth(teams: usize, options: &BracketOptions) -> usize {
+    match options.format {
+        BracketFormat::SingleElimination => winners_rounds(teams),
+        BracketFormat::DoubleElimination => winners_rounds(teams) + 1,
+    }
+}
+
+fn winners_rounds(teams: usize) -> usize {
+    teams.next_power_of_two().max(2).trailing_zeros() as usize
+}
+
+/// Depth at which the matchup is played. Losers rounds share the depth of the winners round
+/// their dropping teams come from, the survivor rounds in between go with the later one
+fn matchup_depth(bracket: &MatchupBracket, winners_rounds: usize) -> usize {
+    let round = bracket.round as usize;
+
+    match bracket.side {
+        BracketSide::Winners => round - 1,
+        BracketSide::Losers => round.div_ceil(2),
+        BracketSide::GrandFinals => winners_rounds,
+    }
+}
+
+/// Lays out every matchup of the bracket, resolving byes so that no matchup is left one-sided.
+/// Matchups are placed in `rounds` by depth, left without a round past its end
+pub fn plan_bracket(
+    tournament_id: &ObjectId,
+    seeds: &[ObjectId],
+    rounds: &[Round],
+    options: &BracketOptions,
+) -> Vec<Matchup> {
+    let winners_rounds = winners_rounds(seeds.len());
+    let nodes = resolve_byes(layout_bracket(seeds.len(), options));
+
+    let ids = nodes
+        .iter()
+        .map(|node| node.as_ref().map(|_| ObjectId::new()))
+        .collect::<Vec<_>>();
+
+    let mut matchups = nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, node)| {
+            let node = node.as_ref()?;
+            let team = |source: Source| match source {
+                Source::Seed(seed) => Some(seeds[seed]),
+                _ => None,
+            };
+            let feeder = |source: Source| match source {
+                Source::WinnerOf(m) => Some(MatchupFeeder {
+                    matchup_id: ids[m]?,
+                    outcome: MatchupOutcome::Winner,
+                }),
+                Source::LoserOf(m) => Some(MatchupFeeder {
+                    matchup_id: ids[m]?,
+                    outcome: MatchupOutcome::Loser,
+                }),
+                _ => None,
+            };
+            let round = rounds.get(matchup_depth(&node.bracket, winners_rounds));
+            let date = match round {
+                Some(round) if !round.is_scheduled_at(&options.date) => round.start_date,
+                _ => options.date,
+            };
+
+            Some((
+                i,
+                Matchup {
+                    id: ids[i],
+                    tournament_id: *tournament_id,
+                    round_id: round.and_then(|round| round.id),
+                    date,
+                    team_red: team(node.red),
+                    team_blue: team(node.blue),
+                    first_team: None,
+                    maps: vec![],
+                    bracket: Some(node.bracket.clone()),
+                    group: None,
+                    winner_to: None,
+                    loser_to: None,
+                    red_from: feeder(node.red),
+                    blue_from: feeder(node.blue),
+                    result: None,
+                },
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    for i in 0..matchups.len() {
+        let node_index = matchups[i].0;
+        let node = nodes[node_index].as_ref().unwrap();
+
+        for (source, team) in [(node.red, MatchupTeam::Red), (node.blue, MatchupTeam::Blue)] {
+            let (feeder, is_winner) = match source {
+                Source::WinnerOf(feeder) => (feeder, true),
+                Source::LoserOf(feeder) => (feeder, false),
+                _ => continue,
+            };
+
+            let slot = MatchupSlot {
+                matchup_id: ids[node_index].unwrap(),
+                team,
+            };
+
+            if let Some((_, feeder)) = matchups.iter_mut().find(|(i, _)| *i == feeder) {
+                if is_winner {
+                    feeder.winner_to = Some(slot);
+                } else {
+                    feeder.loser_to = Some(slot);
+                }
+            }
+        }
+    }
+
+    matchups.into_iter().map(|(_, matchup)| matchup).collect()
+}
+
+/// Seed order of the first round for a bracket of `size` slots, e.g. 1v8, 4v5, 2v7, 3v6
+fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+
+    while order.len() < size {
+        let len = order.len() * 2;
+        order = order.iter().flat_map(|&s| [s, len - 1 - s]).collect();
+    }
+
+    order
+}
+
+fn layout_bracket(teams: usize, options: &BracketOptions) -> Vec<Node> {
+    let size = teams.next_power_of_two().max(2);
+    let rounds = size.trailing_zeros() as usize;
+
+    let mut nodes = vec![];
+    let push = |nodes: &mut Vec<Node>, side, round: usize, position: usize, red, blue| {
+        nodes.push(Node {
+            bracket: MatchupBracket {
+                side,
+                round: round as i32,
+                position: position as i32,
+            },
+            red,
+            blue,
+        });
+        nodes.len() - 1
+    };
+
+    let seed = |s: usize| {
+        if s < teams {
+            Source::Seed(s)
+        } else {
+            Source::Bye
+        }
+    };
+
+    // Winners bracket
+    let order = seed_order(size);
+    let mut winners: Vec<Vec<usize>> = vec![(0..size / 2)
+        .map(|i| {
+            push(
+                &mut nodes,
+                BracketSide::Winners,
+                1,
+                i + 1,
+                seed(order[2 * i]),
+                seed(order[2 * i + 1]),
+            )
+        })
+        .collect()];
+
+    for round in 2..=rounds {
+        let previous = winners.last().unwrap().clone();
+        let current = (0..previous.len() / 2)
+            .map(|i| {
+                push(
+                    &mut nodes,
+                    BracketSide::Winners,
+                    round,
+                    i + 1,
+                    Source::WinnerOf(previous[2 * i]),
+                    Source::WinnerOf(previous[2 * i + 1]),
+                )
+            })
+            .collect();
+        winners.push(current);
+    }
+
+    let winners_final = winners[rounds - 1][0];
+
+    if options.format == BracketFormat::SingleElimination {
+        return nodes;
+    }
+
+    // Losers bracket, alternating between a round among its own survivors and one
+    // where they face the teams dropping down from the winners bracket
+    let losers_final = if rounds == 1 {
+        None
+    } else {
+        let first = &winners[0];
+        let mut previous = (0..first.len() / 2)
+            .map(|i| {
+                push(
+                    &mut nodes,
+                    BracketSide::Losers,
+                    1,
+                    i + 1,
+                    Source::LoserOf(first[2 * i]),
+                    Source::LoserOf(first[2 * i + 1]),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (j, dropping) in winners.iter().enumerate().skip(1) {
+            let reversed = j % 2 == 1;
+
+            previous = (0..previous.len())
+                .map(|i| {
+                    let drop = if reversed { dropping.len() - 1 - i } else { i };
+                    push(
+                        &mut nodes,
+                        BracketSide::Losers,
+                        2 * j,
+                        i + 1,
+                        Source::WinnerOf(previous[i]),
+                        Source::LoserOf(dropping[drop]),
+                    )
+                })
+                .collect();
+
+            if j < rounds - 1 {
+                previous = (0..previous.len() / 2)
+                    .map(|i| {
+                        push(
+                            &mut nodes,
+                            BracketSide::Losers,
+                            2 * j + 1,
+                            i + 1,
+                            Source::WinnerOf(previous[2 * i]),
+                            Source::WinnerOf(previous[2 * i + 1]),
+                        )
+                    })
+                    .collect();
+            }
+        }
+
+        Some(previous[0])
+    };
+
+    let grand_finals = push(
+        &mut nodes,
+        BracketSide::GrandFinals,
+        1,
+        1,
+        Source::WinnerOf(winners_final),
+        match losers_final {
+            Some(losers_final) => Source::WinnerOf(losers_final),
+            None => Source::LoserOf(winners_final),
+        },
+    );
+
+    if options.grand_finals_reset {
+        push(
+            &mut nodes,
+            BracketSide::GrandFinals,
+            2,
+            1,
+            Source::WinnerOf(grand_finals),
+            Source::LoserOf(grand_finals),
+        );
+    }
+
+    nodes
+}
+
+/// Drops matchups missing a team, forwarding the present team (if any) to where the winner goes
+fn resolve_byes(nodes: Vec<Node>) -> Vec<Option<Node>> {
+    let mut advanced: Vec<Option<(Source, Source)>> = vec![None; nodes.len()];
+    let mut resolved = Vec::with_capacity(nodes.len());
+
+    for (i, mut node) in nodes.into_iter().enumerate() {
+        let forward = |source: Source| match source {
+            Source::WinnerOf(m) => advanced[m].map_or(source, |(winner, _)| winner),
+            Source::LoserOf(m) => advanced[m].map_or(source, |(_, loser)| loser),
+            _ => source,
+        };
+
+        node.red = forward(node.red);
+        node.blue = forward(node.blue);
+
+        match (node.red, node.blue) {
+            (Source::Bye, other) | (other, Source::Bye) => {
+                advanced[i] = Some((other, Source::Bye));
+                resolved.push(None);
+            }
+            _ => resolved.push(Some(node)),
+        }
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    fn options(format: BracketFormat, grand_finals_reset: bool) -> BracketOptions {
+        BracketOptions {
+            format,
+            grand_finals_reset,
+            date: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+        }
+    }
+
+    fn seeds(count: usize) -> Vec<ObjectId> {
+        (0..count).map(|_| ObjectId::new()).collect()
+    }
+
+    fn find(matchups: &[Matchup], side: BracketSide, round: i32, position: i32) -> &Matchup {
+        matchups
+            .iter()
+            .find(|matchup| {
+                matchup.bracket
+                    == Some(MatchupBracket {
+                        side,
+                        round,
+                        position,
+                    })
+            })
+            .unwrap()
+    }
+
+    fn slot(matchup: &Matchup, team: MatchupTeam) -> Option<MatchupSlot> {
+        Some(MatchupSlot {
+            matchup_id: matchup.id.unwrap(),
+            team,
+        })
+    }
+
+    fn round(tournament_id: ObjectId, order: i32, start_date: DateTime<Utc>) -> Round {
+        Round {
+            id: Some(ObjectId::new()),
+            tournament_id,
+            name: format!("Round {order}"),
+            kind: RoundKind::Bracket,
+            order,
+            best_of: 9,
+            ban_count: 2,
+            pick_ban_sequence: None,
+            scoring: None,
+            start_date,
+            end_date: start_date + Duration::days(2),
+        }
+    }
+
+    #[test]
+    fn seed_order_pairs_best_with_worst() {
+        assert_eq!(seed_order(2), vec![0, 1]);
+        assert_eq!(seed_order(4), vec![0, 3, 1, 2]);
+        assert_eq!(seed_order(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+    }
+
+    #[test]
+    fn single_elimination_of_four() {
+        let seeds = seeds(4);
+        let options = options(BracketFormat::SingleElimination, false);
+        let matchups = plan_bracket(&ObjectId::new(), &seeds, &[], &options);
+
+        assert_eq!(matchups.len(), 3);
+
+        let first = find(&matchups, BracketSide::Winners, 1, 1);
+        let second = find(&matchups, BracketSide::Winners, 1, 2);
+        let finals = find(&matchups, BracketSide::Winners, 2, 1);
+
+        assert_eq!(
+            (first.team_red, first.team_blue),
+            (Some(seeds[0]), Some(seeds[3]))
+        );
+        assert_eq!(
+            (second.team_red, second.team_blue),
+            (Some(seeds[1]), Some(seeds[2]))
+        );
+
+        assert_eq!(first.winner_to, slot(finals, MatchupTeam::Red));
+        assert_eq!(second.winner_to, slot(finals, MatchupTeam::Blue));
+        assert_eq!(first.loser_to, None);
+        assert_eq!(finals.winner_to, None);
+    }
+
+    #[test]
+    fn bye_sends_top_seed_straight_through() {
+        let seeds = seeds(3);
+        let options = options(BracketFormat::SingleElimination, false);
+        let matchups = plan_bracket(&ObjectId::new(), &seeds, &[], &options);
+
+        assert_eq!(matchups.len(), 2);
+
+        let played = find(&matchups, BracketSide::Winners, 1, 2);
+        let finals = find(&matchups, BracketSide::Winners, 2, 1);
+
+        assert_eq!(
+            (played.team_red, played.team_blue),
+            (Some(seeds[1]), Some(seeds[2]))
+        );
+        assert_eq!(finals.team_red, Some(seeds[0]));
+        assert_eq!(played.winner_to, slot(finals, MatchupTeam::Blue));
+    }
+
+    #[test]
+    fn double_elimination_of_four() {
+        let matchups = plan_bracket(
+            &ObjectId::new(),
+            &seeds(4),
+            &[],
+            &options(BracketFormat::DoubleElimination, true),
+        );
+
+        // 3 winners, 2 losers, grand finals and its reset
+        assert_eq!(matchups.len(), 7);
+
+        let winners_first = find(&matchups, BracketSide::Winners, 1, 1);
+        let winners_second = find(&matchups, BracketSide::Winners, 1, 2);
+        let winners_final = find(&matchups, BracketSide::Winners, 2, 1);
+        let losers_first = find(&matchups, BracketSide::Losers, 1, 1);
+        let losers_final = find(&matchups, BracketSide::Losers, 2, 1);
+        let grand_finals = find(&matchups, BracketSide::GrandFinals, 1, 1);
+        let reset = find(&matchups, BracketSide::GrandFinals, 2, 1);
+
+        assert_eq!(winners_first.loser_to, slot(losers_first, MatchupTeam::Red));
+        assert_eq!(
+            winners_second.loser_to,
+            slot(losers_first, MatchupTeam::Blue)
+        );
+        assert_eq!(losers_first.winner_to, slot(losers_final, MatchupTeam::Red));
+        assert_eq!(
+            winners_final.loser_to,
+            slot(losers_final, MatchupTeam::Blue)
+        );
+        assert_eq!(
+            losers_final.winner_to,
+            slot(grand_finals, MatchupTeam::Blue)
+        );
+        assert_eq!(grand_finals.winner_to, slot(reset, MatchupTeam::Red));
+        assert_eq!(grand_finals.loser_to, slot(reset, MatchupTeam::Blue));
+    }
+
+    #[test]
+    fn bye_loser_skips_losers_round() {
+        let seeds = seeds(3);
+        let matchups = plan_bracket(
+            &ObjectId::new(),
+            &seeds,
+            &[],
+            &options(BracketFormat::DoubleElimination, false),
+        );
+
+        // The first losers round would face the loser of a bye, so it is dropped
+        assert_eq!(matchups.len(), 4);
+        assert!(!matchups.iter().any(|matchup| matchup.bracket
+            == Some(MatchupBracket {
+                side: BracketSide::Losers,
+                round: 1,
+                position: 1,
+            })));
+
+        let played = find(&matchups, BracketSide::Winners, 1, 2);
+        let losers_final = find(&matchups, BracketSide::Losers, 2, 1);
+
+        assert_eq!(played.loser_to, slot(losers_final, MatchupTeam::Red));
+    }
+
+    #[test]
+    fn every_slot_is_filled() {
+        for teams in 2..=17 {
+            for format in [
+                BracketFormat::SingleElimination,
+                BracketFormat::DoubleElimination,
+            ] {
+                let matchups =
+                    plan_bracket(&ObjectId::new(), &seeds(teams), &[], &options(format, true));
+
+                for matchup in &matchups {
+                    for (team_id, team) in [
+                        (matchup.team_red, MatchupTeam::Red),
+                        (matchup.team_blue, MatchupTeam::Blue),
+                    ] {
+                        let slot = slot(matchup, team);
+                        let feeders = matchups
+                            .iter()
+                            .filter(|feeder| feeder.winner_to == slot || feeder.loser_to == slot)
+                            .count();
+
+                        assert_eq!(feeders, if team_id.is_some() { 0 } else { 1 });
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn depth_counts_grand_finals_in_double_elimination() {
+        let single = options(BracketFormat::SingleElimination, false);
+        let double = options(BracketFormat::DoubleElimination, false);
+
+        assert_eq!(bracket_depth(2, &single), 1);
+        assert_eq!(bracket_depth(5, &single), 3);
+        assert_eq!(bracket_depth(8, &double), 4);
+    }
+
+    #[test]
+    fn matchups_are_placed_in_rounds_by_depth() {
+        let tournament_id = ObjectId::new();
+        let options = options(BracketFormat::DoubleElimination, false);
+        let rounds = (0..3)
+            .map(|i| round(tournament_id, i, options.date + Duration::weeks(i as i64)))
+            .collect::<Vec<_>>();
+
+        let matchups = plan_bracket(&tournament_id, &seeds(4), &rounds, &options);
+        let round_of = |side, round, position| find(&matchups, side, round, position).round_id;
+
+        assert_eq!(round_of(BracketSide::Winners, 1, 1), rounds[0].id);
+        assert_eq!(round_of(BracketSide::Winners, 2, 1), rounds[1].id);
+        assert_eq!(round_of(BracketSide::Losers, 1, 1), rounds[1].id);
+        assert_eq!(round_of(BracketSide::Losers, 2, 1), rounds[1].id);
+        assert_eq!(round_of(BracketSide::GrandFinals, 1, 1), rounds[2].id);
+
+        // Rounds not scheduled at the requested date start their matchups on their first day
+        let first = find(&matchups, BracketSide::Winners, 1, 1);
+        let finals = find(&matchups, BracketSide::GrandFinals, 1, 1);
+        assert_eq!(first.date, options.date);
+        assert_eq!(finals.date, rounds[2].start_date);
+    }
+}
@@ -0,0 +1,281 @@
+use std::fmt;
+
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use super::{GameMode, Player};
+
+/// Restrictions players must meet to register, checked against their cached profile
+#[derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct EligibilityRules {
+    /// Best rank allowed, e.g. 1000 in a 1k-5k tournament
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_rank: Option<i32>,
+    /// Worst rank allowed, e.g. 5000 in a 1k-5k tournament
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_rank: Option<i32>,
+    /// Compare the badge weighted seeding rank against the range instead of the global rank
+    #[serde(skip_serializing_if = "vocolo_models::if_false", default)]
+    pub badge_weighted: bool,
+    /// Allowed countries, any country when empty
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub countries: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub enum IneligibilityReason {
+    MissingProfile,
+    Unranked,
+    RankAboveRange { rank: i32, min_rank: i32 },
+    RankBelowRange { rank: i32, max_rank: i32 },
+    CountryNotAllowed { country: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PlayerEligibility {
+    pub player_id: i32,
+    pub reasons: Vec<IneligibilityReason>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct TeamScreening {
+    pub team_id: ObjectId,
+    pub players: Vec<PlayerEligibility>,
+}
+
+impl EligibilityRules {
+    pub fn is_empty(&self) -> bool {
+        self.min_rank.is_none() && self.max_rank.is_none() && self.countries.is_empty()
+    }
+
+    pub fn evaluate(
+        &self,
+        player_id: i32,
+        player: Option<&Player>,
+        mode: &GameMode,
+    ) -> PlayerEligibility {
+        let mut reasons = vec![];
+
+        match player {
+            None => reasons.push(IneligibilityReason::MissingProfile),
+            Some(player) => {
+                if self.min_rank.is_some() || self.max_rank.is_some() {
+                    let rank = if self.badge_weighted {
+                        player.badge_weighted_rank(mode)
+                    } else {
+                        player.global_rank(mode)
+                    };
+
+                    match rank {
+                        None => reasons.push(IneligibilityReason::Unranked),
+                        Some(rank) => {
+                            if let Some(min_rank) = self.min_rank.filter(|min| rank < *min) {
+                                reasons
+                                    .push(IneligibilityReason::RankAboveRange { rank, min_rank });
+                            }
+
+                            if let Some(max_rank) = self.max_rank.filter(|max| rank > *max) {
+                                reasons
+                                    .push(IneligibilityReason::RankBelowRange { rank, max_rank });
+                            }
+                        }
+                    }
+                }
+
+                if !self.countries.is_empty()
+                    && !self
+                        .countries
+                        .iter()
+                        .any(|country| country.eq_ignore_ascii_case(&player.country))
+                {
+                    reasons.push(IneligibilityReason::CountryNotAllowed {
+                        country: player.country.clone(),
+                    });
+                }
+            }
+        }
+
+        PlayerEligibility { player_id, reasons }
+    }
+}
+
+impl PlayerEligibility {
+    pub fn is_eligible(&self) -> bool {
+        self.reasons.is_empty()
+    }
+}
+
+impl fmt::Display for IneligibilityReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IneligibilityReason::MissingProfile => write!(f, "no osu! profile on record"),
+            IneligibilityReason::Unranked => write!(f, "unranked"),
+            IneligibilityReason::RankAboveRange { rank, min_rank } => {
+                write!(f, "rank #{rank} is better than #{min_rank}")
+            }
+            IneligibilityReason::RankBelowRange { rank, max_rank } => {
+                write!(f, "rank #{rank} is worse than #{max_rank}")
+            }
+            IneligibilityReason::CountryNotAllowed { country } => {
+                write!(f, "country {country} is not allowed")
+            }
+        }
+    }
+}
+
+impl fmt::Display for PlayerEligibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.player_id)?;
+
+        for (i, reason) in self.reasons.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{reason}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::osu::PlayerStats;
+
+    fn player(rank: Option<i32>, badges: i32, country: &str) -> Player {
+        Player {
+            id: 1,
+            username: "player".to_string(),
+            country: country.to_string(),
+            badges,
+            stats: vec![PlayerStats {
+                mode: GameMode::Standard,
+                global_rank: rank,
+                country_rank: None,
+                pp: 0.0,
+            }],
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn range(min_rank: i32, max_rank: i32) -> EligibilityRules {
+        EligibilityRules {
+            min_rank: Some(min_rank),
+            max_rank: Some(max_rank),
+            ..Default::default()
+        }
+    }
+
+    fn reasons(rules: &EligibilityRules, player: Option<&Player>) -> Vec<IneligibilityReason> {
+        rules.evaluate(1, player, &GameMode::Standard).reasons
+    }
+
+    #[test]
+    fn rank_within_range_is_eligible() {
+        let rules = range(1000, 5000);
+
+        assert!(reasons(&rules, Some(&player(Some(1000), 0, "FR"))).is_empty());
+        assert!(reasons(&rules, Some(&player(Some(5000), 0, "FR"))).is_empty());
+    }
+
+    #[test]
+    fn rank_outside_range() {
+        let rules = range(1000, 5000);
+
+        assert_eq!(
+            reasons(&rules, Some(&player(Some(999), 0, "FR"))),
+            vec![IneligibilityReason::RankAboveRange {
+                rank: 999,
+                min_rank: 1000
+            }]
+        );
+        assert_eq!(
+            reasons(&rules, Some(&player(Some(5001), 0, "FR"))),
+            vec![IneligibilityReason::RankBelowRange {
+                rank: 5001,
+                max_rank: 5000
+            }]
+        );
+    }
+
+    #[test]
+    fn missing_profile_and_rank() {
+        let rules = range(1000, 5000);
+
+        assert_eq!(
+            reasons(&rules, None),
+            vec![IneligibilityReason::MissingProfile]
+        );
+        assert_eq!(
+            reasons(&rules, Some(&player(None, 0, "FR"))),
+            vec![IneligibilityReason::Unranked]
+        );
+        // Players from the wrong mode have no rank either
+        assert_eq!(
+            rules
+                .evaluate(1, Some(&player(Some(2000), 0, "FR")), &GameMode::Mania)
+                .reasons,
+            vec![IneligibilityReason::Unranked]
+        );
+    }
+
+    #[test]
+    fn unranked_players_pass_without_a_range() {
+        let rules = EligibilityRules::default();
+
+        assert!(rules.is_empty());
+        assert!(reasons(&rules, Some(&player(None, 0, "FR"))).is_empty());
+    }
+
+    #[test]
+    fn badges_improve_the_weighted_rank() {
+        let unweighted = range(1000, 8000);
+        let weighted = EligibilityRules {
+            badge_weighted: true,
+            ..range(1000, 8000)
+        };
+        let player = player(Some(10000), 3, "FR");
+
+        assert!(!unweighted
+            .evaluate(1, Some(&player), &GameMode::Standard)
+            .is_eligible());
+        assert!(weighted
+            .evaluate(1, Some(&player), &GameMode::Standard)
+            .is_eligible());
+    }
+
+    #[test]
+    fn countries_match_case_insensitively() {
+        let rules = EligibilityRules {
+            countries: vec!["fr".to_string(), "BE".to_string()],
+            ..Default::default()
+        };
+
+        assert!(reasons(&rules, Some(&player(Some(1), 0, "FR"))).is_empty());
+        assert_eq!(
+            reasons(&rules, Some(&player(Some(1), 0, "DE"))),
+            vec![IneligibilityReason::CountryNotAllowed {
+                country: "DE".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn every_reason_is_reported() {
+        let rules = EligibilityRules {
+            countries: vec!["FR".to_string()],
+            ..range(1000, 5000)
+        };
+
+        let eligibility = rules.evaluate(7, Some(&player(Some(10), 0, "DE")), &GameMode::Standard);
+
+        assert_eq!(eligibility.reasons.len(), 2);
+        assert_eq!(
+            eligibility.to_string(),
+            "7: rank #10 is better than #1000, country DE is not allowed"
+        );
+    }
+}
@@ -0,0 +1,485 @@
+use std::collections::HashSet;
+
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{
+    compute_standings, AbstractOsuGroup, AbstractOsuMatchup, Matchup, MatchupGroup, MatchupResult,
+    MatchupResultKind, MatchupTeam, Round, RoundKind, Standing, Tiebreaker, Tournament,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct Group {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+    pub round_id: ObjectId,
+
+    pub name: String,
+    pub format: GroupFormat,
+    /// Teams of the group, best seed first
+    pub teams: Vec<ObjectId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swiss_rounds: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GroupFormat {
+    RoundRobin,
+    Swiss,
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupOptions {
+    pub format: GroupFormat,
+    pub group_count: usize,
+    /// Swiss rounds to play, defaults to enough rounds to leave a single unbeaten team
+    pub swiss_rounds: Option<i32>,
+}
+
+impl Group {
+    pub async fn list(db: &Database, round_id: &ObjectId) -> Result<Vec<Group>> {
+        let groups = db.fetch_osu_round_groups(round_id).await?;
+        Ok(groups)
+    }
+
+    /// Snake seeds the teams into groups of the round and generates their first matchups,
+    /// storing them unless `dry_run`
+    pub async fn generate(
+        db: &Database,
+        round_id: &str,
+        seeds: Option<Vec<ObjectId>>,
+        options: &GroupOptions,
+        dry_run: bool,
+    ) -> Result<(Vec<Group>, Vec<Matchup>)> {
+        let round = Round::fetch(db, round_id).await?;
+        let round_oid = round.id.ok_or(Error::UnknownRound)?;
+
+        if round.kind != RoundKind::Groups {
+            return Err(Error::InvalidRoundKind);
+        }
+
+        let tournament_id = round.tournament_id.to_hex();
+        let tournament = Tournament::fetch(db, &tournament_id).await?;
+
+        if tournament.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let seeds = Tournament::resolve_seeds(db, &tournament_id, seeds).await?;
+
+        if options.group_count == 0 || seeds.len() < options.group_count * 2 {
+            return Err(Error::NotEnoughTeams);
+        }
+
+        if !db.fetch_osu_round_groups(&round_oid).await?.is_empty() {
+            return Err(Error::AlreadyExists("groups already generated".to_string()));
+        }
+
+        let groups = snake_groups(&seeds, options.group_count)
+            .into_iter()
+            .enumerate()
+            .map(|(i, teams)| Group {
+                id: Some(ObjectId::new()),
+                tournament_id: round.tournament_id,
+                round_id: round_oid,
+                name: group_name(i),
+                format: options.format,
+                swiss_rounds: match options.format {
+                    GroupFormat::RoundRobin => None,
+                    GroupFormat::Swiss => Some(
+                        options
+                            .swiss_rounds
+                            .unwrap_or(teams.len().next_power_of_two().trailing_zeros() as i32),
+                    ),
+                },
+                teams,
+            })
+            .collect::<Vec<_>>();
+
+        if groups.iter().any(|group| {
+            group
+                .swiss_rounds
+                .is_some_and(|rounds| rounds < 1 || rounds as usize >= group.teams.len())
+        }) {
+            return Err(Error::InvalidGroupOptions);
+        }
+
+        let matchups = groups
+            .iter()
+            .flat_map(|group| match group.format {
+                GroupFormat::RoundRobin => plan_round_robin(group, &round.start_date),
+                GroupFormat::Swiss => plan_swiss_round(group, &[], 1, &round.start_date),
+            })
+            .collect::<Vec<_>>();
+
+        if !dry_run {
+            if !db.insert_osu_groups(&groups).await? {
+                return Err(Error::AlreadyExists("groups already generated".to_string()));
+            }
+
+            // Groups without their matchups would keep the round from generating them again
+            let stored = db.insert_osu_matchups(&matchups).await;
+            if !matches!(stored, Ok(true)) {
+                let matchup_ids = matchups
+                    .iter()
+                    .filter_map(|matchup| matchup.id)
+                    .collect::<Vec<_>>();
+                let group_ids = groups
+                    .iter()
+                    .filter_map(|group| group.id)
+                    .collect::<Vec<_>>();
+
+                db.delete_osu_matchups(&matchup_ids).await?;
+                db.delete_osu_groups(&group_ids).await?;
+
+                stored?;
+                return Err(Error::AlreadyExists("groups already generated".to_string()));
+            }
+        }
+
+        Ok((groups, matchups))
+    }
+
+    /// Pairs the next round of every Swiss group of the round once its current round is decided
+    pub async fn next_swiss_round(db: &Database, round_id: &str) -> Result<Vec<Matchup>> {
+        let round = Round::fetch(db, round_id).await?;
+        let round_oid = round.id.ok_or(Error::UnknownRound)?;
+
+        let mut matchups = vec![];
+
+        for group in Self::list(db, &round_oid).await? {
+            let Some(swiss_rounds) = group.swiss_rounds else {
+                continue;
+            };
+
+            let played = group.matchups(db).await?;
+            if played.iter().any(|matchup| matchup.result.is_none()) {
+                return Err(Error::PendingMatches);
+            }
+
+            let current = played
+                .iter()
+                .filter_map(|matchup| matchup.group.as_ref().map(|group| group.round))
+                .max()
+                .unwrap_or(0);
+
+            if current < swiss_rounds {
+                matchups.extend(plan_swiss_round(
+                    &group,
+                    &played,
+                    current + 1,
+                    &round.start_date,
+                ));
+            }
+        }
+
+        if !db.insert_osu_matchups(&matchups).await? {
+            return Err(Error::AlreadyExists(
+                "swiss round already paired".to_string(),
+            ));
+        }
+
+        Ok(matchups)
+    }
+
+    pub async fn matchups(&self, db: &Database) -> Result<Vec<Matchup>> {
+        let matchups = db
+            .fetch_osu_group_matchups(&self.id.ok_or(Error::UnknownGroup)?)
+            .await?;
+        Ok(matchups)
+    }
+
+    pub async fn standings(
+        &self,
+        db: &Database,
+        tiebreakers: &[Tiebreaker],
+    ) -> Result<Vec<Standing>> {
+        let matchups = self.matchups(db).await?;
+        Ok(compute_standings(&self.teams, &matchups, tiebreakers))
+    }
+}
+
+fn group_name(index: usize) -> String {
+    if index < 26 {
+        ((b'A' + index as u8) as char).to_string()
+    } else {
+        (index + 1).to_string()
+    }
+}
+
+/// Deals seeds across groups, reversing direction every pass, e.g. A B C C B A A B C
+fn snake_groups(seeds: &[ObjectId], group_count: usize) -> Vec<Vec<ObjectId>> {
+    let mut groups = vec![vec![]; group_count];
+
+    for (i, seed) in seeds.iter().enumerate() {
+        let pass = i / group_count;
+        let position = i % group_count;
+        let group = if pass % 2 == 0 {
+            position
+        } else {
+            group_count - 1 - position
+        };
+
+        groups[group].push(*seed);
+    }
+
+    groups
+}
+
+fn group_matchup(
+    group: &Group,
+    round: i32,
+    date: &DateTime<Utc>,
+    team_red: ObjectId,
+    team_blue: Option<ObjectId>,
+) -> Matchup {
+    Matchup {
+        id: Some(ObjectId::new()),
+        tournament_id: group.tournament_id,
+        round_id: Some(group.round_id),
+        date: *date,
+        team_red: Some(team_red),
+        team_blue,
+        first_team: None,
+        maps: vec![],
+        bracket: None,
+        group: Some(MatchupGroup {
+            group_id: group.id.unwrap_or_default(),
+            round,
+        }),
+        winner_to: None,
+        loser_to: None,
+        red_from: None,
+        blue_from: None,
+        // A team without an opponent gets the win by walkover
+        result: team_blue.is_none().then_some(MatchupResult {
+            winner: MatchupTeam::Red,
+            kind: MatchupResultKind::Walkover,
+            team_red_score: 0,
+            team_blue_score: 0,
+        }),
+    }
+}
+
+/// Every team plays every other team once, scheduled with the circle method
+fn plan_round_robin(group: &Group, date: &DateTime<Utc>) -> Vec<Matchup> {
+    let mut slots = group.teams.iter().copied().map(Some).collect::<Vec<_>>();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+
+    let n = slots.len();
+    let mut matchups = vec![];
+
+    for round in 0..n - 1 {
+        for i in 0..n / 2 {
+            let (mut red, mut blue) = (slots[i], slots[n - 1 - i]);
+            if i == 0 && round % 2 == 1 {
+                (red, blue) = (blue, red);
+            }
+
+            if let (Some(red), Some(blue)) = (red, blue) {
+                matchups.push(group_matchup(
+                    group,
+                    round as i32 + 1,
+                    date,
+                    red,
+                    Some(blue),
+                ));
+            }
+        }
+
+        // Keep the first slot fixed and rotate the others
+        let last = slots.pop().unwrap();
+        slots.insert(1, last);
+    }
+
+    matchups
+}
+
+/// Pairs teams of similar standing that have not met yet, the lowest team without one
+/// getting a bye when the count is odd
+fn plan_swiss_round(
+    group: &Group,
+    played: &[Matchup],
+    round: i32,
+    date: &DateTime<Utc>,
+) -> Vec<Matchup> {
+    let mut ranking = if played.is_empty() {
+        // First round pairs the top half against the bottom half
+        let half = group.teams.len() / 2;
+        let (top, bottom) = group.teams.split_at(half);
+        let mut teams = vec![];
+        for i in 0..half {
+            teams.push(top[i]);
+            teams.push(bottom[i]);
+        }
+        teams.extend_from_slice(&bottom[half..]);
+        teams
+    } else {
+        compute_standings(
+            &group.teams,
+            played,
+            &[Tiebreaker::MatchWins, Tiebreaker::MapDifferential],
+        )
+        .into_iter()
+        .map(|standing| standing.team_id)
+        .collect()
+    };
+
+    let met = played
+        .iter()
+        .filter_map(|matchup| match (matchup.team_red, matchup.team_blue) {
+            (Some(red), Some(blue)) => Some((red, blue)),
+            _ => None,
+        })
+        .flat_map(|(red, blue)| [(red, blue), (blue, red)])
+        .collect::<HashSet<_>>();
+
+    let mut matchups = vec![];
+
+    if ranking.len() % 2 == 1 {
+        let had_bye = played
+            .iter()
+            .filter(|matchup| matchup.team_blue.is_none())
+            .filter_map(|matchup| matchup.team_red)
+            .collect::<HashSet<_>>();
+
+        let bye = ranking
+            .iter()
+            .rposition(|team| !had_bye.contains(team))
+            .unwrap_or(ranking.len() - 1);
+
+        let team = ranking.remove(bye);
+        matchups.push(group_matchup(group, round, date, team, None));
+    }
+
+    let pairs = pair_unmet(&ranking, &met)
+        .unwrap_or_else(|| ranking.chunks(2).map(|pair| (pair[0], pair[1])).collect());
+
+    matchups.extend(
+        pairs
+            .into_iter()
+            .map(|(red, blue)| group_matchup(group, round, date, red, Some(blue))),
+    );
+
+    matchups
+}
+
+/// Pairs each highest unpaired team with the next highest one it has not met, backtracking
+/// when the remaining teams cannot be paired
+fn pair_unmet(
+    teams: &[ObjectId],
+    met: &HashSet<(ObjectId, ObjectId)>,
+) -> Option<Vec<(ObjectId, ObjectId)>> {
+    let Some((&first, rest)) = teams.split_first() else {
+        return Some(vec![]);
+    };
+
+    for (i, &opponent) in rest.iter().enumerate() {
+        if met.contains(&(first, opponent)) {
+            continue;
+        }
+
+        let remaining = rest
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, team)| *team)
+            .collect::<Vec<_>>();
+
+        if let Some(mut pairs) = pair_unmet(&remaining, met) {
+            pairs.insert(0, (first, opponent));
+            return Some(pairs);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn teams(count: usize) -> Vec<ObjectId> {
+        (0..count).map(|_| ObjectId::new()).collect()
+    }
+
+    fn met(pairs: &[(ObjectId, ObjectId)]) -> HashSet<(ObjectId, ObjectId)> {
+        pairs
+            .iter()
+            .flat_map(|&(red, blue)| [(red, blue), (blue, red)])
+            .collect()
+    }
+
+    #[test]
+    fn snake_reverses_every_pass() {
+        let s = teams(9);
+        let groups = snake_groups(&s, 3);
+
+        assert_eq!(groups[0], vec![s[0], s[5], s[6]]);
+        assert_eq!(groups[1], vec![s[1], s[4], s[7]]);
+        assert_eq!(groups[2], vec![s[2], s[3], s[8]]);
+    }
+
+    #[test]
+    fn snake_fills_uneven_groups_from_the_front() {
+        let s = teams(5);
+        let groups = snake_groups(&s, 2);
+
+        assert_eq!(groups[0], vec![s[0], s[3], s[4]]);
+        assert_eq!(groups[1], vec![s[1], s[2]]);
+    }
+
+    #[test]
+    fn pairs_highest_teams_first() {
+        let t = teams(4);
+
+        assert_eq!(
+            pair_unmet(&t, &HashSet::new()),
+            Some(vec![(t[0], t[1]), (t[2], t[3])])
+        );
+    }
+
+    #[test]
+    fn pairing_avoids_rematches() {
+        let t = teams(4);
+
+        assert_eq!(
+            pair_unmet(&t, &met(&[(t[0], t[1])])),
+            Some(vec![(t[0], t[2]), (t[1], t[3])])
+        );
+    }
+
+    #[test]
+    fn pairing_backtracks_when_the_rest_cannot_pair() {
+        let t = teams(4);
+
+        // Pairing the top two would leave the bottom two facing each other again
+        assert_eq!(
+            pair_unmet(&t, &met(&[(t[2], t[3])])),
+            Some(vec![(t[0], t[2]), (t[1], t[3])])
+        );
+    }
+
+    #[test]
+    fn pairing_fails_when_everyone_met() {
+        let t = teams(2);
+
+        assert_eq!(pair_unmet(&t, &met(&[(t[0], t[1])])), None);
+    }
+
+    #[test]
+    fn group_names_run_through_the_alphabet() {
+        assert_eq!(group_name(0), "A");
+        assert_eq!(group_name(25), "Z");
+        assert_eq!(group_name(26), "27");
+    }
+}
@@ -0,0 +1,88 @@
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::{
+    options::{FindOptions, IndexOptions},
+    IndexModel,
+};
+
+use vocolo_internal::*;
+
+use crate::{is_duplicate_key, to_vocolo_error, Database};
+
+use super::Group;
+
+pub(super) static COL: &str = "osu_groups";
+
+pub trait AbstractOsuGroup: Sync + Send {
+    async fn fetch_osu_round_groups(&self, round_id: &ObjectId) -> Result<Vec<Group>>;
+
+    /// Stores the groups of a round, returning false when the round already has them
+    async fn insert_osu_groups(&self, groups: &[Group]) -> Result<bool>;
+
+    async fn delete_osu_groups(&self, ids: &[ObjectId]) -> Result<()>;
+
+    /// Keeps group names unique within a round, which keeps a round to one set of groups
+    async fn ensure_osu_group_indexes(&self) -> Result<()>;
+}
+
+impl AbstractOsuGroup for Database {
+    async fn fetch_osu_round_groups(&self, round_id: &ObjectId) -> Result<Vec<Group>> {
+        let opts = FindOptions::builder().sort(doc! { "name": 1 }).build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "round_id": round_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn insert_osu_groups(&self, groups: &[Group]) -> Result<bool> {
+        if groups.is_empty() {
+            return Ok(true);
+        }
+
+        match self.col::<Group>(COL).insert_many(groups, None).await {
+            Ok(_) => Ok(true),
+            Err(error) if is_duplicate_key(&error) => {
+                // The insert stops at the first taken name, drop what made it in before it
+                let ids = groups
+                    .iter()
+                    .filter_map(|group| group.id)
+                    .collect::<Vec<_>>();
+
+                self.delete_osu_groups(&ids).await?;
+
+                Ok(false)
+            }
+            Err(error) => Err(to_vocolo_error(error)),
+        }
+    }
+
+    async fn delete_osu_groups(&self, ids: &[ObjectId]) -> Result<()> {
+        self.col::<Document>(COL)
+            .delete_many(doc! { "_id": { "$in": ids } }, None)
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
+    async fn ensure_osu_group_indexes(&self) -> Result<()> {
+        let index = IndexModel::builder()
+            .keys(doc! {
+                "round_id": 1,
+                "name": 1
+            })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        self.col::<Document>(COL)
+            .create_index(index, None)
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::AbstractOsuInviteCode;
+
+const CODE_LENGTH: usize = 12;
+
+/// Single use code an organizer hands out to let a team register to an invite-only tournament
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct InviteCode {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+
+    pub code: String,
+    pub created_by: i32,
+    pub created_at: DateTime<Utc>,
+
+    /// Team registered with the code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_by: Option<ObjectId>,
+}
+
+impl InviteCode {
+    pub async fn generate(
+        db: &Database,
+        tournament_id: &ObjectId,
+        created_by: i32,
+        count: usize,
+    ) -> Result<Vec<InviteCode>> {
+        let now = Utc::now();
+        let codes = (0..count)
+            .map(|_| InviteCode {
+                id: None,
+                tournament_id: *tournament_id,
+                code: rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(CODE_LENGTH)
+                    .map(char::from)
+                    .collect(),
+                created_by,
+                created_at: now,
+                used_by: None,
+            })
+            .collect::<Vec<_>>();
+
+        db.insert_osu_invite_codes(&codes).await?;
+
+        Ok(codes)
+    }
+
+    pub async fn list(db: &Database, tournament_id: &ObjectId) -> Result<Vec<InviteCode>> {
+        let codes = db.fetch_osu_tournament_invite_codes(tournament_id).await?;
+        Ok(codes)
+    }
+
+    pub async fn delete(db: &Database, tournament_id: &ObjectId, code: &str) -> Result<()> {
+        db.delete_osu_invite_code(tournament_id, code).await?;
+        Ok(())
+    }
+
+    /// Marks the code as used by the team, failing if it is unknown or already used
+    pub async fn redeem(
+        db: &Database,
+        tournament_id: &ObjectId,
+        code: &str,
+        team_id: &ObjectId,
+    ) -> Result<()> {
+        if !db
+            .redeem_osu_invite_code(tournament_id, code, team_id)
+            .await?
+        {
+            return Err(Error::InvalidInviteCode);
+        }
+
+        Ok(())
+    }
+
+    /// Frees the code redeemed by the team, used when its registration did not go through
+    pub async fn release(
+        db: &Database,
+        tournament_id: &ObjectId,
+        team_id: &ObjectId,
+    ) -> Result<()> {
+        db.release_osu_invite_code(tournament_id, team_id).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::FindOptions;
+
+use vocolo_internal::*;
+
+use crate::{to_vocolo_error, Database};
+
+use super::InviteCode;
+
+pub(super) static COL: &str = "osu_invite_codes";
+
+pub trait AbstractOsuInviteCode: Sync + Send {
+    async fn fetch_osu_tournament_invite_codes(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<InviteCode>>;
+
+    async fn insert_osu_invite_codes(&self, codes: &[InviteCode]) -> Result<()>;
+
+    async fn delete_osu_invite_code(&self, tournament_id: &ObjectId, code: &str) -> Result<()>;
+
+    /// Claims an unused code for the team, returning whether it did
+    async fn redeem_osu_invite_code(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+        team_id: &ObjectId,
+    ) -> Result<bool>;
+
+    /// Frees the code claimed by the team so it can be used again
+    async fn release_osu_invite_code(
+        &self,
+        tournament_id: &ObjectId,
+        team_id: &ObjectId,
+    ) -> Result<()>;
+}
+
+impl AbstractOsuInviteCode for Database {
+    async fn fetch_osu_tournament_invite_codes(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<InviteCode>> {
+        let opts = FindOptions::builder()
+            .sort(doc! { "created_at": -1 })
+            .build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "tournament_id": tournament_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn insert_osu_invite_codes(&self, codes: &[InviteCode]) -> Result<()> {
+        if codes.is_empty() {
+            return Ok(());
+        }
+
+        self.col::<InviteCode>(COL)
+            .insert_many(codes, None)
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
+    async fn delete_osu_invite_code(&self, tournament_id: &ObjectId, code: &str) -> Result<()> {
+        let result = self
+            .col::<Document>(COL)
+            .delete_one(
+                doc! {
+                    "tournament_id": tournament_id,
+                    "code": code
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.deleted_count == 0 {
+            return Err(Error::InvalidInviteCode);
+        }
+
+        Ok(())
+    }
+
+    async fn redeem_osu_invite_code(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+        team_id: &ObjectId,
+    ) -> Result<bool> {
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "tournament_id": tournament_id,
+                    "code": code,
+                    "used_by": { "$exists": false }
+                },
+                doc! {
+                    "$set": {
+                        "used_by": team_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(result.modified_count > 0)
+    }
+
+    async fn release_osu_invite_code(
+        &self,
+        tournament_id: &ObjectId,
+        team_id: &ObjectId,
+    ) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "tournament_id": tournament_id,
+                    "used_by": team_id
+                },
+                doc! {
+                    "$unset": {
+                        "used_by": ""
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+}
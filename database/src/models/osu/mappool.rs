@@ -6,7 +6,7 @@ use vocolo_internal::*;
 
 use crate::Database;
 
-use super::AbstractOsuMappool;
+use super::{AbstractOsuMappool, Round};
 
 #[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[optional_derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
@@ -17,6 +17,8 @@ pub struct Mappool {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub tournament_id: ObjectId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_id: Option<ObjectId>,
 
     pub private: bool,
 
@@ -37,6 +39,11 @@ pub struct MappoolMap {
 
 impl Mappool {
     pub async fn create(&self, db: &Database) -> Result<ObjectId> {
+        if let Some(round_id) = &self.round_id {
+            Round::fetch_in_tournament(db, round_id, &self.tournament_id).await?;
+        }
+
+        // A round that already has a mappool is refused by the round index
         let id = db.insert_osu_mappool(self).await?;
         Ok(id)
     }
@@ -51,6 +58,22 @@ impl Mappool {
         Ok(mappool)
     }
 
+    pub async fn fetch_by_round(db: &Database, round_id: &ObjectId) -> Result<Mappool> {
+        let mappool = db
+            .fetch_osu_round_mappool(round_id)
+            .await?
+            .ok_or(Error::UnknownMappool)?;
+        Ok(mappool)
+    }
+
+    pub async fn list_by_tournament(
+        db: &Database,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<Mappool>> {
+        let mappools = db.fetch_osu_tournament_mappools(tournament_id).await?;
+        Ok(mappools)
+    }
+
     pub async fn delete(db: &Database, mappool_id: &str) -> Result<()> {
         db.delete_osu_mappool(mappool_id).await?;
         Ok(())
@@ -66,3 +89,10 @@ impl Mappool {
         Ok(())
     }
 }
+
+impl MappoolMap {
+    /// Tiebreaker maps are marked with the `TB` modifier
+    pub fn is_tiebreaker(&self) -> bool {
+        self.modifiers.eq_ignore_ascii_case("TB")
+    }
+}
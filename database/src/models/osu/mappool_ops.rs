@@ -1,24 +1,33 @@
 use bson::{doc, oid::ObjectId, to_document, Document};
+use mongodb::{options::IndexOptions, IndexModel};
 
 use vocolo_internal::{Error, Result};
 
-use crate::{str_to_oid, to_vocolo_error, Database};
+use crate::{is_duplicate_key, str_to_oid, to_vocolo_error, Database};
 
 use super::{Mappool, MappoolMap, PartialMappool};
 
-static COL: &str = "osu_mappools";
+pub(super) static COL: &str = "osu_mappools";
 
 pub trait AbstractOsuMappool: Sync + Send {
     async fn fetch_osu_mappool(&self, mappool_id: &str) -> Result<Mappool>;
 
+    async fn fetch_osu_round_mappool(&self, round_id: &ObjectId) -> Result<Option<Mappool>>;
+
+    async fn fetch_osu_tournament_mappools(&self, tournament_id: &ObjectId)
+        -> Result<Vec<Mappool>>;
+
+    /// Stores the mappool, failing with `AlreadyExists` when its round already has one
     async fn insert_osu_mappool(&self, mappool: &Mappool) -> Result<ObjectId>;
 
+    /// Keeps a round to a single mappool
+    async fn ensure_osu_mappool_indexes(&self) -> Result<()>;
+
     async fn update_osu_mappool(&self, id: &str, data: &PartialMappool) -> Result<()>;
 
     async fn delete_osu_mappool(&self, mappool_id: &str) -> Result<u64>;
 
-    async fn insert_osu_mappool_maps(&self, mappool_id: &str, maps: &Vec<MappoolMap>)
-        -> Result<()>;
+    async fn insert_osu_mappool_maps(&self, mappool_id: &str, maps: &[MappoolMap]) -> Result<()>;
 
     async fn delete_osu_mappool_map(&self, mappool_id: &str, map_ids: &i32) -> Result<()>;
 }
@@ -40,10 +49,41 @@ impl AbstractOsuMappool for Database {
         Ok(mappool)
     }
 
+    async fn fetch_osu_round_mappool(&self, round_id: &ObjectId) -> Result<Option<Mappool>> {
+        self.find_one(
+            COL,
+            doc! {
+                "round_id": round_id
+            },
+        )
+        .await
+    }
+
+    async fn fetch_osu_tournament_mappools(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<Mappool>> {
+        self.find(
+            COL,
+            doc! {
+                "tournament_id": tournament_id
+            },
+        )
+        .await
+    }
+
     async fn insert_osu_mappool(&self, mappool: &Mappool) -> Result<ObjectId> {
         let id = self
-            .insert_one(COL, mappool)
-            .await?
+            .col::<Mappool>(COL)
+            .insert_one(mappool, None)
+            .await
+            .map_err(|error| {
+                if is_duplicate_key(&error) {
+                    Error::AlreadyExists("duplication on field round_id".to_string())
+                } else {
+                    to_vocolo_error(error)
+                }
+            })?
             .inserted_id
             .as_object_id()
             .unwrap();
@@ -51,6 +91,27 @@ impl AbstractOsuMappool for Database {
         Ok(id)
     }
 
+    async fn ensure_osu_mappool_indexes(&self) -> Result<()> {
+        let index = IndexModel::builder()
+            .keys(doc! {
+                "round_id": 1
+            })
+            .options(
+                IndexOptions::builder()
+                    .unique(true)
+                    .partial_filter_expression(doc! { "round_id": { "$exists": true } })
+                    .build(),
+            )
+            .build();
+
+        self.col::<Document>(COL)
+            .create_index(index, None)
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
     async fn update_osu_mappool(&self, id: &str, data: &PartialMappool) -> Result<()> {
         let id = str_to_oid(id);
 
@@ -83,7 +144,7 @@ impl AbstractOsuMappool for Database {
         Ok(result.deleted_count)
     }
 
-    async fn insert_osu_mappool_maps(&self, mappool_id: &str, map: &Vec<MappoolMap>) -> Result<()> {
+    async fn insert_osu_mappool_maps(&self, mappool_id: &str, map: &[MappoolMap]) -> Result<()> {
         let mappool_oid = str_to_oid(mappool_id);
 
         self.col::<Document>(COL)
@@ -7,28 +7,118 @@ use vocolo_internal::*;
 
 use crate::Database;
 
-use super::AbstractOsuMatchup;
+use super::{
+    check_bans, AbstractOsuMappool, AbstractOsuMatchup, GameMode, Round, ScoreJudgements,
+    ScoringRules, ScoringVersion, Tournament,
+};
 
-#[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
-#[optional_derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
+#[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[optional_derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
 #[optional_name = "PartialMatchup"]
 #[opt_skip_serializing_none]
 #[opt_some_priority]
 pub struct Matchup {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub tournament_id: ObjectId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_id: Option<ObjectId>,
 
     pub date: DateTime<Utc>,
 
     pub team_red: Option<ObjectId>,
     pub team_blue: Option<ObjectId>,
+    /// Team acting as `A` in the round's pick/ban sequence, red when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_team: Option<MatchupTeam>,
 
     pub maps: Vec<MatchupMap>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bracket: Option<MatchupBracket>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<MatchupGroup>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner_to: Option<MatchupSlot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loser_to: Option<MatchupSlot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub red_from: Option<MatchupFeeder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blue_from: Option<MatchupFeeder>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<MatchupResult>,
+}
+
+/// Placement of a matchup within a generated elimination bracket
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupBracket {
+    pub side: BracketSide,
+    pub round: i32,
+    pub position: i32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum BracketSide {
+    Winners,
+    Losers,
+    GrandFinals,
+}
+
+/// Placement of a matchup within a group stage
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupGroup {
+    pub group_id: ObjectId,
+    pub round: i32,
+}
+
+/// Team slot of another matchup a team advances into
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupSlot {
+    pub matchup_id: ObjectId,
+    pub team: MatchupTeam,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MatchupTeam {
+    Red,
+    Blue,
+}
+
+/// Matchup whose winner or loser fills a team slot
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupFeeder {
+    pub matchup_id: ObjectId,
+    pub outcome: MatchupOutcome,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MatchupOutcome {
+    Winner,
+    Loser,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupResult {
+    pub winner: MatchupTeam,
+    pub kind: MatchupResultKind,
+
+    pub team_red_score: i16,
+    pub team_blue_score: i16,
 }
 
-#[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
-#[optional_derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MatchupResultKind {
+    Played,
+    /// The opponent did not show up
+    Walkover,
+    /// The opponent gave up or was disqualified
+    Forfeit,
+}
+
+#[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[optional_derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
 #[optional_name = "PartialMatchupMap"]
 #[opt_skip_serializing_none]
 #[opt_some_priority]
@@ -42,6 +132,9 @@ pub struct MatchupMap {
     pub team_red_scores: Option<Vec<MatchupMapScore>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub team_blue_scores: Option<Vec<MatchupMapScore>>,
+    /// Team that won the map, set once its scores are recorded and `None` on a tie
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner: Option<MatchupTeam>,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -51,8 +144,22 @@ pub enum MatchupMapType {
     Protect,
 }
 
-#[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
-#[optional_derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
+/// Outcome of importing an osu! multiplayer match into a matchup
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchupImport {
+    pub matchup: Matchup,
+    /// Picks that received scores
+    pub imported_maps: Vec<i32>,
+    /// Beatmaps played in the lobby that are not picks of the matchup
+    pub unknown_maps: Vec<i32>,
+    /// Players who set scores without being on either team
+    pub unknown_players: Vec<i32>,
+    /// Beatmaps played with another scoring version than the matchup's rules
+    pub mismatched_scoring: Vec<i32>,
+}
+
+#[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[optional_derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
 #[optional_name = "PartialMatchupMapScore"]
 #[opt_skip_serializing_none]
 #[opt_some_priority]
@@ -63,10 +170,27 @@ pub struct MatchupMapScore {
     pub mods: Option<String>,
 
     pub score: u32,
+
+    /// Between 0 and 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_combo: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub misses: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub judgements: Option<ScoreJudgements>,
 }
 
 impl Matchup {
     pub async fn create(&self, db: &Database) -> Result<ObjectId> {
+        self.check_round(db).await?;
+
+        let tournament_id = self.tournament_id.to_hex();
+        for team_id in [&self.team_red, &self.team_blue].into_iter().flatten() {
+            Tournament::fetch_team(db, &tournament_id, team_id).await?;
+        }
+
         let id = db.insert_osu_matchup(self).await?;
         Ok(id)
     }
@@ -76,7 +200,32 @@ impl Matchup {
         Ok(matchup)
     }
 
+    pub async fn list_by_round(db: &Database, round_id: &ObjectId) -> Result<Vec<Matchup>> {
+        let matchups = db.fetch_osu_round_matchups(round_id).await?;
+        Ok(matchups)
+    }
+
+    /// Updates the matchup, checking the picks and bans recorded so far again when the
+    /// round or the first team changes
     pub async fn update(db: &Database, match_id: &str, partial: &PartialMatchup) -> Result<()> {
+        let reschedules = partial.round_id.is_some() || partial.date.is_some();
+        let reorders = partial.round_id.is_some() || partial.first_team.is_some();
+
+        if reschedules || reorders {
+            let mut matchup = Self::fetch(db, match_id).await?;
+            matchup.round_id = partial.round_id.or(matchup.round_id);
+            matchup.date = partial.date.unwrap_or(matchup.date);
+            matchup.first_team = partial.first_team.or(matchup.first_team);
+
+            if reschedules {
+                matchup.check_round(db).await?;
+            }
+
+            if reorders && !matchup.maps.is_empty() {
+                matchup.check_pick_ban(db).await?;
+            }
+        }
+
         db.update_osu_matchup(match_id, partial).await?;
         Ok(())
     }
@@ -85,4 +234,567 @@ impl Matchup {
         db.delete_osu_matchup(match_id).await?;
         Ok(())
     }
+
+    /// Whether the result is the winners bracket champion taking the first grand finals,
+    /// which hands them the reset without playing it
+    fn settles_reset(&self, result: &MatchupResult) -> bool {
+        result.winner == MatchupTeam::Red
+            && matches!(
+                &self.bracket,
+                Some(MatchupBracket {
+                    side: BracketSide::GrandFinals,
+                    round: 1,
+                    ..
+                })
+            )
+    }
+
+    pub fn team(&self, team: MatchupTeam) -> Option<ObjectId> {
+        match team {
+            MatchupTeam::Red => self.team_red,
+            MatchupTeam::Blue => self.team_blue,
+        }
+    }
+
+    /// Stores the result and slots the winner, and the loser if it moves on, into the next matchups
+    pub async fn record_result(
+        db: &Database,
+        match_id: &str,
+        result: MatchupResult,
+    ) -> Result<Matchup> {
+        let matchup = Self::fetch_undecided(db, match_id).await?;
+        Self::apply_result(db, matchup, result).await
+    }
+
+    async fn apply_result(
+        db: &Database,
+        mut matchup: Matchup,
+        result: MatchupResult,
+    ) -> Result<Matchup> {
+        let id = matchup.id.ok_or(Error::UnknownMatch)?;
+
+        let winner = matchup.team(result.winner);
+        let loser = matchup.team(result.winner.opponent());
+
+        if winner.is_none() || (result.kind == MatchupResultKind::Played && loser.is_none()) {
+            return Err(Error::MatchNotReady);
+        }
+
+        result.check_scores()?;
+
+        // Only the request that stores the result goes on to advance the teams
+        db.set_osu_matchup_result(&id, &result).await?;
+
+        if let Some(slot) = &matchup.winner_to {
+            db.set_osu_matchup_team(&slot.matchup_id, slot.team, winner)
+                .await?;
+        }
+
+        if matchup.settles_reset(&result) {
+            if let Some(slot) = &matchup.winner_to {
+                db.set_osu_matchup_result(&slot.matchup_id, &reset_walkover(slot))
+                    .await?;
+            }
+        } else if let Some(slot) = &matchup.loser_to {
+            db.set_osu_matchup_team(&slot.matchup_id, slot.team, loser)
+                .await?;
+        }
+
+        matchup.result = Some(result);
+        Ok(matchup)
+    }
+
+    /// Removes the result and the teams it advanced, as long as none of them have played since
+    pub async fn clear_result(db: &Database, match_id: &str) -> Result<Matchup> {
+        let mut matchup = Self::fetch(db, match_id).await?;
+        let id = matchup.id.ok_or(Error::UnknownMatch)?;
+
+        let Some(result) = matchup.result.clone() else {
+            return Ok(matchup);
+        };
+
+        let settled_reset = matchup.settles_reset(&result);
+        let winner = matchup.team(result.winner);
+        let loser = matchup.team(result.winner.opponent());
+
+        // The loser of a settled grand finals was never slotted into the reset
+        let advanced = [
+            (&matchup.winner_to, winner),
+            (&matchup.loser_to, loser.filter(|_| !settled_reset)),
+        ]
+        .into_iter()
+        .filter_map(|(slot, team_id)| Some((slot.as_ref()?, team_id?)))
+        .collect::<Vec<_>>();
+
+        let reset = matchup.winner_to.as_ref().filter(|_| settled_reset);
+        let unset_reset = match reset {
+            Some(slot) => db.unset_osu_matchup_result(&slot.matchup_id).await?,
+            None => false,
+        };
+
+        // Each slot is only emptied while its matchup is undecided and still holds the team,
+        // so a matchup decided meanwhile keeps its teams and this result stays
+        let mut cleared: Vec<(&MatchupSlot, ObjectId)> = vec![];
+        for (slot, team_id) in advanced {
+            if let Err(error) = db
+                .clear_osu_matchup_team(&slot.matchup_id, slot.team, &team_id)
+                .await
+            {
+                for (slot, team_id) in cleared {
+                    db.set_osu_matchup_team(&slot.matchup_id, slot.team, Some(team_id))
+                        .await?;
+                }
+
+                if let Some(slot) = reset.filter(|_| unset_reset) {
+                    db.set_osu_matchup_result(&slot.matchup_id, &reset_walkover(slot))
+                        .await?;
+                }
+
+                return Err(error);
+            }
+
+            cleared.push((slot, team_id));
+        }
+
+        db.unset_osu_matchup_result(&id).await?;
+
+        matchup.result = None;
+        Ok(matchup)
+    }
+
+    /// Records the next pick, ban or protect of the matchup, made by `team`
+    pub async fn add_map(
+        db: &Database,
+        match_id: &str,
+        map_type: MatchupMapType,
+        team: MatchupTeam,
+        map_id: i32,
+    ) -> Result<Matchup> {
+        let mut matchup = Self::fetch_undecided(db, match_id).await?;
+        let id = matchup.id.ok_or(Error::UnknownMatch)?;
+        let previous = matchup.maps.clone();
+
+        let team = matchup.team(team).ok_or(Error::MatchNotReady)?;
+        matchup.check_mappool_map(db, map_id).await?;
+
+        matchup.maps.push(MatchupMap {
+            map_id,
+            map_type,
+            team,
+            team_red_scores: None,
+            team_blue_scores: None,
+            winner: None,
+        });
+
+        matchup.check_pick_ban(db).await?;
+
+        db.set_osu_matchup_maps(&id, &previous, &matchup.maps)
+            .await?;
+        Ok(matchup)
+    }
+
+    /// Amends the entry at `index`, dropping its scores when the map changes
+    pub async fn update_map(
+        db: &Database,
+        match_id: &str,
+        index: usize,
+        map_type: Option<MatchupMapType>,
+        team: Option<MatchupTeam>,
+        map_id: Option<i32>,
+    ) -> Result<Matchup> {
+        let mut matchup = Self::fetch_undecided(db, match_id).await?;
+        let id = matchup.id.ok_or(Error::UnknownMatch)?;
+        let previous = matchup.maps.clone();
+
+        let team = team
+            .map(|team| matchup.team(team).ok_or(Error::MatchNotReady))
+            .transpose()?;
+        if let Some(map_id) = map_id {
+            matchup.check_mappool_map(db, map_id).await?;
+        }
+
+        let entry = matchup
+            .maps
+            .get_mut(index)
+            .ok_or(Error::UnknownMatchupMap)?;
+
+        if let Some(team) = team {
+            entry.team = team;
+        }
+
+        // Scores only belong to the map as it was picked, changing either discards them
+        let map_type = map_type.filter(|map_type| *map_type != entry.map_type);
+        let map_id = map_id.filter(|map_id| *map_id != entry.map_id);
+
+        if map_type.is_some() || map_id.is_some() {
+            entry.team_red_scores = None;
+            entry.team_blue_scores = None;
+            entry.winner = None;
+        }
+
+        if let Some(map_type) = map_type {
+            entry.map_type = map_type;
+        }
+
+        if let Some(map_id) = map_id {
+            entry.map_id = map_id;
+        }
+
+        matchup.check_pick_ban(db).await?;
+
+        db.set_osu_matchup_maps(&id, &previous, &matchup.maps)
+            .await?;
+        Ok(matchup)
+    }
+
+    pub async fn remove_map(db: &Database, match_id: &str, index: usize) -> Result<Matchup> {
+        let mut matchup = Self::fetch_undecided(db, match_id).await?;
+        let id = matchup.id.ok_or(Error::UnknownMatch)?;
+        let previous = matchup.maps.clone();
+
+        if index >= matchup.maps.len() {
+            return Err(Error::UnknownMatchupMap);
+        }
+        matchup.maps.remove(index);
+
+        matchup.check_pick_ban(db).await?;
+
+        db.set_osu_matchup_maps(&id, &previous, &matchup.maps)
+            .await?;
+        Ok(matchup)
+    }
+
+    /// Checks the recorded maps against the ban count and pick/ban sequence of the
+    /// matchup's round
+    async fn check_pick_ban(&self, db: &Database) -> Result<()> {
+        let Some(round_id) = &self.round_id else {
+            return Ok(());
+        };
+
+        let round = Round::fetch(db, &round_id.to_hex()).await?;
+        check_bans(&self.maps, round.ban_count)?;
+
+        let Some(sequence) = &round.pick_ban_sequence else {
+            return Ok(());
+        };
+
+        let team_a = self
+            .team(self.first_team.unwrap_or(MatchupTeam::Red))
+            .ok_or(Error::MatchNotReady)?;
+
+        let tiebreakers = db
+            .fetch_osu_round_mappool(round_id)
+            .await?
+            .map(|mappool| {
+                mappool
+                    .maps
+                    .iter()
+                    .filter(|map| map.is_tiebreaker())
+                    .map(|map| map.beatmap_id)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        sequence.validate(&self.maps, &team_a, round.best_of, &tiebreakers)
+    }
+
+    /// Stores the scores of the pick at `index` and its winner, recording the result of the
+    /// matchup once a team has won the majority of the round's best-of
+    pub async fn record_map_scores(
+        db: &Database,
+        match_id: &str,
+        index: usize,
+        team_red_scores: Vec<MatchupMapScore>,
+        team_blue_scores: Vec<MatchupMapScore>,
+    ) -> Result<Matchup> {
+        let mut matchup = Self::fetch_undecided(db, match_id).await?;
+        let id = matchup.id.ok_or(Error::UnknownMatch)?;
+        let previous = matchup.maps.clone();
+
+        let (mode, rules, round) = matchup.scoring(db).await?;
+
+        let entry = matchup
+            .maps
+            .get_mut(index)
+            .ok_or(Error::UnknownMatchupMap)?;
+
+        if entry.map_type != MatchupMapType::Pick {
+            return Err(Error::MapNotPicked);
+        }
+
+        entry.team_red_scores = Some(team_red_scores);
+        entry.team_blue_scores = Some(team_blue_scores);
+        entry.winner = entry.compute_winner(&rules, &mode);
+
+        db.set_osu_matchup_maps(&id, &previous, &matchup.maps)
+            .await?;
+
+        Self::decide(db, matchup, round).await
+    }
+
+    /// Imports the scores of an osu! multiplayer match. Games are matched to the picks by
+    /// beatmap and scores to the teams by roster, the last game of a map replacing earlier
+    /// ones. Maps and players that match nothing are reported rather than rejected
+    pub async fn import_scores(
+        db: &Database,
+        match_id: &str,
+        osu_match: &OsuMatch,
+    ) -> Result<MatchupImport> {
+        let mut matchup = Self::fetch_undecided(db, match_id).await?;
+        let id = matchup.id.ok_or(Error::UnknownMatch)?;
+        let previous = matchup.maps.clone();
+
+        let (Some(team_red), Some(team_blue)) = (matchup.team_red, matchup.team_blue) else {
+            return Err(Error::MatchNotReady);
+        };
+
+        let tournament_id = matchup.tournament_id.to_hex();
+        let team_red = Tournament::fetch_team(db, &tournament_id, &team_red).await?;
+        let team_blue = Tournament::fetch_team(db, &tournament_id, &team_blue).await?;
+
+        let (mode, rules, round) = matchup.scoring(db).await?;
+
+        let mut imported_maps = vec![];
+        let mut unknown_maps = vec![];
+        let mut unknown_players = vec![];
+        let mut mismatched_scoring = vec![];
+
+        let games = osu_match
+            .events
+            .iter()
+            .filter_map(|event| event.game.as_ref());
+
+        for game in games {
+            // Aborted games have no scores
+            if game.scores.is_empty() {
+                continue;
+            }
+
+            let Some(entry) = matchup
+                .maps
+                .iter_mut()
+                .find(|map| map.map_type == MatchupMapType::Pick && map.map_id == game.beatmap_id)
+            else {
+                push_unique(&mut unknown_maps, game.beatmap_id);
+                continue;
+            };
+
+            if ScoringVersion::from_osu(&game.scoring_type)
+                .is_some_and(|version| version != rules.version)
+            {
+                push_unique(&mut mismatched_scoring, game.beatmap_id);
+            }
+
+            let mut team_red_scores = vec![];
+            let mut team_blue_scores = vec![];
+
+            for score in &game.scores {
+                let record = MatchupMapScore::from_osu(score, &game.mods);
+
+                if team_red.players.contains(&score.user_id) {
+                    team_red_scores.push(record);
+                } else if team_blue.players.contains(&score.user_id) {
+                    team_blue_scores.push(record);
+                } else {
+                    push_unique(&mut unknown_players, score.user_id);
+                }
+            }
+
+            entry.team_red_scores = Some(team_red_scores);
+            entry.team_blue_scores = Some(team_blue_scores);
+            entry.winner = entry.compute_winner(&rules, &mode);
+
+            push_unique(&mut imported_maps, game.beatmap_id);
+        }
+
+        if !imported_maps.is_empty() {
+            db.set_osu_matchup_maps(&id, &previous, &matchup.maps)
+                .await?;
+            matchup = Self::decide(db, matchup, round).await?;
+        }
+
+        Ok(MatchupImport {
+            matchup,
+            imported_maps,
+            unknown_maps,
+            unknown_players,
+            mismatched_scoring,
+        })
+    }
+
+    /// Records the result once a team has won the majority of the round's best-of
+    async fn decide(db: &Database, matchup: Matchup, round: Option<Round>) -> Result<Matchup> {
+        let Some(round) = round else {
+            return Ok(matchup);
+        };
+
+        match matchup.decided_winner(round.best_of) {
+            Some(winner) => {
+                let (team_red_score, team_blue_score) = matchup.map_score();
+                let result = MatchupResult {
+                    winner,
+                    kind: MatchupResultKind::Played,
+                    team_red_score,
+                    team_blue_score,
+                };
+
+                Self::apply_result(db, matchup, result).await
+            }
+            None => Ok(matchup),
+        }
+    }
+
+    /// Game mode and scoring rules the matchup is played with, along with its round
+    pub async fn scoring(&self, db: &Database) -> Result<(GameMode, ScoringRules, Option<Round>)> {
+        let tournament = Tournament::fetch(db, &self.tournament_id.to_hex()).await?;
+        let round = match &self.round_id {
+            Some(round_id) => Some(Round::fetch(db, &round_id.to_hex()).await?),
+            None => None,
+        };
+
+        let rules = round
+            .as_ref()
+            .and_then(|round| round.scoring)
+            .or(tournament.scoring)
+            .unwrap_or_default();
+
+        Ok((tournament.mode, rules, round))
+    }
+
+    /// Picked maps won by the red and blue teams so far
+    pub fn map_score(&self) -> (i16, i16) {
+        self.maps
+            .iter()
+            .filter(|map| map.map_type == MatchupMapType::Pick)
+            .fold((0, 0), |(red, blue), map| match map.winner {
+                Some(MatchupTeam::Red) => (red + 1, blue),
+                Some(MatchupTeam::Blue) => (red, blue + 1),
+                None => (red, blue),
+            })
+    }
+
+    /// Team that won the majority of a best-of `best_of`, if any has yet
+    pub fn decided_winner(&self, best_of: i16) -> Option<MatchupTeam> {
+        let needed = best_of / 2 + 1;
+        let (red, blue) = self.map_score();
+
+        if red >= needed {
+            Some(MatchupTeam::Red)
+        } else if blue >= needed {
+            Some(MatchupTeam::Blue)
+        } else {
+            None
+        }
+    }
+
+    async fn fetch_undecided(db: &Database, match_id: &str) -> Result<Matchup> {
+        let matchup = Self::fetch(db, match_id).await?;
+
+        if matchup.result.is_some() {
+            return Err(Error::MatchAlreadyDecided);
+        }
+
+        Ok(matchup)
+    }
+
+    /// Makes sure the map is part of the mappool of the matchup's round
+    async fn check_mappool_map(&self, db: &Database, map_id: i32) -> Result<()> {
+        let round_id = self.round_id.ok_or(Error::UnknownMappool)?;
+        let mappool = db
+            .fetch_osu_round_mappool(&round_id)
+            .await?
+            .ok_or(Error::UnknownMappool)?;
+
+        if !mappool.maps.iter().any(|map| map.beatmap_id == map_id) {
+            return Err(Error::UnknownMappoolMap);
+        }
+
+        Ok(())
+    }
+
+    /// Makes sure the round belongs to the matchup's tournament and covers its date
+    async fn check_round(&self, db: &Database) -> Result<()> {
+        if let Some(round_id) = &self.round_id {
+            let round = Round::fetch_in_tournament(db, round_id, &self.tournament_id).await?;
+
+            if !round.is_scheduled_at(&self.date) {
+                return Err(Error::OutsideRoundSchedule);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MatchupMap {
+    /// Winner under `rules`, `None` until both teams have scores or on a tie
+    pub fn compute_winner(&self, rules: &ScoringRules, mode: &GameMode) -> Option<MatchupTeam> {
+        rules.map_winner(
+            mode,
+            self.team_red_scores.as_ref()?,
+            self.team_blue_scores.as_ref()?,
+        )
+    }
+}
+
+impl MatchupMapScore {
+    /// Score of an osu! multiplayer game, with the game's mods if they were not free
+    pub fn from_osu(score: &OsuMatchScore, game_mods: &[String]) -> MatchupMapScore {
+        let mods = game_mods
+            .iter()
+            .chain(score.mods.iter())
+            .map(|m| m.as_str())
+            .collect::<String>();
+
+        MatchupMapScore {
+            player: score.user_id,
+            mods: (!mods.is_empty()).then_some(mods),
+            score: score.score,
+            accuracy: Some(score.accuracy),
+            max_combo: Some(score.max_combo),
+            misses: Some(score.statistics.count_miss),
+            judgements: Some(score.statistics.into()),
+        }
+    }
+}
+
+impl MatchupResult {
+    /// Rejects a played result whose winner did not score more than the loser
+    pub fn check_scores(&self) -> Result<()> {
+        let (winner_score, loser_score) = match self.winner {
+            MatchupTeam::Red => (self.team_red_score, self.team_blue_score),
+            MatchupTeam::Blue => (self.team_blue_score, self.team_red_score),
+        };
+
+        if self.kind == MatchupResultKind::Played && winner_score <= loser_score {
+            return Err(Error::InvalidMatchScore);
+        }
+
+        Ok(())
+    }
+}
+
+impl MatchupTeam {
+    pub fn opponent(&self) -> MatchupTeam {
+        match self {
+            MatchupTeam::Red => MatchupTeam::Blue,
+            MatchupTeam::Blue => MatchupTeam::Red,
+        }
+    }
+}
+
+/// Result of a grand finals reset the winners bracket champion gets without playing it
+fn reset_walkover(slot: &MatchupSlot) -> MatchupResult {
+    MatchupResult {
+        winner: slot.team,
+        kind: MatchupResultKind::Walkover,
+        team_red_score: 0,
+        team_blue_score: 0,
+    }
+}
+
+fn push_unique(values: &mut Vec<i32>, value: i32) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
 }
@@ -1,21 +1,77 @@
-use bson::{doc, oid::ObjectId, to_document, Document};
+use bson::{doc, oid::ObjectId, to_bson, to_document, Bson, Document};
+use mongodb::{
+    options::{FindOptions, IndexOptions},
+    IndexModel,
+};
 
 use vocolo_internal::*;
 
-use crate::{str_to_oid, to_vocolo_error, Database};
+use crate::{is_duplicate_key, str_to_oid, to_vocolo_error, Database};
 
-use super::{Matchup, PartialMatchup};
+use super::{Matchup, MatchupMap, MatchupResult, MatchupTeam, PartialMatchup};
 
-static COL: &str = "osu_matches";
+pub(super) static COL: &str = "osu_matches";
 
 pub trait AbstractOsuMatchup: Sync + Send {
     async fn fetch_osu_matchup(&self, match_id: &str) -> Result<Matchup>;
 
+    async fn fetch_osu_round_matchups(&self, round_id: &ObjectId) -> Result<Vec<Matchup>>;
+
+    async fn fetch_osu_group_matchups(&self, group_id: &ObjectId) -> Result<Vec<Matchup>>;
+
     async fn insert_osu_matchup(&self, matchup: &Matchup) -> Result<ObjectId>;
 
+    /// Stores all of the matchups or none, returning false when one of them takes a
+    /// bracket position or group round slot that is already taken
+    async fn insert_osu_matchups(&self, matchups: &[Matchup]) -> Result<bool>;
+
+    /// Stores a generated bracket, returning false when the tournament already has one
+    async fn insert_osu_bracket(&self, matchups: &[Matchup]) -> Result<bool>;
+
+    async fn check_osu_tournament_bracket_exists(&self, tournament_id: &ObjectId) -> Result<bool>;
+
+    /// Keeps a tournament to a single bracket, one matchup per bracket position, and a
+    /// group round from being stored twice by concurrent generation or Swiss pairing
+    async fn ensure_osu_matchup_indexes(&self) -> Result<()>;
+
     async fn update_osu_matchup(&self, match_id: &str, tournament: &PartialMatchup) -> Result<()>;
 
     async fn delete_osu_matchup(&self, match_id: &str) -> Result<()>;
+
+    async fn delete_osu_matchups(&self, ids: &[ObjectId]) -> Result<()>;
+
+    async fn set_osu_matchup_team(
+        &self,
+        match_id: &ObjectId,
+        team: MatchupTeam,
+        team_id: Option<ObjectId>,
+    ) -> Result<()>;
+
+    /// Empties the team slot of an undecided matchup as long as it still holds `team_id`
+    async fn clear_osu_matchup_team(
+        &self,
+        match_id: &ObjectId,
+        team: MatchupTeam,
+        team_id: &ObjectId,
+    ) -> Result<()>;
+
+    /// Stores the result of a matchup that does not have one yet
+    async fn set_osu_matchup_result(
+        &self,
+        match_id: &ObjectId,
+        result: &MatchupResult,
+    ) -> Result<()>;
+
+    /// Removes the result of a matchup, returning false when it had none left to remove
+    async fn unset_osu_matchup_result(&self, match_id: &ObjectId) -> Result<bool>;
+
+    /// Replaces the maps of an undecided matchup, as long as they are still `previous`
+    async fn set_osu_matchup_maps(
+        &self,
+        match_id: &ObjectId,
+        previous: &[MatchupMap],
+        maps: &[MatchupMap],
+    ) -> Result<()>;
 }
 
 impl AbstractOsuMatchup for Database {
@@ -35,6 +91,34 @@ impl AbstractOsuMatchup for Database {
         Ok(mappool)
     }
 
+    async fn fetch_osu_round_matchups(&self, round_id: &ObjectId) -> Result<Vec<Matchup>> {
+        let opts = FindOptions::builder().sort(doc! { "date": 1 }).build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "round_id": round_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn fetch_osu_group_matchups(&self, group_id: &ObjectId) -> Result<Vec<Matchup>> {
+        let opts = FindOptions::builder()
+            .sort(doc! { "group.round": 1, "date": 1 })
+            .build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "group.group_id": group_id
+            },
+            opts,
+        )
+        .await
+    }
+
     async fn insert_osu_matchup(&self, matchup: &Matchup) -> Result<ObjectId> {
         let id = self
             .insert_one(COL, matchup)
@@ -46,6 +130,84 @@ impl AbstractOsuMatchup for Database {
         Ok(id)
     }
 
+    async fn insert_osu_matchups(&self, matchups: &[Matchup]) -> Result<bool> {
+        if matchups.is_empty() {
+            return Ok(true);
+        }
+
+        match self.col::<Matchup>(COL).insert_many(matchups, None).await {
+            Ok(_) => Ok(true),
+            Err(error) if is_duplicate_key(&error) => {
+                // The insert stops at the first taken slot, drop what made it in before it
+                let ids = matchups
+                    .iter()
+                    .filter_map(|matchup| matchup.id)
+                    .collect::<Vec<_>>();
+
+                self.delete_osu_matchups(&ids).await?;
+
+                Ok(false)
+            }
+            Err(error) => Err(to_vocolo_error(error)),
+        }
+    }
+
+    async fn insert_osu_bracket(&self, matchups: &[Matchup]) -> Result<bool> {
+        self.insert_osu_matchups(matchups).await
+    }
+
+    async fn check_osu_tournament_bracket_exists(&self, tournament_id: &ObjectId) -> Result<bool> {
+        self.exists(
+            COL,
+            doc! {
+                "tournament_id": tournament_id,
+                "bracket": { "$exists": true }
+            },
+        )
+        .await
+    }
+
+    async fn ensure_osu_matchup_indexes(&self) -> Result<()> {
+        let bracket = IndexModel::builder()
+            .keys(doc! {
+                "tournament_id": 1,
+                "bracket.side": 1,
+                "bracket.round": 1,
+                "bracket.position": 1
+            })
+            .options(
+                IndexOptions::builder()
+                    .unique(true)
+                    .partial_filter_expression(doc! { "bracket": { "$exists": true } })
+                    .build(),
+            )
+            .build();
+
+        // Generating a group round twice places the same red teams again, so a second
+        // concurrent generation or Swiss pairing of the round collides here. It does not
+        // check on its own that a team plays once per round, the pairing takes care of that
+        let group = IndexModel::builder()
+            .keys(doc! {
+                "group.group_id": 1,
+                "group.round": 1,
+                "team_red": 1
+            })
+            .options(
+                IndexOptions::builder()
+                    .unique(true)
+                    .partial_filter_expression(doc! { "group": { "$exists": true } })
+                    .build(),
+            )
+            .build();
+
+        self.col::<Document>(COL)
+            .create_indexes([bracket, group], None)
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
     async fn update_osu_matchup(&self, match_id: &str, partial: &PartialMatchup) -> Result<()> {
         let id = str_to_oid(match_id);
 
@@ -90,4 +252,164 @@ impl AbstractOsuMatchup for Database {
 
         Ok(())
     }
+
+    async fn delete_osu_matchups(&self, ids: &[ObjectId]) -> Result<()> {
+        self.col::<Document>(COL)
+            .delete_many(doc! { "_id": { "$in": ids } }, None)
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
+    async fn set_osu_matchup_team(
+        &self,
+        match_id: &ObjectId,
+        team: MatchupTeam,
+        team_id: Option<ObjectId>,
+    ) -> Result<()> {
+        let field = match team {
+            MatchupTeam::Red => "team_red",
+            MatchupTeam::Blue => "team_blue",
+        };
+
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": match_id
+                },
+                doc! {
+                    "$set": {
+                        field: team_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Err(Error::UnknownMatch);
+        }
+
+        Ok(())
+    }
+
+    async fn clear_osu_matchup_team(
+        &self,
+        match_id: &ObjectId,
+        team: MatchupTeam,
+        team_id: &ObjectId,
+    ) -> Result<()> {
+        let field = match team {
+            MatchupTeam::Red => "team_red",
+            MatchupTeam::Blue => "team_blue",
+        };
+
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": match_id,
+                    field: team_id,
+                    "result": { "$exists": false }
+                },
+                doc! {
+                    "$set": {
+                        field: Bson::Null
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Err(Error::MatchAlreadyDecided);
+        }
+
+        Ok(())
+    }
+
+    async fn set_osu_matchup_result(
+        &self,
+        match_id: &ObjectId,
+        result: &MatchupResult,
+    ) -> Result<()> {
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": match_id,
+                    "result": { "$exists": false }
+                },
+                doc! {
+                    "$set": {
+                        "result": to_document(result)?
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Err(Error::MatchAlreadyDecided);
+        }
+
+        Ok(())
+    }
+
+    async fn unset_osu_matchup_result(&self, match_id: &ObjectId) -> Result<bool> {
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": match_id,
+                    "result": { "$exists": true }
+                },
+                doc! {
+                    "$unset": {
+                        "result": 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(result.modified_count > 0)
+    }
+
+    async fn set_osu_matchup_maps(
+        &self,
+        match_id: &ObjectId,
+        previous: &[MatchupMap],
+        maps: &[MatchupMap],
+    ) -> Result<()> {
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": match_id,
+                    "maps": to_bson(previous)?,
+                    "result": { "$exists": false }
+                },
+                doc! {
+                    "$set": {
+                        "maps": to_bson(maps)?
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Err(Error::MatchChanged);
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,427 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use super::{MatchupMap, MatchupMapType, MatchupTeam};
+
+/// Order in which the teams of a matchup protect, ban and pick maps, e.g. protect A,
+/// protect B, ban B, ban A, pick A. Once the steps run out the teams keep alternating
+/// picks, and a tiebreaker is only played once every regular pick is in and the teams won
+/// as many of them
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PickBanSequence {
+    pub steps: Vec<PickBanStep>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PickBanStep {
+    pub action: MatchupMapType,
+    pub team: SequenceTeam,
+}
+
+/// Team `A` is the matchup's first team, usually the roll winner, `B` its opponent
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum SequenceTeam {
+    A,
+    B,
+}
+
+impl SequenceTeam {
+    pub fn other(&self) -> SequenceTeam {
+        match self {
+            SequenceTeam::A => SequenceTeam::B,
+            SequenceTeam::B => SequenceTeam::A,
+        }
+    }
+}
+
+impl PickBanSequence {
+    /// Step expected at `index`, alternating picks past the end of the template
+    pub fn step(&self, index: usize) -> PickBanStep {
+        if let Some(step) = self.steps.get(index) {
+            return step.clone();
+        }
+
+        let last_pick = self
+            .steps
+            .iter()
+            .rev()
+            .find(|step| step.action == MatchupMapType::Pick)
+            .map(|step| step.team);
+
+        // Extra picks alternate, starting with whoever did not make the last templated pick
+        let first = last_pick
+            .map(|team| team.other())
+            .unwrap_or(SequenceTeam::A);
+        let extra = index - self.steps.len();
+
+        PickBanStep {
+            action: MatchupMapType::Pick,
+            team: if extra % 2 == 0 { first } else { first.other() },
+        }
+    }
+
+    /// Makes sure the template gives each team exactly `ban_count` bans
+    pub fn check_ban_count(&self, ban_count: i16) -> Result<()> {
+        for team in [SequenceTeam::A, SequenceTeam::B] {
+            let bans = self
+                .steps
+                .iter()
+                .filter(|step| step.action == MatchupMapType::Ban && step.team == team)
+                .count();
+
+            if bans != ban_count as usize {
+                return Err(Error::InvalidPickBanSequence);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks every recorded entry against the sequence: each entry is made by the team
+    /// whose turn it is, a map is protected, banned or picked at most once, and the
+    /// tiebreaker is only picked once all regular picks are in and their recorded winners
+    /// are level. Picks without a recorded winner count for neither team
+    pub fn validate(
+        &self,
+        maps: &[MatchupMap],
+        team_a: &ObjectId,
+        best_of: i16,
+        tiebreakers: &[i32],
+    ) -> Result<()> {
+        let max_picks = if tiebreakers.is_empty() {
+            best_of as usize
+        } else {
+            best_of as usize - 1
+        };
+
+        let mut used = vec![];
+        let mut picks = 0;
+        let mut wins = (0, 0);
+        let mut tiebreaker_played = false;
+
+        for (i, map) in maps.iter().enumerate() {
+            if tiebreaker_played {
+                return Err(invalid(i, "nothing comes after the tiebreaker"));
+            }
+
+            if used.contains(&map.map_id) {
+                return Err(invalid(i, "map was already protected, banned or picked"));
+            }
+            used.push(map.map_id);
+
+            if tiebreakers.contains(&map.map_id) {
+                if map.map_type != MatchupMapType::Pick {
+                    return Err(invalid(i, "tiebreaker can only be picked"));
+                }
+
+                if picks != max_picks || wins.0 != wins.1 {
+                    return Err(invalid(i, "tiebreaker is only played at match point"));
+                }
+
+                tiebreaker_played = true;
+                continue;
+            }
+
+            let expected = self.step(i);
+
+            if map.map_type != expected.action {
+                return Err(invalid(
+                    i,
+                    &format!("expected {:?} by team {:?}", expected.action, expected.team),
+                ));
+            }
+
+            let team = if &map.team == team_a {
+                SequenceTeam::A
+            } else {
+                SequenceTeam::B
+            };
+
+            if team != expected.team {
+                return Err(invalid(i, &format!("not team {team:?}'s turn")));
+            }
+
+            if map.map_type == MatchupMapType::Pick {
+                picks += 1;
+
+                match map.winner {
+                    Some(MatchupTeam::Red) => wins.0 += 1,
+                    Some(MatchupTeam::Blue) => wins.1 += 1,
+                    None => {}
+                }
+
+                if picks > max_picks {
+                    return Err(invalid(i, "no picks left"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Rejects a ban made by a team that already used its `ban_count` bans
+pub fn check_bans(maps: &[MatchupMap], ban_count: i16) -> Result<()> {
+    for (i, map) in maps.iter().enumerate() {
+        if map.map_type != MatchupMapType::Ban {
+            continue;
+        }
+
+        let earlier_bans = maps[..i]
+            .iter()
+            .filter(|earlier| earlier.map_type == MatchupMapType::Ban && earlier.team == map.team)
+            .count();
+
+        if earlier_bans >= ban_count as usize {
+            return Err(invalid(i, "team has no bans left"));
+        }
+    }
+
+    Ok(())
+}
+
+fn invalid(index: usize, reason: &str) -> Error {
+    Error::InvalidPickBan(format!("map {index}: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIEBREAKER: i32 = 99;
+
+    /// Protect A, protect B, ban B, ban A, pick A
+    fn sequence() -> PickBanSequence {
+        let step = |action, team| PickBanStep { action, team };
+
+        PickBanSequence {
+            steps: vec![
+                step(MatchupMapType::Protect, SequenceTeam::A),
+                step(MatchupMapType::Protect, SequenceTeam::B),
+                step(MatchupMapType::Ban, SequenceTeam::B),
+                step(MatchupMapType::Ban, SequenceTeam::A),
+                step(MatchupMapType::Pick, SequenceTeam::A),
+            ],
+        }
+    }
+
+    struct Teams {
+        a: ObjectId,
+        b: ObjectId,
+    }
+
+    impl Teams {
+        fn new() -> Self {
+            Self {
+                a: ObjectId::new(),
+                b: ObjectId::new(),
+            }
+        }
+
+        fn entry(&self, map_id: i32, map_type: MatchupMapType, team: SequenceTeam) -> MatchupMap {
+            MatchupMap {
+                map_id,
+                map_type,
+                team: match team {
+                    SequenceTeam::A => self.a,
+                    SequenceTeam::B => self.b,
+                },
+                team_red_scores: None,
+                team_blue_scores: None,
+                winner: None,
+            }
+        }
+
+        fn pick(&self, map_id: i32, team: SequenceTeam, winner: Option<MatchupTeam>) -> MatchupMap {
+            MatchupMap {
+                winner,
+                ..self.entry(map_id, MatchupMapType::Pick, team)
+            }
+        }
+
+        /// Protects and bans in template order
+        fn opening(&self) -> Vec<MatchupMap> {
+            vec![
+                self.entry(1, MatchupMapType::Protect, SequenceTeam::A),
+                self.entry(2, MatchupMapType::Protect, SequenceTeam::B),
+                self.entry(3, MatchupMapType::Ban, SequenceTeam::B),
+                self.entry(4, MatchupMapType::Ban, SequenceTeam::A),
+            ]
+        }
+
+        /// Four regular picks, alternating from A, won as given
+        fn picks(&self, winners: [Option<MatchupTeam>; 4]) -> Vec<MatchupMap> {
+            winners
+                .into_iter()
+                .enumerate()
+                .map(|(i, winner)| {
+                    let team = if i % 2 == 0 {
+                        SequenceTeam::A
+                    } else {
+                        SequenceTeam::B
+                    };
+                    self.pick(10 + i as i32, team, winner)
+                })
+                .collect()
+        }
+    }
+
+    fn validate(teams: &Teams, maps: &[MatchupMap]) -> Result<()> {
+        sequence().validate(maps, &teams.a, 5, &[TIEBREAKER])
+    }
+
+    #[test]
+    fn extra_picks_alternate_after_the_template() {
+        let sequence = sequence();
+
+        assert_eq!(sequence.step(4).team, SequenceTeam::A);
+        assert_eq!(sequence.step(5).team, SequenceTeam::B);
+        assert_eq!(sequence.step(6).team, SequenceTeam::A);
+        assert_eq!(sequence.step(7).action, MatchupMapType::Pick);
+    }
+
+    #[test]
+    fn full_match_with_tiebreaker() {
+        let teams = Teams::new();
+        let mut maps = teams.opening();
+        maps.extend(teams.picks([
+            Some(MatchupTeam::Red),
+            Some(MatchupTeam::Blue),
+            Some(MatchupTeam::Blue),
+            Some(MatchupTeam::Red),
+        ]));
+        maps.push(teams.pick(TIEBREAKER, SequenceTeam::A, None));
+
+        assert!(validate(&teams, &maps).is_ok());
+    }
+
+    #[test]
+    fn rejects_out_of_turn_entries() {
+        let teams = Teams::new();
+
+        let wrong_team = vec![teams.entry(1, MatchupMapType::Protect, SequenceTeam::B)];
+        assert!(matches!(
+            validate(&teams, &wrong_team),
+            Err(Error::InvalidPickBan(_))
+        ));
+
+        let wrong_action = vec![teams.entry(1, MatchupMapType::Ban, SequenceTeam::A)];
+        assert!(matches!(
+            validate(&teams, &wrong_action),
+            Err(Error::InvalidPickBan(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_reused_maps() {
+        let teams = Teams::new();
+        let mut maps = teams.opening();
+        maps.push(teams.pick(1, SequenceTeam::A, None));
+
+        assert!(matches!(
+            validate(&teams, &maps),
+            Err(Error::InvalidPickBan(_))
+        ));
+    }
+
+    #[test]
+    fn tiebreaker_waits_for_every_pick() {
+        let teams = Teams::new();
+        let mut maps = teams.opening();
+        maps.extend(teams.picks([Some(MatchupTeam::Red), Some(MatchupTeam::Blue), None, None]));
+        maps.truncate(maps.len() - 1);
+        maps.push(teams.pick(TIEBREAKER, SequenceTeam::B, None));
+
+        assert!(matches!(
+            validate(&teams, &maps),
+            Err(Error::InvalidPickBan(_))
+        ));
+    }
+
+    #[test]
+    fn tiebreaker_needs_level_winners() {
+        let teams = Teams::new();
+        let mut maps = teams.opening();
+        maps.extend(teams.picks([
+            Some(MatchupTeam::Red),
+            Some(MatchupTeam::Red),
+            Some(MatchupTeam::Blue),
+            Some(MatchupTeam::Red),
+        ]));
+        maps.push(teams.pick(TIEBREAKER, SequenceTeam::A, None));
+
+        assert!(matches!(
+            validate(&teams, &maps),
+            Err(Error::InvalidPickBan(_))
+        ));
+    }
+
+    #[test]
+    fn picks_without_winner_count_for_neither_team() {
+        let teams = Teams::new();
+        let mut maps = teams.opening();
+        maps.extend(teams.picks([Some(MatchupTeam::Red), None, None, None]));
+        maps.push(teams.pick(TIEBREAKER, SequenceTeam::A, None));
+
+        assert!(matches!(
+            validate(&teams, &maps),
+            Err(Error::InvalidPickBan(_))
+        ));
+
+        maps[5].winner = Some(MatchupTeam::Blue);
+        assert!(validate(&teams, &maps).is_ok());
+    }
+
+    #[test]
+    fn nothing_after_the_tiebreaker() {
+        let teams = Teams::new();
+        let mut maps = teams.opening();
+        maps.extend(teams.picks([None; 4]));
+        maps.push(teams.pick(TIEBREAKER, SequenceTeam::A, None));
+        maps.push(teams.pick(20, SequenceTeam::A, None));
+
+        assert!(matches!(
+            validate(&teams, &maps),
+            Err(Error::InvalidPickBan(_))
+        ));
+    }
+
+    #[test]
+    fn sequence_bans_match_ban_count() {
+        assert!(sequence().check_ban_count(1).is_ok());
+        assert!(matches!(
+            sequence().check_ban_count(2),
+            Err(Error::InvalidPickBanSequence)
+        ));
+    }
+
+    #[test]
+    fn rejects_bans_past_ban_count() {
+        let teams = Teams::new();
+        let mut maps = teams.opening();
+
+        assert!(check_bans(&maps, 1).is_ok());
+
+        maps.push(teams.entry(5, MatchupMapType::Ban, SequenceTeam::B));
+        assert!(matches!(
+            check_bans(&maps, 1),
+            Err(Error::InvalidPickBan(_))
+        ));
+        assert!(check_bans(&maps, 2).is_ok());
+    }
+
+    #[test]
+    fn rejects_picks_past_best_of() {
+        let teams = Teams::new();
+        let mut maps = teams.opening();
+        maps.extend(teams.picks([None; 4]));
+
+        assert!(sequence().validate(&maps, &teams.a, 4, &[]).is_ok());
+        assert!(matches!(
+            sequence().validate(&maps, &teams.a, 3, &[]),
+            Err(Error::InvalidPickBan(_))
+        ));
+    }
+}
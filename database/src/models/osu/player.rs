@@ -0,0 +1,167 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{AbstractOsuPlayer, GameMode};
+
+/// Players refreshed by a single run of the refresh job
+const REFRESH_BATCH_SIZE: i64 = 100;
+
+/// Cached osu! profile of a player, keyed by their osu! user id
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Player {
+    #[serde(rename = "_id")]
+    pub id: i32,
+    pub username: String,
+
+    /// ISO 3166-1 alpha-2 country code
+    pub country: String,
+    pub badges: i32,
+    /// Statistics of every mode the player was fetched in
+    pub stats: Vec<PlayerStats>,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub mode: GameMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_rank: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_rank: Option<i32>,
+    pub pp: f64,
+}
+
+impl GameMode {
+    /// Ruleset name used by the osu! API
+    pub fn ruleset(&self) -> &'static str {
+        match self {
+            GameMode::Standard => "osu",
+            GameMode::Mania => "mania",
+        }
+    }
+}
+
+impl Player {
+    pub async fn fetch(db: &Database, player_id: i32) -> Result<Player> {
+        db.fetch_osu_players(&[player_id])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::UnknownPlayer)
+    }
+
+    pub async fn fetch_many(db: &Database, player_ids: &[i32]) -> Result<Vec<Player>> {
+        let players = db.fetch_osu_players(player_ids).await?;
+        Ok(players)
+    }
+
+    pub fn stats(&self, mode: &GameMode) -> Option<&PlayerStats> {
+        self.stats.iter().find(|stats| &stats.mode == mode)
+    }
+
+    pub fn global_rank(&self, mode: &GameMode) -> Option<i32> {
+        self.stats(mode)?.global_rank
+    }
+
+    /// Badge weighted seeding rank, `rank ^ (0.9937 ^ (badges ^ 2))`
+    pub fn badge_weighted_rank(&self, mode: &GameMode) -> Option<i32> {
+        let rank = self.global_rank(mode)? as f64;
+        let exponent = 0.9937f64.powi(self.badges.pow(2));
+
+        Some(rank.powf(exponent).round() as i32)
+    }
+
+    /// Fetches the players from the osu! API into the cache. Players the API fails on keep
+    /// their cached profile, so registration does not depend on the API being up
+    pub async fn refresh(
+        db: &Database,
+        api: &impl AbstractOsuApi,
+        mode: &GameMode,
+        player_ids: &[i32],
+    ) -> Result<Vec<Player>> {
+        let cached = Self::fetch_many(db, player_ids).await?;
+        let mut players = vec![];
+
+        for player_id in player_ids {
+            let cached = cached.iter().find(|player| player.id == *player_id);
+
+            match api.fetch_osu_user(*player_id, mode.ruleset()).await {
+                Ok(Some(user)) => {
+                    let player = Self::from_api(user, mode, cached);
+                    db.upsert_osu_player(&player).await?;
+                    players.push(player);
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    tracing::warn!("Failed to refresh osu! player {player_id}: {error}");
+                    players.extend(cached.cloned());
+                }
+            }
+        }
+
+        Ok(players)
+    }
+
+    /// Refreshes the players not updated for `max_age` in every mode they were fetched in.
+    /// Every attempt counts as an update, so players the API can't return don't hold the
+    /// batch up
+    pub async fn refresh_stale(
+        db: &Database,
+        api: &impl AbstractOsuApi,
+        max_age: Duration,
+    ) -> Result<usize> {
+        let stale = db
+            .fetch_osu_stale_players(&(Utc::now() - max_age), REFRESH_BATCH_SIZE)
+            .await?;
+
+        for player in &stale {
+            let mut modes = player
+                .stats
+                .iter()
+                .map(|stats| stats.mode.clone())
+                .collect::<Vec<_>>();
+            if modes.is_empty() {
+                modes.push(GameMode::Standard);
+            }
+
+            for mode in &modes {
+                Self::refresh(db, api, mode, &[player.id]).await?;
+            }
+
+            // Restricted players and API failures would otherwise stay first in line forever
+            db.touch_osu_player(player.id, &Utc::now()).await?;
+        }
+
+        Ok(stale.len())
+    }
+
+    fn from_api(user: OsuUser, mode: &GameMode, cached: Option<&Player>) -> Player {
+        let mut stats = cached
+            .map(|player| player.stats.clone())
+            .unwrap_or_default();
+        stats.retain(|stats| &stats.mode != mode);
+
+        if let Some(statistics) = user.statistics {
+            stats.push(PlayerStats {
+                mode: mode.clone(),
+                global_rank: statistics.global_rank,
+                country_rank: statistics.country_rank,
+                pp: statistics.pp,
+            });
+        }
+
+        Player {
+            id: user.id,
+            username: user.username,
+            country: user.country_code,
+            badges: user.badges.len() as i32,
+            stats,
+            updated_at: Utc::now(),
+        }
+    }
+}
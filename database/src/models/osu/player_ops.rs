@@ -0,0 +1,103 @@
+use bson::doc;
+use chrono::{DateTime, Utc};
+use mongodb::options::{FindOptions, ReplaceOptions};
+
+use vocolo_internal::*;
+
+use crate::{to_vocolo_error, Database};
+
+use super::Player;
+
+static COL: &str = "osu_players";
+
+pub trait AbstractOsuPlayer: Sync + Send {
+    async fn fetch_osu_players(&self, player_ids: &[i32]) -> Result<Vec<Player>>;
+
+    /// Players last updated before `before`, least recently updated first
+    async fn fetch_osu_stale_players(
+        &self,
+        before: &DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Player>>;
+
+    async fn upsert_osu_player(&self, player: &Player) -> Result<()>;
+
+    /// Marks the player as updated at `at` without touching their profile
+    async fn touch_osu_player(&self, player_id: i32, at: &DateTime<Utc>) -> Result<()>;
+}
+
+impl AbstractOsuPlayer for Database {
+    async fn fetch_osu_players(&self, player_ids: &[i32]) -> Result<Vec<Player>> {
+        if player_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.find(
+            COL,
+            doc! {
+                "_id": {
+                    "$in": player_ids
+                }
+            },
+        )
+        .await
+    }
+
+    async fn fetch_osu_stale_players(
+        &self,
+        before: &DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Player>> {
+        let opts = FindOptions::builder()
+            .sort(doc! { "updated_at": 1 })
+            .limit(limit)
+            .build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "updated_at": {
+                    "$lt": bson::to_bson(before)?
+                }
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn upsert_osu_player(&self, player: &Player) -> Result<()> {
+        let opts = ReplaceOptions::builder().upsert(true).build();
+
+        self.col::<Player>(COL)
+            .replace_one(
+                doc! {
+                    "_id": player.id
+                },
+                player,
+                opts,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
+    async fn touch_osu_player(&self, player_id: i32, at: &DateTime<Utc>) -> Result<()> {
+        self.col::<Player>(COL)
+            .update_one(
+                doc! {
+                    "_id": player_id
+                },
+                doc! {
+                    "$set": {
+                        "updated_at": bson::to_bson(at)?
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+}
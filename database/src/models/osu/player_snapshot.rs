@@ -0,0 +1,42 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{AbstractOsuPlayerSnapshot, Player};
+
+/// Profile of a player as it was when they joined the roster of a tournament
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PlayerSnapshot {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+    pub player: Player,
+    pub taken_at: DateTime<Utc>,
+}
+
+impl PlayerSnapshot {
+    pub async fn list(db: &Database, tournament_id: &ObjectId) -> Result<Vec<PlayerSnapshot>> {
+        let snapshots = db.fetch_osu_player_snapshots(tournament_id).await?;
+        Ok(snapshots)
+    }
+
+    /// Stores the cached profiles of players joining a roster, keeping the first snapshot of
+    /// players who rejoin
+    pub async fn take(db: &Database, tournament_id: &ObjectId, player_ids: &[i32]) -> Result<()> {
+        for player in Player::fetch_many(db, player_ids).await? {
+            db.insert_osu_player_snapshot(&PlayerSnapshot {
+                id: None,
+                tournament_id: *tournament_id,
+                player,
+                taken_at: Utc::now(),
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+}
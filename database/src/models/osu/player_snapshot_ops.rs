@@ -0,0 +1,61 @@
+use bson::{doc, oid::ObjectId};
+use mongodb::options::{FindOptions, UpdateOptions};
+
+use vocolo_internal::*;
+
+use crate::{to_vocolo_error, Database};
+
+use super::PlayerSnapshot;
+
+pub(super) static COL: &str = "osu_player_snapshots";
+
+pub trait AbstractOsuPlayerSnapshot: Sync + Send {
+    async fn fetch_osu_player_snapshots(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<PlayerSnapshot>>;
+
+    /// Inserts the snapshot unless the player already has one in the tournament
+    async fn insert_osu_player_snapshot(&self, snapshot: &PlayerSnapshot) -> Result<()>;
+}
+
+impl AbstractOsuPlayerSnapshot for Database {
+    async fn fetch_osu_player_snapshots(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<PlayerSnapshot>> {
+        let opts = FindOptions::builder().sort(doc! { "taken_at": 1 }).build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "tournament_id": tournament_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn insert_osu_player_snapshot(&self, snapshot: &PlayerSnapshot) -> Result<()> {
+        let opts = UpdateOptions::builder().upsert(true).build();
+
+        self.col::<PlayerSnapshot>(COL)
+            .update_one(
+                doc! {
+                    "tournament_id": snapshot.tournament_id,
+                    "player._id": snapshot.player.id
+                },
+                doc! {
+                    "$setOnInsert": {
+                        "player": bson::to_bson(&snapshot.player)?,
+                        "taken_at": bson::to_bson(&snapshot.taken_at)?
+                    }
+                },
+                opts,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use revolt_optional_struct::OptionalStruct;
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{AbstractOsuQualifierLobby, Round, RoundKind, Tournament, TournamentStaffRole};
+
+#[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[optional_derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
+#[optional_name = "PartialQualifierLobby"]
+#[opt_skip_serializing_none]
+#[opt_some_priority]
+pub struct QualifierLobby {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_id: Option<ObjectId>,
+
+    pub name: String,
+    pub date: DateTime<Utc>,
+    /// Teams can no longer sign up or leave past this date
+    pub signup_deadline: DateTime<Utc>,
+
+    pub capacity: i32,
+    pub teams: Vec<ObjectId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referee: Option<i32>,
+}
+
+impl QualifierLobby {
+    pub async fn create(&self, db: &Database) -> Result<ObjectId> {
+        if let Some(round_id) = &self.round_id {
+            let round = Round::fetch_in_tournament(db, round_id, &self.tournament_id).await?;
+
+            if round.kind != RoundKind::Qualifiers {
+                return Err(Error::InvalidRoundKind);
+            }
+        }
+
+        if self.signup_deadline > self.date {
+            return Err(Error::InvalidLobbySchedule);
+        }
+
+        let id = db.insert_osu_qualifier_lobby(self).await?;
+        Ok(id)
+    }
+
+    pub async fn fetch(db: &Database, lobby_id: &str) -> Result<QualifierLobby> {
+        let lobby = db
+            .fetch_osu_qualifier_lobby(lobby_id)
+            .await?
+            .ok_or(Error::UnknownQualifierLobby)?;
+        Ok(lobby)
+    }
+
+    pub async fn list(db: &Database, tournament_id: &ObjectId) -> Result<Vec<QualifierLobby>> {
+        let lobbies = db
+            .fetch_osu_tournament_qualifier_lobbies(tournament_id)
+            .await?;
+        Ok(lobbies)
+    }
+
+    pub async fn update(
+        db: &Database,
+        lobby_id: &str,
+        partial: &PartialQualifierLobby,
+    ) -> Result<()> {
+        let lobby = Self::fetch(db, lobby_id).await?;
+
+        let date = partial.date.unwrap_or(lobby.date);
+        let signup_deadline = partial.signup_deadline.unwrap_or(lobby.signup_deadline);
+        if signup_deadline > date {
+            return Err(Error::InvalidLobbySchedule);
+        }
+
+        if partial
+            .capacity
+            .is_some_and(|capacity| (capacity as usize) < lobby.teams.len())
+        {
+            return Err(Error::LobbyFull);
+        }
+
+        // With a new capacity, nothing matching means teams signed up past it meanwhile
+        db.update_osu_qualifier_lobby(lobby_id, partial)
+            .await?
+            .ok_or(match partial.capacity {
+                Some(_) => Error::LobbyFull,
+                None => Error::UnknownQualifierLobby,
+            })?;
+        Ok(())
+    }
+
+    pub async fn delete(db: &Database, lobby_id: &str) -> Result<()> {
+        db.delete_osu_qualifier_lobby(lobby_id)
+            .await?
+            .ok_or(Error::UnknownQualifierLobby)?;
+        Ok(())
+    }
+
+    /// Signs the team captained by `captain` up for the lobby, leaving any other lobby it was in
+    pub async fn sign_up(db: &Database, lobby_id: &str, captain: i32) -> Result<QualifierLobby> {
+        let lobby = Self::fetch(db, lobby_id).await?;
+        let id = lobby.id.ok_or(Error::UnknownQualifierLobby)?;
+        let team = Self::check_signup(db, &lobby, captain).await?;
+
+        if !lobby.teams.contains(&team) {
+            // Moving out of a lobby whose sign-ups are closed would be leaving it late
+            let current = Self::list(db, &lobby.tournament_id)
+                .await?
+                .into_iter()
+                .find(|other| other.teams.contains(&team));
+            if current.is_some_and(|current| current.signup_deadline < Utc::now()) {
+                return Err(Error::LobbySignupClosed);
+            }
+
+            if !db.add_osu_qualifier_lobby_team(&id, &team).await? {
+                return Err(Error::LobbyFull);
+            }
+            db.remove_osu_qualifier_lobby_team(&lobby.tournament_id, &team, Some(&id))
+                .await?;
+        }
+
+        Self::fetch(db, lobby_id).await
+    }
+
+    pub async fn leave(db: &Database, lobby_id: &str, captain: i32) -> Result<()> {
+        let lobby = Self::fetch(db, lobby_id).await?;
+        let team = Self::check_signup(db, &lobby, captain).await?;
+
+        if !lobby.teams.contains(&team) {
+            return Err(Error::NotSignedUp);
+        }
+
+        db.remove_osu_qualifier_lobby_team(&lobby.tournament_id, &team, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Assigns a referee, who must be staff of the tournament able to referee
+    pub async fn assign_referee(db: &Database, lobby_id: &str, referee: Option<i32>) -> Result<()> {
+        let lobby = Self::fetch(db, lobby_id).await?;
+
+        if let Some(referee) = referee {
+            let staff = Tournament::get_staff(db, &lobby.tournament_id.to_hex()).await?;
+            let allowed = staff.iter().any(|staff| {
+                staff.id == referee
+                    && staff.roles.iter().any(|role| {
+                        matches!(
+                            role,
+                            TournamentStaffRole::Referee | TournamentStaffRole::Organizer
+                        )
+                    })
+            });
+
+            if !allowed {
+                return Err(Error::InvalidReferee);
+            }
+        }
+
+        db.set_osu_qualifier_lobby_referee(lobby_id, referee)
+            .await?
+            .ok_or(Error::UnknownQualifierLobby)?;
+        Ok(())
+    }
+
+    /// Makes sure sign-ups are still open and returns the team captained by `captain`
+    async fn check_signup(db: &Database, lobby: &QualifierLobby, captain: i32) -> Result<ObjectId> {
+        if lobby.signup_deadline < Utc::now() {
+            return Err(Error::LobbySignupClosed);
+        }
+
+        let tournament_id = lobby.tournament_id.to_hex();
+        let tournament = Tournament::fetch(db, &tournament_id).await?;
+
+        if tournament.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let team = Tournament::fetch_captain_team(db, &tournament_id, captain).await?;
+        Ok(team.id)
+    }
+}
@@ -0,0 +1,220 @@
+use bson::{doc, oid::ObjectId, to_document, Document};
+use mongodb::options::FindOptions;
+
+use vocolo_internal::*;
+
+use crate::{str_to_oid, to_vocolo_error, Database};
+
+use super::{PartialQualifierLobby, QualifierLobby};
+
+pub(super) static COL: &str = "osu_qualifier_lobbies";
+
+pub trait AbstractOsuQualifierLobby: Sync + Send {
+    async fn fetch_osu_qualifier_lobby(&self, lobby_id: &str) -> Result<Option<QualifierLobby>>;
+
+    async fn fetch_osu_tournament_qualifier_lobbies(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<QualifierLobby>>;
+
+    async fn insert_osu_qualifier_lobby(&self, lobby: &QualifierLobby) -> Result<ObjectId>;
+
+    /// Updates the lobby, matching nothing when a new capacity is below its signed up teams
+    async fn update_osu_qualifier_lobby(
+        &self,
+        lobby_id: &str,
+        lobby: &PartialQualifierLobby,
+    ) -> Result<Option<()>>;
+
+    async fn delete_osu_qualifier_lobby(&self, lobby_id: &str) -> Result<Option<()>>;
+
+    /// Adds the team as long as the lobby has room left, returning whether it did
+    async fn add_osu_qualifier_lobby_team(
+        &self,
+        lobby_id: &ObjectId,
+        team_id: &ObjectId,
+    ) -> Result<bool>;
+
+    /// Removes the team from every lobby of the tournament but `except`
+    async fn remove_osu_qualifier_lobby_team(
+        &self,
+        tournament_id: &ObjectId,
+        team_id: &ObjectId,
+        except: Option<&ObjectId>,
+    ) -> Result<()>;
+
+    async fn set_osu_qualifier_lobby_referee(
+        &self,
+        lobby_id: &str,
+        referee: Option<i32>,
+    ) -> Result<Option<()>>;
+}
+
+impl AbstractOsuQualifierLobby for Database {
+    async fn fetch_osu_qualifier_lobby(&self, lobby_id: &str) -> Result<Option<QualifierLobby>> {
+        let lobby_oid = str_to_oid(lobby_id);
+
+        self.find_one(
+            COL,
+            doc! {
+                "_id": lobby_oid
+            },
+        )
+        .await
+    }
+
+    async fn fetch_osu_tournament_qualifier_lobbies(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<QualifierLobby>> {
+        let opts = FindOptions::builder().sort(doc! { "date": 1 }).build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "tournament_id": tournament_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn insert_osu_qualifier_lobby(&self, lobby: &QualifierLobby) -> Result<ObjectId> {
+        let id = self
+            .insert_one(COL, lobby)
+            .await?
+            .inserted_id
+            .as_object_id()
+            .unwrap();
+
+        Ok(id)
+    }
+
+    async fn update_osu_qualifier_lobby(
+        &self,
+        lobby_id: &str,
+        partial: &PartialQualifierLobby,
+    ) -> Result<Option<()>> {
+        let id = str_to_oid(lobby_id);
+
+        let mut filter = doc! {
+            "_id": id
+        };
+        if let Some(capacity) = partial.capacity {
+            filter.insert("$expr", doc! { "$lte": [{ "$size": "$teams" }, capacity] });
+        }
+
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                filter,
+                doc! {
+                    "$set": to_document(partial)?
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(()))
+    }
+
+    async fn delete_osu_qualifier_lobby(&self, lobby_id: &str) -> Result<Option<()>> {
+        let oid = str_to_oid(lobby_id);
+
+        let result = self.delete_one_by_id(COL, &oid).await?;
+
+        if result.deleted_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(()))
+    }
+
+    async fn add_osu_qualifier_lobby_team(
+        &self,
+        lobby_id: &ObjectId,
+        team_id: &ObjectId,
+    ) -> Result<bool> {
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": lobby_id,
+                    "teams": { "$ne": team_id },
+                    "$expr": { "$lt": [{ "$size": "$teams" }, "$capacity"] }
+                },
+                doc! {
+                    "$push": { "teams": team_id }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn remove_osu_qualifier_lobby_team(
+        &self,
+        tournament_id: &ObjectId,
+        team_id: &ObjectId,
+        except: Option<&ObjectId>,
+    ) -> Result<()> {
+        let mut filter = doc! {
+            "tournament_id": tournament_id,
+            "teams": team_id
+        };
+        if let Some(except) = except {
+            filter.insert("_id", doc! { "$ne": except });
+        }
+
+        self.col::<Document>(COL)
+            .update_many(
+                filter,
+                doc! {
+                    "$pull": { "teams": team_id }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
+    async fn set_osu_qualifier_lobby_referee(
+        &self,
+        lobby_id: &str,
+        referee: Option<i32>,
+    ) -> Result<Option<()>> {
+        let id = str_to_oid(lobby_id);
+
+        let update = match referee {
+            Some(referee) => doc! { "$set": { "referee": referee } },
+            None => doc! { "$unset": { "referee": 1 } },
+        };
+
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                update,
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(()))
+    }
+}
@@ -0,0 +1,105 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{
+    compute_seeding, AbstractOsuMappool, AbstractOsuQualifierScore, MatchupMapScore, Round,
+    RoundKind, Seed, SeedingMethod, Tournament,
+};
+
+/// Scores a team set on one map of the qualifier mappool
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct QualifierScore {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+    pub round_id: ObjectId,
+
+    pub team_id: ObjectId,
+    pub map_id: i32,
+
+    pub scores: Vec<MatchupMapScore>,
+}
+
+impl QualifierScore {
+    pub fn total(&self) -> u64 {
+        self.scores.iter().map(|score| score.score as u64).sum()
+    }
+
+    /// Stores the team's scores on a map of the qualifier round, replacing previous ones
+    pub async fn submit(db: &Database, round_id: &str, mut score: QualifierScore) -> Result<()> {
+        let round = Self::fetch_qualifier_round(db, round_id).await?;
+        let round_oid = round.id.ok_or(Error::UnknownRound)?;
+
+        let teams = Tournament::get_teams(db, &round.tournament_id.to_hex()).await?;
+        let team = teams
+            .iter()
+            .find(|team| team.id == score.team_id)
+            .ok_or(Error::UnknownTeam)?;
+
+        if score
+            .scores
+            .iter()
+            .any(|score| !team.players.contains(&score.player))
+        {
+            return Err(Error::NotInTeam);
+        }
+
+        if let Some(mappool) = db.fetch_osu_round_mappool(&round_oid).await? {
+            if !mappool
+                .maps
+                .iter()
+                .any(|map| map.beatmap_id == score.map_id)
+            {
+                return Err(Error::UnknownMappoolMap);
+            }
+        }
+
+        score.tournament_id = round.tournament_id;
+        score.round_id = round_oid;
+
+        db.upsert_osu_qualifier_score(&score).await?;
+        Ok(())
+    }
+
+    pub async fn list(db: &Database, round_id: &ObjectId) -> Result<Vec<QualifierScore>> {
+        let scores = db.fetch_osu_round_qualifier_scores(round_id).await?;
+        Ok(scores)
+    }
+
+    /// Seeds every team still in the tournament with qualifier scores in the round, best
+    /// seed first. Scores of withdrawn and disqualified teams do not weigh on the others
+    pub async fn seeding(
+        db: &Database,
+        round_id: &str,
+        method: SeedingMethod,
+    ) -> Result<Vec<Seed>> {
+        let round = Self::fetch_qualifier_round(db, round_id).await?;
+        let mut scores = Self::list(db, &round.id.ok_or(Error::UnknownRound)?).await?;
+
+        let teams = Tournament::get_teams(db, &round.tournament_id.to_hex())
+            .await?
+            .into_iter()
+            .filter(|team| !team.disqualified)
+            .map(|team| team.id)
+            .filter(|team_id| scores.iter().any(|score| &score.team_id == team_id))
+            .collect::<Vec<_>>();
+
+        scores.retain(|score| teams.contains(&score.team_id));
+
+        Ok(compute_seeding(&teams, &scores, method))
+    }
+
+    async fn fetch_qualifier_round(db: &Database, round_id: &str) -> Result<Round> {
+        let round = Round::fetch(db, round_id).await?;
+
+        if round.kind != RoundKind::Qualifiers {
+            return Err(Error::InvalidRoundKind);
+        }
+
+        Ok(round)
+    }
+}
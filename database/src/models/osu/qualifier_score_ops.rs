@@ -0,0 +1,64 @@
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::{FindOptions, UpdateOptions};
+
+use vocolo_internal::*;
+
+use crate::{to_vocolo_error, Database};
+
+use super::QualifierScore;
+
+pub(super) static COL: &str = "osu_qualifier_scores";
+
+pub trait AbstractOsuQualifierScore: Sync + Send {
+    async fn fetch_osu_round_qualifier_scores(
+        &self,
+        round_id: &ObjectId,
+    ) -> Result<Vec<QualifierScore>>;
+
+    /// Inserts the scores, or replaces the ones of the same team on the same map
+    async fn upsert_osu_qualifier_score(&self, score: &QualifierScore) -> Result<()>;
+}
+
+impl AbstractOsuQualifierScore for Database {
+    async fn fetch_osu_round_qualifier_scores(
+        &self,
+        round_id: &ObjectId,
+    ) -> Result<Vec<QualifierScore>> {
+        let opts = FindOptions::builder()
+            .sort(doc! { "map_id": 1, "team_id": 1 })
+            .build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "round_id": round_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn upsert_osu_qualifier_score(&self, score: &QualifierScore) -> Result<()> {
+        let opts = UpdateOptions::builder().upsert(true).build();
+
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "round_id": score.round_id,
+                    "team_id": score.team_id,
+                    "map_id": score.map_id
+                },
+                doc! {
+                    "$set": {
+                        "tournament_id": score.tournament_id,
+                        "scores": bson::to_bson(&score.scores)?
+                    }
+                },
+                opts,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+}
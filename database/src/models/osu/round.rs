@@ -0,0 +1,136 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use revolt_optional_struct::OptionalStruct;
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{
+    AbstractOsuGroup, AbstractOsuMappool, AbstractOsuMatchup, AbstractOsuRound, PickBanSequence,
+    ScoringRules,
+};
+
+#[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[optional_derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
+#[optional_name = "PartialRound"]
+#[opt_skip_serializing_none]
+#[opt_some_priority]
+pub struct Round {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+
+    pub name: String,
+    pub kind: RoundKind,
+    pub order: i32,
+
+    pub best_of: i16,
+    pub ban_count: i16,
+    /// Protect, ban and pick order enforced on the round's matchups, unchecked when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pick_ban_sequence: Option<PickBanSequence>,
+    /// Overrides the tournament's scoring rules
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<ScoringRules>,
+
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum RoundKind {
+    Qualifiers,
+    Groups,
+    Bracket,
+}
+
+impl Round {
+    pub async fn create(&self, db: &Database) -> Result<ObjectId> {
+        if self.start_date > self.end_date {
+            return Err(Error::InvalidRoundSchedule);
+        }
+
+        if let Some(sequence) = &self.pick_ban_sequence {
+            sequence.check_ban_count(self.ban_count)?;
+        }
+
+        let id = db.insert_osu_round(self).await?;
+        Ok(id)
+    }
+
+    pub async fn fetch(db: &Database, round_id: &str) -> Result<Round> {
+        let round = db
+            .fetch_osu_round(round_id)
+            .await?
+            .ok_or(Error::UnknownRound)?;
+        Ok(round)
+    }
+
+    pub async fn list(db: &Database, tournament_id: &ObjectId) -> Result<Vec<Round>> {
+        let rounds = db.fetch_osu_tournament_rounds(tournament_id).await?;
+        Ok(rounds)
+    }
+
+    pub async fn update(db: &Database, round_id: &str, partial: &PartialRound) -> Result<()> {
+        let round = Self::fetch(db, round_id).await?;
+
+        let start_date = partial.start_date.unwrap_or(round.start_date);
+        let end_date = partial.end_date.unwrap_or(round.end_date);
+        if start_date > end_date {
+            return Err(Error::InvalidRoundSchedule);
+        }
+
+        let ban_count = partial.ban_count.unwrap_or(round.ban_count);
+        if let Some(sequence) = partial
+            .pick_ban_sequence
+            .as_ref()
+            .or(round.pick_ban_sequence.as_ref())
+        {
+            sequence.check_ban_count(ban_count)?;
+        }
+
+        db.update_osu_round(round_id, partial)
+            .await?
+            .ok_or(Error::UnknownRound)?;
+        Ok(())
+    }
+
+    /// Deletes the round, refusing while matches, a mappool or groups still belong to it
+    pub async fn delete(db: &Database, round_id: &str) -> Result<()> {
+        let round = Self::fetch(db, round_id).await?;
+        let id = round.id.ok_or(Error::UnknownRound)?;
+
+        if !db.fetch_osu_round_matchups(&id).await?.is_empty()
+            || db.fetch_osu_round_mappool(&id).await?.is_some()
+            || !db.fetch_osu_round_groups(&id).await?.is_empty()
+        {
+            return Err(Error::RoundInUse);
+        }
+
+        db.delete_osu_round(round_id)
+            .await?
+            .ok_or(Error::UnknownRound)?;
+        Ok(())
+    }
+
+    /// Fetches the round and makes sure it belongs to the given tournament
+    pub async fn fetch_in_tournament(
+        db: &Database,
+        round_id: &ObjectId,
+        tournament_id: &ObjectId,
+    ) -> Result<Round> {
+        let round = Self::fetch(db, &round_id.to_hex()).await?;
+
+        if &round.tournament_id != tournament_id {
+            return Err(Error::UnknownRound);
+        }
+
+        Ok(round)
+    }
+
+    pub fn is_scheduled_at(&self, date: &DateTime<Utc>) -> bool {
+        &self.start_date <= date && date <= &self.end_date
+    }
+}
@@ -0,0 +1,101 @@
+use bson::{doc, oid::ObjectId, to_document, Document};
+use mongodb::options::FindOptions;
+
+use vocolo_internal::*;
+
+use crate::{str_to_oid, to_vocolo_error, Database};
+
+use super::{PartialRound, Round};
+
+pub(super) static COL: &str = "osu_rounds";
+
+pub trait AbstractOsuRound: Sync + Send {
+    async fn fetch_osu_round(&self, round_id: &str) -> Result<Option<Round>>;
+
+    async fn fetch_osu_tournament_rounds(&self, tournament_id: &ObjectId) -> Result<Vec<Round>>;
+
+    async fn insert_osu_round(&self, round: &Round) -> Result<ObjectId>;
+
+    async fn update_osu_round(&self, round_id: &str, round: &PartialRound) -> Result<Option<()>>;
+
+    async fn delete_osu_round(&self, round_id: &str) -> Result<Option<()>>;
+}
+
+impl AbstractOsuRound for Database {
+    async fn fetch_osu_round(&self, round_id: &str) -> Result<Option<Round>> {
+        let round_oid = str_to_oid(round_id);
+
+        self.find_one(
+            COL,
+            doc! {
+                "_id": round_oid
+            },
+        )
+        .await
+    }
+
+    async fn fetch_osu_tournament_rounds(&self, tournament_id: &ObjectId) -> Result<Vec<Round>> {
+        let opts = FindOptions::builder()
+            .sort(doc! {
+                "order": 1,
+                "start_date": 1,
+            })
+            .build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "tournament_id": tournament_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn insert_osu_round(&self, round: &Round) -> Result<ObjectId> {
+        let id = self
+            .insert_one(COL, round)
+            .await?
+            .inserted_id
+            .as_object_id()
+            .unwrap();
+
+        Ok(id)
+    }
+
+    async fn update_osu_round(&self, round_id: &str, partial: &PartialRound) -> Result<Option<()>> {
+        let id = str_to_oid(round_id);
+
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": to_document(partial)?
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(()))
+    }
+
+    async fn delete_osu_round(&self, round_id: &str) -> Result<Option<()>> {
+        let oid = str_to_oid(round_id);
+
+        let result = self.delete_one_by_id(COL, &oid).await?;
+
+        if result.deleted_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(()))
+    }
+}
@@ -0,0 +1,377 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::OsuScoreStatistics;
+
+use super::{GameMode, MatchupMapScore, MatchupTeam};
+
+/// How maps of a tournament or round are won, rounds override their tournament
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ScoringRules {
+    #[serde(default)]
+    pub win_condition: WinCondition,
+    #[serde(default)]
+    pub version: ScoringVersion,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum WinCondition {
+    /// Highest total score
+    #[default]
+    Score,
+    /// Highest average accuracy
+    Accuracy,
+    /// Highest total max combo
+    Combo,
+    /// Fewest total misses
+    Misses,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ScoringVersion {
+    ScoreV1,
+    #[default]
+    ScoreV2,
+}
+
+/// Hit counts of a score, `geki` and `katu` are the MAX and 200 judgements in mania
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ScoreJudgements {
+    #[serde(default)]
+    pub count_geki: u32,
+    #[serde(default)]
+    pub count_300: u32,
+    #[serde(default)]
+    pub count_katu: u32,
+    #[serde(default)]
+    pub count_100: u32,
+    #[serde(default)]
+    pub count_50: u32,
+    #[serde(default)]
+    pub count_miss: u32,
+}
+
+impl ScoringVersion {
+    /// Version of an osu! multiplayer game's `scoring_type`, `None` for accuracy and combo
+    pub fn from_osu(scoring_type: &str) -> Option<ScoringVersion> {
+        match scoring_type {
+            "score" => Some(ScoringVersion::ScoreV1),
+            "scorev2" => Some(ScoringVersion::ScoreV2),
+            _ => None,
+        }
+    }
+}
+
+impl From<OsuScoreStatistics> for ScoreJudgements {
+    fn from(statistics: OsuScoreStatistics) -> Self {
+        Self {
+            count_geki: statistics.count_geki,
+            count_300: statistics.count_300,
+            count_katu: statistics.count_katu,
+            count_100: statistics.count_100,
+            count_50: statistics.count_50,
+            count_miss: statistics.count_miss,
+        }
+    }
+}
+
+impl ScoreJudgements {
+    /// Accuracy between 0 and 1. ScoreV2 weights mania MAX judgements above 300s
+    pub fn accuracy(&self, mode: &GameMode, version: ScoringVersion) -> Option<f64> {
+        let (weighted, total, perfect) = match mode {
+            // geki and katu only split the 300s and 100s further in standard
+            GameMode::Standard => (
+                300 * self.count_300 + 100 * self.count_100 + 50 * self.count_50,
+                self.count_300 + self.count_100 + self.count_50 + self.count_miss,
+                300,
+            ),
+            GameMode::Mania => {
+                let max = match version {
+                    ScoringVersion::ScoreV1 => 300,
+                    ScoringVersion::ScoreV2 => 305,
+                };
+
+                (
+                    max * self.count_geki
+                        + 300 * self.count_300
+                        + 200 * self.count_katu
+                        + 100 * self.count_100
+                        + 50 * self.count_50,
+                    self.count_geki
+                        + self.count_300
+                        + self.count_katu
+                        + self.count_100
+                        + self.count_50
+                        + self.count_miss,
+                    max,
+                )
+            }
+        };
+
+        (total > 0).then(|| weighted as f64 / (perfect * total) as f64)
+    }
+}
+
+impl MatchupMapScore {
+    /// Recorded accuracy, or computed from the judgements when only those are known
+    pub fn accuracy(&self, mode: &GameMode, version: ScoringVersion) -> Option<f64> {
+        self.accuracy
+            .or_else(|| self.judgements.as_ref()?.accuracy(mode, version))
+    }
+
+    pub fn misses(&self) -> Option<u32> {
+        self.misses
+            .or_else(|| Some(self.judgements.as_ref()?.count_miss))
+    }
+}
+
+impl ScoringRules {
+    /// Team winning the map under the win condition, `None` on a tie, when a team has no
+    /// scores or when a score lacks the metric the condition compares
+    pub fn map_winner(
+        &self,
+        mode: &GameMode,
+        team_red_scores: &[MatchupMapScore],
+        team_blue_scores: &[MatchupMapScore],
+    ) -> Option<MatchupTeam> {
+        let red = self.team_metric(mode, team_red_scores)?;
+        let blue = self.team_metric(mode, team_blue_scores)?;
+
+        let ordering = match self.win_condition {
+            WinCondition::Misses => blue.partial_cmp(&red)?,
+            _ => red.partial_cmp(&blue)?,
+        };
+
+        match ordering {
+            Ordering::Greater => Some(MatchupTeam::Red),
+            Ordering::Less => Some(MatchupTeam::Blue),
+            Ordering::Equal => None,
+        }
+    }
+
+    fn team_metric(&self, mode: &GameMode, scores: &[MatchupMapScore]) -> Option<f64> {
+        let values = scores
+            .iter()
+            .map(|score| match self.win_condition {
+                WinCondition::Score => Some(score.score as f64),
+                WinCondition::Accuracy => score.accuracy(mode, self.version),
+                WinCondition::Combo => score.max_combo.map(|combo| combo as f64),
+                WinCondition::Misses => score.misses().map(|misses| misses as f64),
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if values.is_empty() {
+            return None;
+        }
+
+        let total = values.iter().sum::<f64>();
+
+        match self.win_condition {
+            WinCondition::Accuracy => Some(total / values.len() as f64),
+            _ => Some(total),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(score: u32) -> MatchupMapScore {
+        MatchupMapScore {
+            player: 1,
+            mods: None,
+            score,
+            accuracy: None,
+            max_combo: None,
+            misses: None,
+            judgements: None,
+        }
+    }
+
+    fn rules(win_condition: WinCondition) -> ScoringRules {
+        ScoringRules {
+            win_condition,
+            ..Default::default()
+        }
+    }
+
+    fn winner(
+        rules: ScoringRules,
+        red: &[MatchupMapScore],
+        blue: &[MatchupMapScore],
+    ) -> Option<MatchupTeam> {
+        rules.map_winner(&GameMode::Standard, red, blue)
+    }
+
+    #[test]
+    fn highest_total_score_wins() {
+        let rules = rules(WinCondition::Score);
+
+        assert_eq!(
+            winner(rules, &[score(300), score(400)], &[score(600)]),
+            Some(MatchupTeam::Red)
+        );
+        assert_eq!(
+            winner(rules, &[score(300)], &[score(600)]),
+            Some(MatchupTeam::Blue)
+        );
+        assert_eq!(winner(rules, &[score(500)], &[score(500)]), None);
+    }
+
+    #[test]
+    fn fewest_misses_win() {
+        let rules = rules(WinCondition::Misses);
+        let misses = |misses| MatchupMapScore {
+            misses: Some(misses),
+            ..score(0)
+        };
+
+        assert_eq!(
+            winner(rules, &[misses(2), misses(3)], &[misses(4)]),
+            Some(MatchupTeam::Blue)
+        );
+        assert_eq!(
+            winner(rules, &[misses(0)], &[misses(1)]),
+            Some(MatchupTeam::Red)
+        );
+        assert_eq!(winner(rules, &[misses(1)], &[misses(1)]), None);
+    }
+
+    #[test]
+    fn misses_fall_back_to_judgements() {
+        let judged = |count_miss| MatchupMapScore {
+            judgements: Some(ScoreJudgements {
+                count_300: 100,
+                count_miss,
+                ..Default::default()
+            }),
+            ..score(0)
+        };
+
+        assert_eq!(
+            winner(rules(WinCondition::Misses), &[judged(5)], &[judged(2)]),
+            Some(MatchupTeam::Blue)
+        );
+    }
+
+    #[test]
+    fn accuracy_is_averaged() {
+        let accuracy = |accuracy| MatchupMapScore {
+            accuracy: Some(accuracy),
+            ..score(0)
+        };
+
+        // Red averages 0.95, blue has a single 0.97
+        assert_eq!(
+            winner(
+                rules(WinCondition::Accuracy),
+                &[accuracy(0.99), accuracy(0.91)],
+                &[accuracy(0.97)]
+            ),
+            Some(MatchupTeam::Blue)
+        );
+    }
+
+    #[test]
+    fn combo_is_summed() {
+        let combo = |combo| MatchupMapScore {
+            max_combo: Some(combo),
+            ..score(0)
+        };
+
+        assert_eq!(
+            winner(
+                rules(WinCondition::Combo),
+                &[combo(300), combo(300)],
+                &[combo(500)]
+            ),
+            Some(MatchupTeam::Red)
+        );
+    }
+
+    #[test]
+    fn no_winner_when_a_side_has_no_scores() {
+        for condition in [
+            WinCondition::Score,
+            WinCondition::Accuracy,
+            WinCondition::Combo,
+            WinCondition::Misses,
+        ] {
+            let full = MatchupMapScore {
+                accuracy: Some(1.0),
+                max_combo: Some(100),
+                misses: Some(0),
+                ..score(1000)
+            };
+
+            let full = [full];
+
+            assert_eq!(winner(rules(condition), &full, &[]), None);
+            assert_eq!(winner(rules(condition), &[], &full), None);
+        }
+    }
+
+    #[test]
+    fn no_winner_when_a_score_lacks_the_metric() {
+        let combo = MatchupMapScore {
+            max_combo: Some(100),
+            ..score(0)
+        };
+
+        assert_eq!(
+            winner(
+                rules(WinCondition::Combo),
+                &[combo.clone(), score(0)],
+                &[combo]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn accuracy_from_judgements() {
+        let standard = ScoreJudgements {
+            count_300: 3,
+            count_miss: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            standard.accuracy(&GameMode::Standard, ScoringVersion::ScoreV2),
+            Some(0.75)
+        );
+
+        let mania = ScoreJudgements {
+            count_geki: 1,
+            count_300: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            mania.accuracy(&GameMode::Mania, ScoringVersion::ScoreV1),
+            Some(1.0)
+        );
+        assert_eq!(
+            mania.accuracy(&GameMode::Mania, ScoringVersion::ScoreV2),
+            Some(605.0 / 610.0)
+        );
+
+        assert_eq!(
+            ScoreJudgements::default().accuracy(&GameMode::Standard, ScoringVersion::ScoreV2),
+            None
+        );
+    }
+
+    #[test]
+    fn scoring_version_from_osu() {
+        assert_eq!(
+            ScoringVersion::from_osu("score"),
+            Some(ScoringVersion::ScoreV1)
+        );
+        assert_eq!(
+            ScoringVersion::from_osu("scorev2"),
+            Some(ScoringVersion::ScoreV2)
+        );
+        assert_eq!(ScoringVersion::from_osu("accuracy"), None);
+    }
+}
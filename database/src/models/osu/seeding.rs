@@ -0,0 +1,257 @@
+use std::collections::{BTreeSet, HashMap};
+
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use super::QualifierScore;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SeedingMethod {
+    /// Total of every score
+    SumOfScores,
+    /// Sum of how many standard deviations each score is from the map's average
+    ZSum,
+    /// Average share of the map's top score
+    Percentile,
+    /// Sum of the placements on each map, the lowest being the best
+    RankSum,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Seed {
+    pub team_id: ObjectId,
+    pub seed: i32,
+    pub value: f64,
+}
+
+/// Orders `teams` by their qualifier results, a team missing a map counting as scoring 0 on it.
+/// Ties fall back to the total score, then to the order of `teams`
+pub fn compute_seeding(
+    teams: &[ObjectId],
+    scores: &[QualifierScore],
+    method: SeedingMethod,
+) -> Vec<Seed> {
+    let maps = scores
+        .iter()
+        .map(|score| score.map_id)
+        .collect::<BTreeSet<_>>();
+
+    let totals = scores
+        .iter()
+        .map(|score| ((score.team_id, score.map_id), score.total() as f64))
+        .collect::<HashMap<_, _>>();
+
+    // Scores of every team on each map, in the order of `teams`
+    let per_map = maps
+        .iter()
+        .map(|map_id| {
+            teams
+                .iter()
+                .map(|team_id| totals.get(&(*team_id, *map_id)).copied().unwrap_or(0.0))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut values = vec![0.0; teams.len()];
+
+    for map in &per_map {
+        let contributions = match method {
+            SeedingMethod::SumOfScores => map.clone(),
+            SeedingMethod::ZSum => {
+                let mean = map.iter().sum::<f64>() / map.len() as f64;
+                let variance =
+                    map.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / map.len() as f64;
+                let deviation = variance.sqrt();
+
+                map.iter()
+                    .map(|v| {
+                        if deviation > 0.0 {
+                            (v - mean) / deviation
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect()
+            }
+            SeedingMethod::Percentile => {
+                let top = map.iter().cloned().fold(0.0, f64::max);
+
+                map.iter()
+                    .map(|v| {
+                        if top > 0.0 {
+                            v / top * 100.0 / per_map.len() as f64
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect()
+            }
+            SeedingMethod::RankSum => map
+                .iter()
+                .map(|v| (map.iter().filter(|other| *other > v).count() + 1) as f64)
+                .collect(),
+        };
+
+        for (value, contribution) in values.iter_mut().zip(contributions) {
+            *value += contribution;
+        }
+    }
+
+    let overall = teams
+        .iter()
+        .enumerate()
+        .map(|(i, _)| per_map.iter().map(|map| map[i]).sum::<f64>())
+        .collect::<Vec<_>>();
+
+    let mut order = (0..teams.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| {
+        let by_value = match method {
+            SeedingMethod::RankSum => values[a].total_cmp(&values[b]),
+            _ => values[b].total_cmp(&values[a]),
+        };
+
+        by_value
+            .then(overall[b].total_cmp(&overall[a]))
+            .then(a.cmp(&b))
+    });
+
+    order
+        .into_iter()
+        .enumerate()
+        .map(|(seed, i)| Seed {
+            team_id: teams[i],
+            seed: seed as i32 + 1,
+            value: values[i],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::osu::MatchupMapScore;
+
+    fn score(team_id: ObjectId, map_id: i32, score: u32) -> QualifierScore {
+        QualifierScore {
+            id: None,
+            tournament_id: ObjectId::new(),
+            round_id: ObjectId::new(),
+            team_id,
+            map_id,
+            scores: vec![MatchupMapScore {
+                player: 1,
+                mods: None,
+                score,
+                accuracy: None,
+                max_combo: None,
+                misses: None,
+                judgements: None,
+            }],
+        }
+    }
+
+    /// A and B play both maps, C only the first
+    fn qualifiers() -> (Vec<ObjectId>, Vec<QualifierScore>) {
+        let teams = (0..3).map(|_| ObjectId::new()).collect::<Vec<_>>();
+        let scores = vec![
+            score(teams[0], 1, 500),
+            score(teams[0], 2, 300),
+            score(teams[1], 1, 400),
+            score(teams[1], 2, 600),
+            score(teams[2], 1, 100),
+        ];
+
+        (teams, scores)
+    }
+
+    fn seeded(seeds: &[Seed]) -> Vec<(ObjectId, i32, f64)> {
+        seeds
+            .iter()
+            .map(|seed| (seed.team_id, seed.seed, seed.value))
+            .collect()
+    }
+
+    #[test]
+    fn sum_of_scores_counts_missing_maps_as_zero() {
+        let (teams, scores) = qualifiers();
+
+        assert_eq!(
+            seeded(&compute_seeding(
+                &teams,
+                &scores,
+                SeedingMethod::SumOfScores
+            )),
+            vec![
+                (teams[1], 1, 1000.0),
+                (teams[0], 2, 800.0),
+                (teams[2], 3, 100.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn percentile_averages_share_of_top_score() {
+        let (teams, scores) = qualifiers();
+
+        assert_eq!(
+            seeded(&compute_seeding(&teams, &scores, SeedingMethod::Percentile)),
+            vec![
+                (teams[1], 1, 90.0),
+                (teams[0], 2, 75.0),
+                (teams[2], 3, 10.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn rank_sum_prefers_lowest_and_breaks_ties_on_total() {
+        let (teams, scores) = qualifiers();
+
+        // A and B both place first once and second once, B scored more overall
+        assert_eq!(
+            seeded(&compute_seeding(&teams, &scores, SeedingMethod::RankSum)),
+            vec![(teams[1], 1, 3.0), (teams[0], 2, 3.0), (teams[2], 3, 6.0)]
+        );
+    }
+
+    #[test]
+    fn z_sum_measures_deviations_from_the_average() {
+        let teams = vec![ObjectId::new(), ObjectId::new()];
+        let scores = vec![score(teams[0], 1, 100), score(teams[1], 1, 300)];
+
+        assert_eq!(
+            seeded(&compute_seeding(&teams, &scores, SeedingMethod::ZSum)),
+            vec![(teams[1], 1, 1.0), (teams[0], 2, -1.0)]
+        );
+    }
+
+    #[test]
+    fn full_ties_keep_team_order() {
+        let teams = vec![ObjectId::new(), ObjectId::new()];
+        let scores = vec![score(teams[1], 1, 200), score(teams[0], 1, 200)];
+
+        for method in [
+            SeedingMethod::SumOfScores,
+            SeedingMethod::ZSum,
+            SeedingMethod::Percentile,
+            SeedingMethod::RankSum,
+        ] {
+            let seeds = compute_seeding(&teams, &scores, method);
+            assert_eq!(seeds[0].team_id, teams[0]);
+            assert_eq!(seeds[1].team_id, teams[1]);
+        }
+    }
+
+    #[test]
+    fn teams_without_scores_are_seeded_last() {
+        let teams = vec![ObjectId::new(), ObjectId::new()];
+        let scores = vec![score(teams[1], 1, 200)];
+
+        let seeds = compute_seeding(&teams, &scores, SeedingMethod::SumOfScores);
+
+        assert_eq!(
+            seeded(&seeds),
+            vec![(teams[1], 1, 200.0), (teams[0], 2, 0.0)]
+        );
+    }
+}
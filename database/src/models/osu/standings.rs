@@ -0,0 +1,249 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use super::Matchup;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct Standing {
+    pub team_id: ObjectId,
+    pub position: i32,
+
+    pub played: i32,
+    pub wins: i32,
+    pub losses: i32,
+
+    pub map_wins: i32,
+    pub map_losses: i32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tiebreaker {
+    MatchWins,
+    MapDifferential,
+    /// Match wins among the teams still tied
+    HeadToHead,
+}
+
+impl Tiebreaker {
+    pub fn defaults() -> Vec<Tiebreaker> {
+        vec![
+            Tiebreaker::MatchWins,
+            Tiebreaker::MapDifferential,
+            Tiebreaker::HeadToHead,
+        ]
+    }
+}
+
+/// Ranks `teams` on the decided matchups, applying `tiebreakers` in order and falling
+/// back to the order of `teams`
+pub fn compute_standings(
+    teams: &[ObjectId],
+    matchups: &[Matchup],
+    tiebreakers: &[Tiebreaker],
+) -> Vec<Standing> {
+    let mut standings = teams
+        .iter()
+        .map(|team_id| Standing {
+            team_id: *team_id,
+            position: 0,
+            played: 0,
+            wins: 0,
+            losses: 0,
+            map_wins: 0,
+            map_losses: 0,
+        })
+        .collect::<Vec<_>>();
+
+    let decided = matchups
+        .iter()
+        .filter_map(|matchup| {
+            let result = matchup.result.as_ref()?;
+            let winner = matchup.team(result.winner)?;
+            let loser = matchup.team(result.winner.opponent());
+            Some((matchup, result, winner, loser))
+        })
+        .collect::<Vec<_>>();
+
+    for (matchup, result, _, _) in &decided {
+        for (team, scored, conceded) in [
+            (
+                matchup.team_red,
+                result.team_red_score,
+                result.team_blue_score,
+            ),
+            (
+                matchup.team_blue,
+                result.team_blue_score,
+                result.team_red_score,
+            ),
+        ] {
+            let Some(standing) = standings
+                .iter_mut()
+                .find(|standing| Some(standing.team_id) == team)
+            else {
+                continue;
+            };
+
+            standing.played += 1;
+            standing.map_wins += scored as i32;
+            standing.map_losses += conceded as i32;
+
+            if team == matchup.team(result.winner) {
+                standing.wins += 1;
+            } else {
+                standing.losses += 1;
+            }
+        }
+    }
+
+    // Split the table into buckets of tied teams, refining them with each tiebreaker
+    let mut buckets = vec![(0..standings.len()).collect::<Vec<_>>()];
+
+    for tiebreaker in tiebreakers {
+        buckets = buckets
+            .into_iter()
+            .flat_map(|bucket| {
+                let key = |i: usize| -> i32 {
+                    let standing = &standings[i];
+                    match tiebreaker {
+                        Tiebreaker::MatchWins => standing.wins,
+                        Tiebreaker::MapDifferential => standing.map_wins - standing.map_losses,
+                        Tiebreaker::HeadToHead => decided
+                            .iter()
+                            .filter(|(_, _, winner, loser)| {
+                                *winner == standing.team_id
+                                    && loser.is_some_and(|loser| {
+                                        bucket.iter().any(|&j| standings[j].team_id == loser)
+                                    })
+                            })
+                            .count() as i32,
+                    }
+                };
+
+                let mut keyed = bucket.iter().map(|&i| (i, key(i))).collect::<Vec<_>>();
+                keyed.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+                let mut split: Vec<Vec<usize>> = vec![];
+                let mut last = None;
+                for (i, key) in keyed {
+                    if last != Some(key) {
+                        split.push(vec![]);
+                        last = Some(key);
+                    }
+                    split.last_mut().unwrap().push(i);
+                }
+
+                split
+            })
+            .collect();
+    }
+
+    let mut ranked = buckets
+        .into_iter()
+        .flatten()
+        .map(|i| standings[i].clone())
+        .collect::<Vec<_>>();
+
+    for (position, standing) in ranked.iter_mut().enumerate() {
+        standing.position = position as i32 + 1;
+    }
+
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::osu::{MatchupResult, MatchupResultKind, MatchupTeam};
+
+    fn matchup(red: ObjectId, blue: ObjectId, red_score: i16, blue_score: i16) -> Matchup {
+        Matchup {
+            id: Some(ObjectId::new()),
+            tournament_id: ObjectId::new(),
+            round_id: None,
+            date: Utc::now(),
+            team_red: Some(red),
+            team_blue: Some(blue),
+            first_team: None,
+            maps: vec![],
+            bracket: None,
+            group: None,
+            winner_to: None,
+            loser_to: None,
+            red_from: None,
+            blue_from: None,
+            result: Some(MatchupResult {
+                winner: if red_score > blue_score {
+                    MatchupTeam::Red
+                } else {
+                    MatchupTeam::Blue
+                },
+                kind: MatchupResultKind::Played,
+                team_red_score: red_score,
+                team_blue_score: blue_score,
+            }),
+        }
+    }
+
+    fn order(standings: &[Standing]) -> Vec<ObjectId> {
+        standings.iter().map(|standing| standing.team_id).collect()
+    }
+
+    #[test]
+    fn ranks_on_match_wins_and_counts_maps() {
+        let (a, b, c) = (ObjectId::new(), ObjectId::new(), ObjectId::new());
+        let matchups = [
+            matchup(c, a, 3, 1),
+            matchup(c, b, 3, 2),
+            matchup(b, a, 3, 0),
+        ];
+
+        let standings = compute_standings(&[a, b, c], &matchups, &Tiebreaker::defaults());
+
+        assert_eq!(order(&standings), vec![c, b, a]);
+        assert_eq!(
+            standings[1],
+            Standing {
+                team_id: b,
+                position: 2,
+                played: 2,
+                wins: 1,
+                losses: 1,
+                map_wins: 5,
+                map_losses: 3,
+            }
+        );
+        assert_eq!(standings[2].position, 3);
+    }
+
+    #[test]
+    fn tiebreakers_apply_in_order() {
+        let (a, b, c) = (ObjectId::new(), ObjectId::new(), ObjectId::new());
+        // A and B win once each, A beat B but B has the better map differential
+        let matchups = [matchup(a, b, 3, 2), matchup(b, c, 3, 0)];
+
+        let by_maps = compute_standings(&[b, a, c], &matchups, &Tiebreaker::defaults());
+        assert_eq!(order(&by_maps), vec![b, a, c]);
+
+        let by_head_to_head = compute_standings(
+            &[b, a, c],
+            &matchups,
+            &[Tiebreaker::MatchWins, Tiebreaker::HeadToHead],
+        );
+        assert_eq!(order(&by_head_to_head), vec![a, b, c]);
+    }
+
+    #[test]
+    fn undecided_matchups_keep_team_order() {
+        let (a, b) = (ObjectId::new(), ObjectId::new());
+        let mut undecided = matchup(b, a, 3, 0);
+        undecided.result = None;
+
+        let standings = compute_standings(&[a, b], &[undecided], &Tiebreaker::defaults());
+
+        assert_eq!(order(&standings), vec![a, b]);
+        assert!(standings.iter().all(|standing| standing.played == 0));
+    }
+}
@@ -0,0 +1,282 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{
+    AbstractOsuSubstitution, AbstractOsuTournament, PlayerSnapshot, Tournament, TournamentTeam,
+};
+
+/// Roster change requested by a captain once rosters are locked, only applied when an
+/// organizer approves it
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct Substitution {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+    pub team_id: ObjectId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_out: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_in: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    pub requested_by: i32,
+    pub status: SubstitutionStatus,
+    pub created_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SubstitutionStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+impl Substitution {
+    pub async fn fetch(db: &Database, substitution_id: &str) -> Result<Substitution> {
+        let substitution = db
+            .fetch_osu_substitution(substitution_id)
+            .await?
+            .ok_or(Error::UnknownSubstitution)?;
+        Ok(substitution)
+    }
+
+    pub async fn list(db: &Database, tournament_id: &ObjectId) -> Result<Vec<Substitution>> {
+        let substitutions = db.fetch_osu_tournament_substitutions(tournament_id).await?;
+        Ok(substitutions)
+    }
+
+    /// Requests swapping `player_out` for `player_in` on the team captained by `captain`,
+    /// either may be left out to only remove or add a player
+    pub async fn request(
+        db: &Database,
+        tournament_id: &str,
+        team_id: &ObjectId,
+        captain: i32,
+        player_out: Option<i32>,
+        player_in: Option<i32>,
+        reason: Option<String>,
+    ) -> Result<Substitution> {
+        let tournament = Tournament::fetch(db, tournament_id).await?;
+        let tournament_id = tournament.id.ok_or(Error::UnknownTournament)?;
+
+        if tournament.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let team = Tournament::fetch_captain_team(db, &tournament_id.to_hex(), captain).await?;
+        if &team.id != team_id {
+            return Err(Error::NotTeamCaptain);
+        }
+
+        if db.check_osu_team_substitution_pending(team_id).await? {
+            return Err(Error::AlreadyExists(
+                "substitution already pending".to_string(),
+            ));
+        }
+
+        let mut substitution = Substitution {
+            id: None,
+            tournament_id,
+            team_id: team.id,
+            player_out,
+            player_in,
+            reason,
+            requested_by: captain,
+            status: SubstitutionStatus::Pending,
+            created_at: Utc::now(),
+            resolved_by: None,
+        };
+
+        substitution.validate(db, &tournament, &team).await?;
+
+        substitution.id = Some(db.insert_osu_substitution(&substitution).await?);
+
+        Ok(substitution)
+    }
+
+    /// Makes sure the swap still leaves a valid roster, run again on approval as the
+    /// roster or registrations may have changed since the request
+    async fn validate(
+        &self,
+        db: &Database,
+        tournament: &Tournament,
+        team: &TournamentTeam,
+    ) -> Result<()> {
+        if (self.player_out.is_none() && self.player_in.is_none())
+            || self.player_out == self.player_in
+        {
+            return Err(Error::InvalidSubstitution);
+        }
+
+        let mut size = team.players.len();
+
+        if let Some(player_out) = self.player_out {
+            if !team.players.contains(&player_out) {
+                return Err(Error::NotInTeam);
+            }
+
+            if team.captain == player_out {
+                return Err(Error::CaptainCannotLeave);
+            }
+
+            size -= 1;
+        }
+
+        if let Some(player_in) = self.player_in {
+            let tournament_id = self.tournament_id.to_hex();
+
+            let registered = db
+                .check_osu_tournament_players_exist(&tournament_id, &[player_in])
+                .await?;
+            if !registered.is_empty() {
+                return Err(Error::AlreadyRegistered);
+            }
+
+            Tournament::check_players_not_staff(db, &tournament_id, &[player_in]).await?;
+            tournament.check_eligibility(db, &[player_in]).await?;
+
+            size += 1;
+        }
+
+        tournament.check_team_size(size)
+    }
+
+    /// Applies a pending substitution to the roster
+    pub async fn approve(
+        db: &Database,
+        tournament_id: &ObjectId,
+        substitution_id: &str,
+        organizer: i32,
+    ) -> Result<Substitution> {
+        let mut substitution = Self::fetch_pending(db, tournament_id, substitution_id).await?;
+        let id = substitution.id.ok_or(Error::UnknownSubstitution)?;
+
+        let tournament_hex = tournament_id.to_hex();
+        let tournament = Tournament::fetch(db, &tournament_hex).await?;
+
+        if tournament.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let team = Tournament::fetch_team(db, &tournament_hex, &substitution.team_id).await?;
+        substitution.validate(db, &tournament, &team).await?;
+
+        // Claim the substitution before touching the roster so two concurrent approvals
+        // can't both apply it
+        Self::resolve(db, &id, SubstitutionStatus::Approved, organizer).await?;
+
+        if let Err(err) = substitution.apply(db, &tournament, &team.id).await {
+            db.reopen_osu_substitution(&id).await?;
+            return Err(err);
+        }
+
+        // The swap is already applied, a missing snapshot should not fail the approval
+        if let Some(player_in) = substitution.player_in {
+            if let Err(error) = PlayerSnapshot::take(db, tournament_id, &[player_in]).await {
+                tracing::warn!(
+                    "Failed to snapshot player {player_in} subbed into team {}: {error}",
+                    team.id
+                );
+            }
+        }
+
+        substitution.status = SubstitutionStatus::Approved;
+        substitution.resolved_by = Some(organizer);
+        Ok(substitution)
+    }
+
+    pub async fn deny(
+        db: &Database,
+        tournament_id: &ObjectId,
+        substitution_id: &str,
+        organizer: i32,
+    ) -> Result<Substitution> {
+        let mut substitution = Self::fetch_pending(db, tournament_id, substitution_id).await?;
+        let id = substitution.id.ok_or(Error::UnknownSubstitution)?;
+
+        Self::resolve(db, &id, SubstitutionStatus::Denied, organizer).await?;
+
+        substitution.status = SubstitutionStatus::Denied;
+        substitution.resolved_by = Some(organizer);
+        Ok(substitution)
+    }
+
+    async fn fetch_pending(
+        db: &Database,
+        tournament_id: &ObjectId,
+        substitution_id: &str,
+    ) -> Result<Substitution> {
+        let substitution = Self::fetch(db, substitution_id).await?;
+
+        if &substitution.tournament_id != tournament_id {
+            return Err(Error::UnknownSubstitution);
+        }
+
+        if substitution.status != SubstitutionStatus::Pending {
+            return Err(Error::SubstitutionNotPending);
+        }
+
+        Ok(substitution)
+    }
+
+    /// Swaps the players on the roster, putting `player_out` back if `player_in` can't join
+    async fn apply(
+        &self,
+        db: &Database,
+        tournament: &Tournament,
+        team_id: &ObjectId,
+    ) -> Result<()> {
+        let tournament_id = tournament.id.ok_or(Error::UnknownTournament)?.to_hex();
+
+        if let Some(player_out) = self.player_out {
+            db.delete_osu_tournament_team_player(&tournament_id, team_id, player_out)
+                .await?
+                .ok_or(Error::UnknownTeam)?;
+        }
+
+        if let Some(player_in) = self.player_in {
+            let joined = tournament.add_team_player(db, team_id, player_in).await;
+
+            if let Err(err) = joined {
+                if let Some(player_out) = self.player_out {
+                    db.insert_osu_tournament_team_player(
+                        &tournament_id,
+                        team_id,
+                        player_out,
+                        tournament.max_team_size as usize,
+                    )
+                    .await?;
+                }
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn resolve(
+        db: &Database,
+        substitution_id: &ObjectId,
+        status: SubstitutionStatus,
+        organizer: i32,
+    ) -> Result<()> {
+        if !db
+            .resolve_osu_substitution(substitution_id, status, organizer)
+            .await?
+        {
+            return Err(Error::SubstitutionNotPending);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::FindOptions;
+
+use vocolo_internal::*;
+
+use crate::{str_to_oid, to_vocolo_error, Database};
+
+use super::{Substitution, SubstitutionStatus};
+
+pub(super) static COL: &str = "osu_substitutions";
+
+pub trait AbstractOsuSubstitution: Sync + Send {
+    async fn fetch_osu_substitution(&self, substitution_id: &str) -> Result<Option<Substitution>>;
+
+    async fn fetch_osu_tournament_substitutions(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<Substitution>>;
+
+    async fn check_osu_team_substitution_pending(&self, team_id: &ObjectId) -> Result<bool>;
+
+    async fn insert_osu_substitution(&self, substitution: &Substitution) -> Result<ObjectId>;
+
+    /// Resolves the substitution if it is still pending, returning whether it did
+    async fn resolve_osu_substitution(
+        &self,
+        substitution_id: &ObjectId,
+        status: SubstitutionStatus,
+        resolved_by: i32,
+    ) -> Result<bool>;
+
+    /// Puts an approved substitution back to pending, returning whether it did
+    async fn reopen_osu_substitution(&self, substitution_id: &ObjectId) -> Result<bool>;
+}
+
+impl AbstractOsuSubstitution for Database {
+    async fn fetch_osu_substitution(&self, substitution_id: &str) -> Result<Option<Substitution>> {
+        let substitution_oid = str_to_oid(substitution_id);
+
+        self.find_one(
+            COL,
+            doc! {
+                "_id": substitution_oid
+            },
+        )
+        .await
+    }
+
+    async fn fetch_osu_tournament_substitutions(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<Substitution>> {
+        let opts = FindOptions::builder()
+            .sort(doc! { "created_at": -1 })
+            .build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "tournament_id": tournament_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn check_osu_team_substitution_pending(&self, team_id: &ObjectId) -> Result<bool> {
+        self.exists(
+            COL,
+            doc! {
+                "team_id": team_id,
+                "status": bson::to_bson(&SubstitutionStatus::Pending)?
+            },
+        )
+        .await
+    }
+
+    async fn insert_osu_substitution(&self, substitution: &Substitution) -> Result<ObjectId> {
+        let id = self
+            .insert_one(COL, substitution)
+            .await?
+            .inserted_id
+            .as_object_id()
+            .unwrap();
+
+        Ok(id)
+    }
+
+    async fn resolve_osu_substitution(
+        &self,
+        substitution_id: &ObjectId,
+        status: SubstitutionStatus,
+        resolved_by: i32,
+    ) -> Result<bool> {
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": substitution_id,
+                    "status": bson::to_bson(&SubstitutionStatus::Pending)?
+                },
+                doc! {
+                    "$set": {
+                        "status": bson::to_bson(&status)?,
+                        "resolved_by": resolved_by
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(result.modified_count > 0)
+    }
+
+    async fn reopen_osu_substitution(&self, substitution_id: &ObjectId) -> Result<bool> {
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": substitution_id,
+                    "status": bson::to_bson(&SubstitutionStatus::Approved)?
+                },
+                doc! {
+                    "$set": {
+                        "status": bson::to_bson(&SubstitutionStatus::Pending)?
+                    },
+                    "$unset": {
+                        "resolved_by": ""
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(result.modified_count > 0)
+    }
+}
@@ -0,0 +1,235 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use vocolo_internal::*;
+
+use crate::Database;
+
+use super::{AbstractOsuTeamInvite, AbstractOsuTournament, PlayerSnapshot, Tournament};
+
+/// Invitation from a team captain, the player only joins the roster once accepted
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct TeamInvite {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+    pub team_id: ObjectId,
+
+    pub player_id: i32,
+    pub invited_by: i32,
+
+    pub status: InviteStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InviteStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Revoked,
+}
+
+impl TeamInvite {
+    pub fn new(
+        tournament_id: ObjectId,
+        team_id: ObjectId,
+        invited_by: i32,
+        player_id: i32,
+    ) -> Self {
+        Self {
+            id: None,
+            tournament_id,
+            team_id,
+            player_id,
+            invited_by,
+            status: InviteStatus::Pending,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub async fn fetch(db: &Database, invite_id: &str) -> Result<TeamInvite> {
+        let invite = db
+            .fetch_osu_team_invite(invite_id)
+            .await?
+            .ok_or(Error::UnknownInvite)?;
+        Ok(invite)
+    }
+
+    pub async fn list_for_team(db: &Database, team_id: &ObjectId) -> Result<Vec<TeamInvite>> {
+        let invites = db.fetch_osu_team_invites(team_id).await?;
+        Ok(invites)
+    }
+
+    /// Invites still awaiting an answer from the player
+    pub async fn list_pending_for_player(db: &Database, player_id: i32) -> Result<Vec<TeamInvite>> {
+        let invites = db.fetch_osu_player_pending_invites(player_id).await?;
+        Ok(invites)
+    }
+
+    pub async fn count_pending_for_team(db: &Database, team_id: &ObjectId) -> Result<usize> {
+        let pending = db.count_osu_team_pending_invites(team_id).await?;
+        Ok(pending as usize)
+    }
+
+    /// Invites `player_id` to the team captained by `captain`, as long as the roster and its
+    /// pending invites stay within the maximum team size
+    pub async fn create(
+        db: &Database,
+        tournament_id: &str,
+        team_id: &ObjectId,
+        captain: i32,
+        player_id: i32,
+    ) -> Result<TeamInvite> {
+        let tournament = Tournament::fetch(db, tournament_id).await?;
+        let tournament_id = tournament.id.ok_or(Error::UnknownTournament)?;
+
+        tournament.check_roster_unlocked()?;
+
+        let team = Tournament::fetch_captain_team(db, &tournament_id.to_hex(), captain).await?;
+        if &team.id != team_id {
+            return Err(Error::NotTeamCaptain);
+        }
+
+        let pending = Self::count_pending_for_team(db, team_id).await?;
+        if team.players.len() + pending + 1 > tournament.max_team_size as usize {
+            return Err(Error::TeamTooLarge);
+        }
+
+        Self::check_invitable(db, &tournament_id, team_id, &[player_id]).await?;
+        tournament.check_eligibility(db, &[player_id]).await?;
+
+        let mut invite = Self::new(tournament_id, team.id, captain, player_id);
+        let ids = db.insert_osu_team_invites(&[&invite]).await?;
+        invite.id = ids.into_iter().next();
+
+        Ok(invite)
+    }
+
+    /// Makes sure the players are free to join a team and are not invited to this one already
+    pub async fn check_invitable(
+        db: &Database,
+        tournament_id: &ObjectId,
+        team_id: &ObjectId,
+        players: &[i32],
+    ) -> Result<()> {
+        let tournament_id = tournament_id.to_hex();
+
+        let registered = db
+            .check_osu_tournament_players_exist(&tournament_id, players)
+            .await?;
+        if !registered.is_empty() {
+            return Err(Error::AlreadyRegistered);
+        }
+
+        Tournament::check_players_not_staff(db, &tournament_id, players).await?;
+
+        for player_id in players {
+            if db
+                .check_osu_team_invite_pending(team_id, *player_id)
+                .await?
+            {
+                return Err(Error::AlreadyExists("player already invited".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Accepts or declines the invite on behalf of the invited player
+    pub async fn respond(
+        db: &Database,
+        invite_id: &str,
+        player_id: i32,
+        accept: bool,
+    ) -> Result<TeamInvite> {
+        let mut invite = Self::fetch(db, invite_id).await?;
+        let id = invite.id.ok_or(Error::UnknownInvite)?;
+
+        if invite.player_id != player_id {
+            return Err(Error::MissingPermission);
+        }
+
+        if invite.status != InviteStatus::Pending {
+            return Err(Error::InviteNotPending);
+        }
+
+        if !accept {
+            if !db
+                .update_osu_team_invite_status(&id, InviteStatus::Pending, InviteStatus::Declined)
+                .await?
+            {
+                return Err(Error::InviteNotPending);
+            }
+
+            invite.status = InviteStatus::Declined;
+            return Ok(invite);
+        }
+
+        let tournament_id = invite.tournament_id.to_hex();
+        let tournament = Tournament::fetch(db, &tournament_id).await?;
+
+        tournament.check_roster_unlocked()?;
+
+        let team = Tournament::get_teams(db, &tournament_id)
+            .await?
+            .into_iter()
+            .find(|team| team.id == invite.team_id)
+            .ok_or(Error::UnknownTeam)?;
+        if team.players.len() + 1 > tournament.max_team_size as usize {
+            return Err(Error::TeamTooLarge);
+        }
+
+        Tournament::check_players_not_staff(db, &tournament_id, &[player_id]).await?;
+
+        // Claim the invite before touching the roster so two concurrent accepts can't both join
+        if !db
+            .update_osu_team_invite_status(&id, InviteStatus::Pending, InviteStatus::Accepted)
+            .await?
+        {
+            return Err(Error::InviteNotPending);
+        }
+
+        let joined = tournament
+            .add_team_player(db, &invite.team_id, player_id)
+            .await;
+        if let Err(err) = joined {
+            db.update_osu_team_invite_status(&id, InviteStatus::Accepted, InviteStatus::Pending)
+                .await?;
+            return Err(err);
+        }
+
+        // The player already joined, a missing snapshot should not fail the answer
+        if let Err(error) = PlayerSnapshot::take(db, &invite.tournament_id, &[player_id]).await {
+            tracing::warn!(
+                "Failed to snapshot player {player_id} joining team {}: {error}",
+                invite.team_id
+            );
+        }
+
+        invite.status = InviteStatus::Accepted;
+        Ok(invite)
+    }
+
+    /// Withdraws a pending invite of the team captained by `captain`
+    pub async fn revoke(db: &Database, invite_id: &str, captain: i32) -> Result<()> {
+        let invite = Self::fetch(db, invite_id).await?;
+        let id = invite.id.ok_or(Error::UnknownInvite)?;
+
+        let team =
+            Tournament::fetch_captain_team(db, &invite.tournament_id.to_hex(), captain).await?;
+        if team.id != invite.team_id {
+            return Err(Error::NotTeamCaptain);
+        }
+
+        if !db
+            .update_osu_team_invite_status(&id, InviteStatus::Pending, InviteStatus::Revoked)
+            .await?
+        {
+            return Err(Error::InviteNotPending);
+        }
+
+        Ok(())
+    }
+}
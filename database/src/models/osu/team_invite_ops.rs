@@ -0,0 +1,176 @@
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::FindOptions;
+
+use vocolo_internal::*;
+
+use crate::{str_to_oid, to_vocolo_error, Database};
+
+use super::{InviteStatus, TeamInvite};
+
+pub(super) static COL: &str = "osu_team_invites";
+
+pub trait AbstractOsuTeamInvite: Sync + Send {
+    async fn fetch_osu_team_invite(&self, invite_id: &str) -> Result<Option<TeamInvite>>;
+
+    async fn fetch_osu_team_invites(&self, team_id: &ObjectId) -> Result<Vec<TeamInvite>>;
+
+    async fn fetch_osu_player_pending_invites(&self, player_id: i32) -> Result<Vec<TeamInvite>>;
+
+    async fn check_osu_team_invite_pending(
+        &self,
+        team_id: &ObjectId,
+        player_id: i32,
+    ) -> Result<bool>;
+
+    async fn count_osu_team_pending_invites(&self, team_id: &ObjectId) -> Result<u64>;
+
+    async fn insert_osu_team_invites(&self, invites: &[&TeamInvite]) -> Result<Vec<ObjectId>>;
+
+    async fn revoke_osu_team_pending_invites(&self, team_id: &ObjectId) -> Result<()>;
+
+    /// Moves the invite to `to` if it is still in `from`, returning whether it did
+    async fn update_osu_team_invite_status(
+        &self,
+        invite_id: &ObjectId,
+        from: InviteStatus,
+        to: InviteStatus,
+    ) -> Result<bool>;
+}
+
+impl AbstractOsuTeamInvite for Database {
+    async fn fetch_osu_team_invite(&self, invite_id: &str) -> Result<Option<TeamInvite>> {
+        let invite_oid = str_to_oid(invite_id);
+
+        self.find_one(
+            COL,
+            doc! {
+                "_id": invite_oid
+            },
+        )
+        .await
+    }
+
+    async fn fetch_osu_team_invites(&self, team_id: &ObjectId) -> Result<Vec<TeamInvite>> {
+        let opts = FindOptions::builder()
+            .sort(doc! { "created_at": -1 })
+            .build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "team_id": team_id
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn fetch_osu_player_pending_invites(&self, player_id: i32) -> Result<Vec<TeamInvite>> {
+        let opts = FindOptions::builder()
+            .sort(doc! { "created_at": -1 })
+            .build();
+
+        self.find_with_options(
+            COL,
+            doc! {
+                "player_id": player_id,
+                "status": bson::to_bson(&InviteStatus::Pending)?
+            },
+            opts,
+        )
+        .await
+    }
+
+    async fn check_osu_team_invite_pending(
+        &self,
+        team_id: &ObjectId,
+        player_id: i32,
+    ) -> Result<bool> {
+        self.exists(
+            COL,
+            doc! {
+                "team_id": team_id,
+                "player_id": player_id,
+                "status": bson::to_bson(&InviteStatus::Pending)?
+            },
+        )
+        .await
+    }
+
+    async fn count_osu_team_pending_invites(&self, team_id: &ObjectId) -> Result<u64> {
+        self.count(
+            COL,
+            doc! {
+                "team_id": team_id,
+                "status": bson::to_bson(&InviteStatus::Pending)?
+            },
+        )
+        .await
+    }
+
+    async fn insert_osu_team_invites(&self, invites: &[&TeamInvite]) -> Result<Vec<ObjectId>> {
+        if invites.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let result = self
+            .col::<TeamInvite>(COL)
+            .insert_many(invites.iter().copied(), None)
+            .await
+            .map_err(to_vocolo_error)?;
+
+        let mut ids = result.inserted_ids.into_iter().collect::<Vec<_>>();
+        ids.sort_by_key(|(index, _)| *index);
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|(_, id)| id.as_object_id())
+            .collect())
+    }
+
+    async fn revoke_osu_team_pending_invites(&self, team_id: &ObjectId) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_many(
+                doc! {
+                    "team_id": team_id,
+                    "status": bson::to_bson(&InviteStatus::Pending)?
+                },
+                doc! {
+                    "$set": {
+                        "status": bson::to_bson(&InviteStatus::Revoked)?
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
+    async fn update_osu_team_invite_status(
+        &self,
+        invite_id: &ObjectId,
+        from: InviteStatus,
+        to: InviteStatus,
+    ) -> Result<bool> {
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": invite_id,
+                    "status": bson::to_bson(&from)?
+                },
+                doc! {
+                    "$set": {
+                        "status": bson::to_bson(&to)?
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(result.modified_count > 0)
+    }
+}
@@ -1,4 +1,6 @@
-use bson::{doc, oid::ObjectId};
+use std::collections::HashSet;
+
+use bson::{doc, oid::ObjectId, to_bson, Bson};
 use chrono::{DateTime, Utc};
 use revolt_optional_struct::OptionalStruct;
 use serde::{Deserialize, Serialize};
@@ -7,7 +9,11 @@ use vocolo_internal::*;
 
 use crate::Database;
 
-use super::{AbstractOsuTournament, GameMode};
+use super::{
+    AbstractOsuQualifierLobby, AbstractOsuTeamInvite, AbstractOsuTournament, EligibilityRules,
+    GameMode, InviteCode, Player, PlayerEligibility, PlayerSnapshot, ScoringRules, TeamInvite,
+    TeamScreening, TournamentStage,
+};
 
 #[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[optional_derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
@@ -21,6 +27,8 @@ pub struct Tournament {
     pub name: String,
 
     pub mode: GameMode,
+    #[serde(default)]
+    pub stage: TournamentStage,
 
     #[serde(skip_serializing_if = "vocolo_models::if_false", default)]
     pub invite_only: bool,
@@ -29,9 +37,17 @@ pub struct Tournament {
 
     pub registration_start_date: DateTime<Utc>,
     pub registration_end_date: DateTime<Utc>,
+    /// Rosters freeze at this date, defaults to the end of registration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roster_lock_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eligibility: Option<EligibilityRules>,
+    /// Win condition and scoring version of the matchups, unless their round overrides them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<ScoringRules>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub teams: Option<Vec<TournamentTeam>>,
+    pub staff: Option<Vec<TournamentStaff>>,
 }
 
 #[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
@@ -40,11 +56,15 @@ pub struct Tournament {
 #[opt_skip_serializing_none]
 #[opt_some_priority]
 pub struct TournamentTeam {
+    #[serde(rename = "_id")]
     pub id: ObjectId,
     pub name: String,
 
     pub captain: i32,
     pub players: Vec<i32>,
+
+    #[serde(skip_serializing_if = "vocolo_models::if_false", default)]
+    pub disqualified: bool,
 }
 
 #[derive(OptionalStruct, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
@@ -54,7 +74,7 @@ pub struct TournamentTeam {
 #[opt_some_priority]
 pub struct TournamentStaff {
     pub id: i32,
-    pub role: TournamentStaffRole,
+    pub roles: Vec<TournamentStaffRole>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
@@ -74,7 +94,118 @@ pub enum TournamentStaffRole {
     Developer,
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TournamentRegistrationStatus {
+    Upcoming,
+    Open,
+    Closed,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TournamentSort {
+    #[default]
+    Newest,
+    Oldest,
+    Name,
+    RegistrationStart,
+    RegistrationEnd,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TournamentQuery {
+    pub mode: Option<GameMode>,
+    pub registration: Option<TournamentRegistrationStatus>,
+    pub search: Option<String>,
+    pub sort: TournamentSort,
+    pub cursor: Option<String>,
+    pub limit: i64,
+}
+
 impl Tournament {
+    /// Lists tournaments matching `query`, along with the cursor of the next page if any
+    pub async fn list(
+        db: &Database,
+        query: &TournamentQuery,
+    ) -> Result<(Vec<Tournament>, Option<String>)> {
+        // Drafts stay out of the public list until they are announced
+        let mut filters = vec![doc! { "stage": { "$ne": to_bson(&TournamentStage::Draft)? } }];
+
+        if let Some(mode) = &query.mode {
+            filters.push(doc! { "mode": to_bson(mode)? });
+        }
+
+        if let Some(status) = &query.registration {
+            let now = to_bson(&Utc::now())?;
+            filters.push(match status {
+                TournamentRegistrationStatus::Upcoming => {
+                    doc! { "registration_start_date": { "$gt": now } }
+                }
+                TournamentRegistrationStatus::Open => doc! {
+                    "stage": to_bson(&TournamentStage::Registration)?,
+                    "registration_start_date": { "$lte": &now },
+                    "registration_end_date": { "$gte": &now },
+                },
+                TournamentRegistrationStatus::Closed => {
+                    doc! { "registration_end_date": { "$lt": now } }
+                }
+            });
+        }
+
+        if let Some(search) = query.search.as_deref().filter(|s| !s.is_empty()) {
+            let pattern = escape_regex(search);
+            filters.push(doc! {
+                "$or": [
+                    { "name": { "$regex": &pattern, "$options": "i" } },
+                    { "slug": { "$regex": &pattern, "$options": "i" } },
+                ]
+            });
+        }
+
+        let (field, direction) = query.sort.field();
+
+        if let Some(cursor) = &query.cursor {
+            let last = db
+                .fetch_osu_tournament(cursor)
+                .await?
+                .ok_or(Error::InvalidCursor)?;
+            let last_id = last.id.ok_or(Error::InvalidCursor)?;
+            let op = if direction > 0 { "$gt" } else { "$lt" };
+
+            filters.push(match query.sort {
+                TournamentSort::Newest | TournamentSort::Oldest => doc! { "_id": { op: last_id } },
+                _ => {
+                    let value = query.sort.value_of(&last)?;
+                    doc! {
+                        "$or": [
+                            { field: { op: &value } },
+                            { field: &value, "_id": { op: last_id } },
+                        ]
+                    }
+                }
+            });
+        }
+
+        let filter = doc! { "$and": filters };
+
+        let mut sort = doc! { field: direction };
+        if field != "_id" {
+            sort.insert("_id", direction);
+        }
+
+        let mut tournaments = db
+            .fetch_osu_tournaments(filter, sort, query.limit + 1)
+            .await?;
+
+        let next_cursor = if tournaments.len() as i64 > query.limit {
+            tournaments.truncate(query.limit as usize);
+            tournaments.last().and_then(|t| t.id).map(|id| id.to_hex())
+        } else {
+            None
+        };
+
+        Ok((tournaments, next_cursor))
+    }
+
     pub async fn fetch(db: &Database, tournament_id: &str) -> Result<Tournament> {
         let tournament = db
             .fetch_osu_tournament(tournament_id)
@@ -84,6 +215,8 @@ impl Tournament {
     }
 
     pub async fn create(&self, db: &Database) -> Result<ObjectId> {
+        check_team_sizes(self.min_team_size, self.max_team_size)?;
+
         if db
             .check_osu_tournament_exists(doc! { "slug": &self.slug })
             .await?
@@ -102,6 +235,9 @@ impl Tournament {
         tournament_id: &str,
         partial: &PartialTournament,
     ) -> Result<()> {
+        let tournament = Self::fetch(db, tournament_id).await?;
+        tournament.check_update_allowed(partial)?;
+
         if partial.slug.is_some()
             && db
                 .check_osu_tournament_exists(doc! { "slug": &partial.slug })
@@ -118,6 +254,39 @@ impl Tournament {
         Ok(())
     }
 
+    /// Rejects changing the format once teams are locked in, or the registration dates once
+    /// registration is over
+    fn check_update_allowed(&self, partial: &PartialTournament) -> Result<()> {
+        if self.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        check_team_sizes(
+            partial.min_team_size.unwrap_or(self.min_team_size),
+            partial.max_team_size.unwrap_or(self.max_team_size),
+        )?;
+
+        let format_changed = changes(&partial.mode, &self.mode)
+            || changes(&partial.min_team_size, &self.min_team_size)
+            || changes(&partial.max_team_size, &self.max_team_size);
+
+        if format_changed && !self.stage.allows_roster_changes() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let registration_changed =
+            changes(
+                &partial.registration_start_date,
+                &self.registration_start_date,
+            ) || changes(&partial.registration_end_date, &self.registration_end_date);
+
+        if registration_changed && self.stage > TournamentStage::Registration {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        Ok(())
+    }
+
     pub async fn delete(db: &Database, tournament_id: &str) -> Result<()> {
         db.delete_osu_tournament(tournament_id)
             .await?
@@ -127,39 +296,847 @@ impl Tournament {
 
     pub async fn get_teams(db: &Database, tournament_id: &str) -> Result<Vec<TournamentTeam>> {
         let teams = db
-            .fetch_osu_tournament_teams(&tournament_id)
+            .fetch_osu_tournament_teams(tournament_id)
             .await?
             .ok_or(Error::UnknownTournament)?;
         Ok(teams)
     }
 
+    /// Checks `seeds` are distinct teams of the tournament, defaulting to registration order
+    pub async fn resolve_seeds(
+        db: &Database,
+        tournament_id: &str,
+        seeds: Option<Vec<ObjectId>>,
+    ) -> Result<Vec<ObjectId>> {
+        let team_ids = Self::get_teams(db, tournament_id)
+            .await?
+            .into_iter()
+            .filter(|team| !team.disqualified)
+            .map(|team| team.id)
+            .collect::<Vec<_>>();
+
+        let Some(seeds) = seeds else {
+            return Ok(team_ids);
+        };
+
+        let mut unique = seeds.clone();
+        unique.sort();
+        unique.dedup();
+
+        if unique.len() != seeds.len() || seeds.iter().any(|id| !team_ids.contains(id)) {
+            return Err(Error::InvalidSeeding);
+        }
+
+        Ok(seeds)
+    }
+
+    /// Team of the tournament captained by `captain`
+    pub async fn fetch_captain_team(
+        db: &Database,
+        tournament_id: &str,
+        captain: i32,
+    ) -> Result<TournamentTeam> {
+        Self::get_teams(db, tournament_id)
+            .await?
+            .into_iter()
+            .find(|team| team.captain == captain)
+            .ok_or(Error::NotTeamCaptain)
+    }
+
+    /// Registers the team with its captain as the only player, inviting `invitees` to join it.
+    /// Invite-only tournaments require an unused `invite_code`
     pub async fn register_team(
         db: &Database,
         tournament_id: &str,
         team: TournamentTeam,
+        invitees: &[i32],
+        invite_code: Option<&str>,
     ) -> Result<TournamentTeam> {
         let tournament = Self::fetch(db, tournament_id).await?;
+
+        if tournament.is_solo() {
+            return Err(Error::SoloRegistrationRequired);
+        }
+
+        Self::register(db, &tournament, team, invitees, invite_code).await
+    }
+
+    /// Registers the player of a solo tournament on their own, as an implicit single player
+    /// entry named after them
+    pub async fn register_player(
+        db: &Database,
+        tournament_id: &str,
+        player_id: i32,
+        username: &str,
+        invite_code: Option<&str>,
+    ) -> Result<TournamentTeam> {
+        let tournament = Self::fetch(db, tournament_id).await?;
+
+        if !tournament.is_solo() {
+            return Err(Error::TeamRegistrationRequired);
+        }
+
+        let entry = TournamentTeam {
+            id: ObjectId::new(),
+            name: username.to_string(),
+            captain: player_id,
+            players: vec![player_id],
+            disqualified: false,
+        };
+
+        Self::register(db, &tournament, entry, &[], invite_code).await
+    }
+
+    /// Players register on their own instead of forming teams
+    pub fn is_solo(&self) -> bool {
+        self.min_team_size == 1 && self.max_team_size == 1
+    }
+
+    /// Checks that registration is open and none of the players is registered yet, cheap
+    /// enough to run before fetching the players from the osu! API
+    pub async fn check_registration(&self, db: &Database, players: &[i32]) -> Result<()> {
+        let tournament_id = &self.id.ok_or(Error::UnknownTournament)?.to_hex();
         let now = Utc::now();
 
-        if tournament.registration_end_date < now {
+        if !self.stage.allows_registration() {
+            return Err(if self.stage < TournamentStage::Registration {
+                Error::RegistrationNotOpen
+            } else {
+                Error::RegistrationClosed
+            });
+        }
+
+        if self.registration_end_date < now {
             return Err(Error::RegistrationClosed);
-        } else if tournament.registration_start_date > now {
+        } else if self.registration_start_date > now {
             return Err(Error::RegistrationNotOpen);
         }
 
         let duplicate_players = db
-            .check_osu_tournament_players_exist(tournament_id, &team.players)
+            .check_osu_tournament_players_exist(tournament_id, players)
             .await?;
 
-        if duplicate_players.len() > 0 {
+        if !duplicate_players.is_empty() {
             return Err(Error::AlreadyRegistered);
         }
 
-        db.insert_osu_tournament_teams(tournament_id, vec![&team])
+        Ok(())
+    }
+
+    async fn register(
+        db: &Database,
+        tournament: &Tournament,
+        team: TournamentTeam,
+        invitees: &[i32],
+        invite_code: Option<&str>,
+    ) -> Result<TournamentTeam> {
+        let tournament_oid = tournament.id.ok_or(Error::UnknownTournament)?;
+        let tournament_id = &tournament_oid.to_hex();
+
+        tournament.check_registration(db, &team.players).await?;
+
+        let mut seen = HashSet::new();
+        let invitees = invitees
+            .iter()
+            .filter(|player_id| !team.players.contains(player_id) && seen.insert(**player_id))
+            .copied()
+            .collect::<Vec<_>>();
+        tournament.check_team_size(team.players.len() + invitees.len())?;
+
+        Self::check_players_not_staff(db, tournament_id, &team.players).await?;
+        TeamInvite::check_invitable(db, &tournament_oid, &team.id, &invitees).await?;
+        tournament
+            .check_eligibility(db, &[team.players.as_slice(), &invitees].concat())
             .await?;
 
+        if tournament.invite_only {
+            let code = invite_code.ok_or(Error::InviteCodeRequired)?;
+            InviteCode::redeem(db, &tournament_oid, code, &team.id).await?;
+        }
+
+        let invites = invitees
+            .iter()
+            .map(|player_id| TeamInvite::new(tournament_oid, team.id, team.captain, *player_id))
+            .collect::<Vec<_>>();
+
+        if let Err(err) = Self::store_registration(db, tournament_id, &team, &invites).await {
+            if tournament.invite_only {
+                InviteCode::release(db, &tournament_oid, &team.id).await?;
+            }
+
+            return Err(err);
+        }
+
+        // Snapshots only record the players' profiles, the team is registered without them
+        if let Err(error) = PlayerSnapshot::take(db, &tournament_oid, &team.players).await {
+            tracing::warn!(
+                "Failed to snapshot the players of team {}: {error}",
+                team.id
+            );
+        }
+
         Ok(team)
     }
+
+    /// Stores the team along with its invites, removing the team again if the invites fail
+    async fn store_registration(
+        db: &Database,
+        tournament_id: &str,
+        team: &TournamentTeam,
+        invites: &[TeamInvite],
+    ) -> Result<()> {
+        db.insert_osu_tournament_teams(tournament_id, vec![team])
+            .await?;
+
+        if let Err(err) = db
+            .insert_osu_team_invites(&invites.iter().collect::<Vec<_>>())
+            .await
+        {
+            db.revoke_osu_team_pending_invites(&team.id).await?;
+            db.delete_osu_tournament_teams(tournament_id, vec![&team.id.to_hex()])
+                .await?;
+
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Moves the tournament to `stage` if allowed from its current stage
+    pub async fn transition(
+        db: &Database,
+        tournament_id: &str,
+        stage: TournamentStage,
+    ) -> Result<Tournament> {
+        let mut tournament = Self::fetch(db, tournament_id).await?;
+
+        if !tournament.stage.can_transition_to(stage) {
+            return Err(Error::InvalidStageTransition);
+        }
+
+        let id = tournament.id.ok_or(Error::UnknownTournament)?.to_hex();
+
+        // Closing registration requires every team still in the running to have filled its roster
+        if tournament.stage == TournamentStage::Registration && stage != TournamentStage::Cancelled
+        {
+            let undersized = Self::get_teams(db, &id)
+                .await?
+                .into_iter()
+                .filter(|team| !team.disqualified)
+                .filter(|team| team.players.len() < tournament.min_team_size as usize)
+                .map(|team| team.name)
+                .collect::<Vec<_>>();
+
+            if !undersized.is_empty() {
+                return Err(Error::TeamsBelowMinimumSize(undersized.join(", ")));
+            }
+        }
+
+        db.update_osu_tournament_stage(&id, tournament.stage, stage)
+            .await?
+            .ok_or(Error::InvalidStageTransition)?;
+
+        tournament.stage = stage;
+        Ok(tournament)
+    }
+
+    /// Evaluates `players` against the eligibility rules of the tournament, in the same order
+    pub async fn screen_players(
+        &self,
+        db: &Database,
+        players: &[i32],
+    ) -> Result<Vec<PlayerEligibility>> {
+        let Some(rules) = self.eligibility.as_ref().filter(|rules| !rules.is_empty()) else {
+            return Ok(players
+                .iter()
+                .map(|player_id| PlayerEligibility {
+                    player_id: *player_id,
+                    reasons: vec![],
+                })
+                .collect());
+        };
+
+        let profiles = Player::fetch_many(db, players).await?;
+
+        Ok(players
+            .iter()
+            .map(|player_id| {
+                rules.evaluate(
+                    *player_id,
+                    profiles.iter().find(|profile| profile.id == *player_id),
+                    &self.mode,
+                )
+            })
+            .collect())
+    }
+
+    /// Rejects with the reasons of every player not meeting the eligibility rules
+    pub async fn check_eligibility(&self, db: &Database, players: &[i32]) -> Result<()> {
+        let ineligible = self
+            .screen_players(db, players)
+            .await?
+            .into_iter()
+            .filter(|eligibility| !eligibility.is_eligible())
+            .map(|eligibility| eligibility.to_string())
+            .collect::<Vec<_>>();
+
+        if !ineligible.is_empty() {
+            return Err(Error::IneligiblePlayers(ineligible.join("; ")));
+        }
+
+        Ok(())
+    }
+
+    /// Screens every player of the teams still in the tournament
+    pub async fn screen(&self, db: &Database) -> Result<Vec<TeamScreening>> {
+        let tournament_id = self.id.ok_or(Error::UnknownTournament)?.to_hex();
+        let teams = Self::get_teams(db, &tournament_id)
+            .await?
+            .into_iter()
+            .filter(|team| !team.disqualified)
+            .collect::<Vec<_>>();
+
+        let players = teams
+            .iter()
+            .flat_map(|team| team.players.iter().copied())
+            .collect::<Vec<_>>();
+        let mut eligibility = self.screen_players(db, &players).await?.into_iter();
+
+        Ok(teams
+            .into_iter()
+            .map(|team| TeamScreening {
+                team_id: team.id,
+                players: eligibility.by_ref().take(team.players.len()).collect(),
+            })
+            .collect())
+    }
+
+    /// Rosters are frozen once the stage is past them or the roster lock date has passed
+    pub fn roster_locked(&self) -> bool {
+        !self.stage.allows_roster_changes()
+            || self.roster_lock_date.unwrap_or(self.registration_end_date) <= Utc::now()
+    }
+
+    pub fn check_roster_unlocked(&self) -> Result<()> {
+        if self.roster_locked() {
+            return Err(Error::RosterLocked);
+        }
+
+        Ok(())
+    }
+
+    /// Checks a roster of `size` players fits within the tournament's team size limits
+    pub fn check_team_size(&self, size: usize) -> Result<()> {
+        if size < self.min_team_size as usize {
+            return Err(Error::TeamTooSmall);
+        }
+
+        if size > self.max_team_size as usize {
+            return Err(Error::TeamTooLarge);
+        }
+
+        Ok(())
+    }
+
+    /// Removes a player other than the captain from the team, keeping the roster and its
+    /// pending invites at or above the minimum team size
+    pub async fn remove_team_player(
+        db: &Database,
+        tournament_id: &str,
+        team_id: &ObjectId,
+        player_id: i32,
+    ) -> Result<TournamentTeam> {
+        let tournament = Self::fetch(db, tournament_id).await?;
+        let tournament_id = tournament.id.ok_or(Error::UnknownTournament)?.to_hex();
+
+        tournament.check_roster_unlocked()?;
+
+        let mut team = Self::fetch_team(db, &tournament_id, team_id).await?;
+
+        if !team.players.contains(&player_id) {
+            return Err(Error::NotInTeam);
+        }
+
+        if team.captain == player_id {
+            return Err(Error::CaptainCannotLeave);
+        }
+
+        // Pending invites may never be accepted, so only the roster counts towards the minimum
+        if team.players.len() - 1 < tournament.min_team_size as usize {
+            return Err(Error::TeamTooSmall);
+        }
+
+        db.delete_osu_tournament_team_player(&tournament_id, team_id, player_id)
+            .await?
+            .ok_or(Error::UnknownTeam)?;
+
+        team.players.retain(|id| *id != player_id);
+        Ok(team)
+    }
+
+    pub async fn fetch_team(
+        db: &Database,
+        tournament_id: &str,
+        team_id: &ObjectId,
+    ) -> Result<TournamentTeam> {
+        Self::get_teams(db, tournament_id)
+            .await?
+            .into_iter()
+            .find(|team| &team.id == team_id)
+            .ok_or(Error::UnknownTeam)
+    }
+
+    /// Adds the player to the team, as long as the team still has room for them
+    pub async fn add_team_player(
+        &self,
+        db: &Database,
+        team_id: &ObjectId,
+        player_id: i32,
+    ) -> Result<()> {
+        let tournament_id = self.id.ok_or(Error::UnknownTournament)?.to_hex();
+        let max_players = self.max_team_size as usize;
+
+        let added = db
+            .insert_osu_tournament_team_player(&tournament_id, team_id, player_id, max_players)
+            .await?;
+
+        if added.is_none() {
+            let team = Self::fetch_team(db, &tournament_id, team_id).await?;
+
+            return Err(if team.players.len() >= max_players {
+                Error::TeamTooLarge
+            } else {
+                Error::AlreadyRegistered
+            });
+        }
+
+        Ok(())
+    }
+
+    pub async fn rename_team(
+        db: &Database,
+        tournament_id: &str,
+        team_id: &ObjectId,
+        name: String,
+    ) -> Result<()> {
+        let tournament = Self::fetch(db, tournament_id).await?;
+        if tournament.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let partial = PartialTeam {
+            name: Some(name),
+            ..Default::default()
+        };
+
+        db.update_osu_tournament_team(tournament_id, team_id, &partial)
+            .await?
+            .ok_or(Error::UnknownTeam)?;
+        Ok(())
+    }
+
+    /// Hands the captaincy over to another player of the team
+    pub async fn transfer_captain(
+        db: &Database,
+        tournament_id: &str,
+        team_id: &ObjectId,
+        player_id: i32,
+    ) -> Result<()> {
+        let tournament = Self::fetch(db, tournament_id).await?;
+        if tournament.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let team = Self::fetch_team(db, tournament_id, team_id).await?;
+        if !team.players.contains(&player_id) {
+            return Err(Error::NotInTeam);
+        }
+
+        let partial = PartialTeam {
+            captain: Some(player_id),
+            ..Default::default()
+        };
+
+        db.update_osu_tournament_team(tournament_id, team_id, &partial)
+            .await?
+            .ok_or(Error::UnknownTeam)?;
+        Ok(())
+    }
+
+    /// Removes the team from the tournament, along with its pending invites and lobby sign-ups
+    pub async fn withdraw_team(
+        db: &Database,
+        tournament_id: &str,
+        team_id: &ObjectId,
+    ) -> Result<()> {
+        let tournament = Self::fetch(db, tournament_id).await?;
+        let tournament_oid = tournament.id.ok_or(Error::UnknownTournament)?;
+
+        if !tournament.stage.allows_roster_changes() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        Self::fetch_team(db, tournament_id, team_id).await?;
+
+        db.delete_osu_tournament_teams(&tournament_oid.to_hex(), vec![&team_id.to_hex()])
+            .await?
+            .ok_or(Error::UnknownTournament)?;
+        db.revoke_osu_team_pending_invites(team_id).await?;
+        db.remove_osu_qualifier_lobby_team(&tournament_oid, team_id, None)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Organizer override excluding the team from seeding while keeping its history
+    pub async fn disqualify_team(
+        db: &Database,
+        tournament_id: &str,
+        team_id: &ObjectId,
+        disqualified: bool,
+    ) -> Result<()> {
+        let tournament = Self::fetch(db, tournament_id).await?;
+        if tournament.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        let partial = PartialTeam {
+            disqualified: Some(disqualified),
+            ..Default::default()
+        };
+
+        db.update_osu_tournament_team(tournament_id, team_id, &partial)
+            .await?
+            .ok_or(Error::UnknownTeam)?;
+        Ok(())
+    }
+
+    /// Rejects staff changes once the tournament is over
+    async fn check_staff_changes_allowed(db: &Database, tournament_id: &str) -> Result<()> {
+        if Self::fetch(db, tournament_id).await?.stage.is_concluded() {
+            return Err(Error::NotAllowedInStage);
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_staff(db: &Database, tournament_id: &str) -> Result<Vec<TournamentStaff>> {
+        let staff = db
+            .fetch_osu_tournament_staff(tournament_id)
+            .await?
+            .ok_or(Error::UnknownTournament)?;
+        Ok(staff)
+    }
+
+    pub async fn add_staff(
+        db: &Database,
+        tournament_id: &str,
+        staff: TournamentStaff,
+    ) -> Result<TournamentStaff> {
+        Self::check_staff_changes_allowed(db, tournament_id).await?;
+
+        let current_staff = Self::get_staff(db, tournament_id).await?;
+
+        if current_staff.iter().any(|s| s.id == staff.id) {
+            return Err(Error::AlreadyExists("duplication on field id".to_string()));
+        }
+
+        if staff.roles.iter().any(|role| role.not_allow_to_play()) {
+            Self::check_staff_not_player(db, tournament_id, staff.id).await?;
+        }
+
+        db.insert_osu_tournament_staff(tournament_id, vec![&staff])
+            .await?
+            .ok_or(Error::AlreadyExists("duplication on field id".to_string()))?;
+
+        Ok(staff)
+    }
+
+    pub async fn update_staff(
+        db: &Database,
+        tournament_id: &str,
+        staff_id: i32,
+        roles: Vec<TournamentStaffRole>,
+    ) -> Result<TournamentStaff> {
+        Self::check_staff_changes_allowed(db, tournament_id).await?;
+
+        let current_staff = Self::get_staff(db, tournament_id).await?;
+
+        if !current_staff.iter().any(|s| s.id == staff_id) {
+            return Err(Error::UnknownTournamentStaff);
+        }
+
+        if roles.iter().any(|role| role.not_allow_to_play()) {
+            Self::check_staff_not_player(db, tournament_id, staff_id).await?;
+        }
+
+        check_keeps_organizer(&current_staff, staff_id, &roles)?;
+
+        db.update_osu_tournament_staff_roles(tournament_id, staff_id, &roles)
+            .await?;
+
+        Ok(TournamentStaff {
+            id: staff_id,
+            roles,
+        })
+    }
+
+    pub async fn remove_staff(db: &Database, tournament_id: &str, staff_id: i32) -> Result<()> {
+        Self::check_staff_changes_allowed(db, tournament_id).await?;
+
+        let current_staff = Self::get_staff(db, tournament_id).await?;
+        check_keeps_organizer(&current_staff, staff_id, &[])?;
+
+        let removed = db
+            .delete_osu_tournament_staff(tournament_id, vec![staff_id])
+            .await?
+            .ok_or(Error::UnknownTournament)?;
+
+        if removed == 0 {
+            return Err(Error::UnknownTournamentStaff);
+        }
+
+        Ok(())
+    }
+
+    /// Puts the staff member back on the roles they held before, off the roster when they
+    /// held none. Undoes a roster change whose permissions failed to sync, so it skips the
+    /// checks the change already passed
+    pub async fn restore_staff(
+        db: &Database,
+        tournament_id: &str,
+        staff_id: i32,
+        roles: &[TournamentStaffRole],
+    ) -> Result<()> {
+        if roles.is_empty() {
+            db.delete_osu_tournament_staff(tournament_id, vec![staff_id])
+                .await?;
+            return Ok(());
+        }
+
+        let updated = db
+            .update_osu_tournament_staff_roles(tournament_id, staff_id, roles)
+            .await?;
+
+        if updated.is_none() {
+            let staff = TournamentStaff {
+                id: staff_id,
+                roles: roles.to_vec(),
+            };
+            db.insert_osu_tournament_staff(tournament_id, vec![&staff])
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Gives `role` to the user, adding them to the staff if they are not yet. Returns the
+    /// roles they held before along with the updated staff member
+    pub async fn grant_staff_role(
+        db: &Database,
+        tournament_id: &str,
+        staff_id: i32,
+        role: TournamentStaffRole,
+    ) -> Result<(Vec<TournamentStaffRole>, TournamentStaff)> {
+        let current = Self::get_staff(db, tournament_id)
+            .await?
+            .into_iter()
+            .find(|s| s.id == staff_id);
+
+        let Some(current) = current else {
+            let staff = TournamentStaff {
+                id: staff_id,
+                roles: vec![role],
+            };
+            return Ok((vec![], Self::add_staff(db, tournament_id, staff).await?));
+        };
+
+        if current.roles.contains(&role) {
+            return Ok((current.roles.clone(), current));
+        }
+
+        let mut roles = current.roles.clone();
+        roles.push(role);
+
+        let staff = Self::update_staff(db, tournament_id, staff_id, roles).await?;
+        Ok((current.roles, staff))
+    }
+
+    /// Takes `role` away from the staff member, removing them from the staff once they hold
+    /// no role anymore. Returns the roles they held before
+    pub async fn revoke_staff_role(
+        db: &Database,
+        tournament_id: &str,
+        staff_id: i32,
+        role: &TournamentStaffRole,
+    ) -> Result<Vec<TournamentStaffRole>> {
+        let current = Self::get_staff(db, tournament_id)
+            .await?
+            .into_iter()
+            .find(|s| s.id == staff_id)
+            .ok_or(Error::UnknownTournamentStaff)?;
+
+        let roles = current
+            .roles
+            .iter()
+            .filter(|r| *r != role)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if roles.len() == current.roles.len() {
+            return Ok(current.roles);
+        }
+
+        if roles.is_empty() {
+            Self::remove_staff(db, tournament_id, staff_id).await?;
+        } else {
+            Self::update_staff(db, tournament_id, staff_id, roles).await?;
+        }
+
+        Ok(current.roles)
+    }
+
+    /// Grants the relation tuples of every staff role that is missing one, so tournaments
+    /// created before permissions were enforced keep their organizers. Returns how many
+    /// tuples were granted
+    pub async fn backfill_staff_permissions(
+        db: &Database,
+        permissions: &Permissions,
+    ) -> Result<usize> {
+        let mut granted = 0;
+
+        for tournament in db.fetch_osu_staffed_tournaments().await? {
+            let Some(id) = tournament.id else {
+                continue;
+            };
+
+            for staff in tournament.staff.unwrap_or_default() {
+                for role in staff.roles {
+                    let tuple = RelationTuple::new(
+                        Namespace::Tournament,
+                        &id.to_hex(),
+                        role.into(),
+                        staff.id,
+                    );
+
+                    if !permissions.check(&tuple).await? {
+                        permissions.grant(&tuple).await?;
+                        granted += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(granted)
+    }
+
+    /// Rejects any of `players` holding a staff role that does not allow playing
+    pub async fn check_players_not_staff(
+        db: &Database,
+        tournament_id: &str,
+        players: &[i32],
+    ) -> Result<()> {
+        let staff = Self::get_staff(db, tournament_id).await?;
+
+        if staff
+            .iter()
+            .any(|s| players.contains(&s.id) && !s.allowed_to_play())
+        {
+            return Err(Error::StaffNotAllowedToPlay);
+        }
+
+        Ok(())
+    }
+
+    /// Rejects a staff assignment for a player already registered in a team
+    pub async fn check_staff_not_player(
+        db: &Database,
+        tournament_id: &str,
+        staff_id: i32,
+    ) -> Result<()> {
+        let registered = db
+            .check_osu_tournament_players_exist(tournament_id, &[staff_id])
+            .await?;
+
+        if !registered.is_empty() {
+            return Err(Error::PlayerNotAllowedAsStaff);
+        }
+
+        Ok(())
+    }
+}
+
+/// Rejects giving `staff_id` the given roles when it would leave no organizer
+fn check_keeps_organizer(
+    staff: &[TournamentStaff],
+    staff_id: i32,
+    roles: &[TournamentStaffRole],
+) -> Result<()> {
+    let has_organizer =
+        |roles: &[TournamentStaffRole]| roles.contains(&TournamentStaffRole::Organizer);
+
+    let keeps_organizer = has_organizer(roles)
+        || staff
+            .iter()
+            .any(|s| s.id != staff_id && has_organizer(&s.roles));
+
+    if !keeps_organizer {
+        return Err(Error::LastOrganizer);
+    }
+
+    Ok(())
+}
+
+impl TournamentSort {
+    fn field(&self) -> (&'static str, i32) {
+        match self {
+            TournamentSort::Newest => ("_id", -1),
+            TournamentSort::Oldest => ("_id", 1),
+            TournamentSort::Name => ("name", 1),
+            TournamentSort::RegistrationStart => ("registration_start_date", 1),
+            TournamentSort::RegistrationEnd => ("registration_end_date", 1),
+        }
+    }
+
+    fn value_of(&self, tournament: &Tournament) -> Result<Bson> {
+        let value = match self {
+            TournamentSort::Newest | TournamentSort::Oldest => to_bson(&tournament.id)?,
+            TournamentSort::Name => to_bson(&tournament.name)?,
+            TournamentSort::RegistrationStart => to_bson(&tournament.registration_start_date)?,
+            TournamentSort::RegistrationEnd => to_bson(&tournament.registration_end_date)?,
+        };
+        Ok(value)
+    }
+}
+
+fn check_team_sizes(min_team_size: i16, max_team_size: i16) -> Result<()> {
+    if min_team_size > max_team_size {
+        return Err(Error::InvalidTeamSizes);
+    }
+
+    Ok(())
+}
+
+/// Whether a partial update sets `value` to something other than `current`
+fn changes<T: PartialEq>(value: &Option<T>, current: &T) -> bool {
+    value.as_ref().is_some_and(|value| value != current)
+}
+
+fn escape_regex(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl TournamentStaff {
+    pub fn allowed_to_play(&self) -> bool {
+        !self.roles.iter().any(|role| role.not_allow_to_play())
+    }
 }
 
 impl TournamentStaffRole {
@@ -1,18 +1,47 @@
-use bson::{doc, oid::ObjectId, to_document, Bson, Document};
-use futures::{StreamExt, TryStreamExt};
-use mongodb::options::FindOneOptions;
+use bson::{doc, oid::ObjectId, to_bson, to_document, Bson, Document};
+use chrono::{DateTime, Utc};
+use mongodb::{
+    options::{FindOneOptions, FindOptions, IndexOptions, UpdateOptions},
+    IndexModel,
+};
 
 use vocolo_internal::*;
 
-use crate::{str_to_oid, to_vocolo_error, Database};
+use crate::{is_duplicate_key, str_to_oid, to_vocolo_error, Database};
 
-use super::{PartialTournament, Tournament, TournamentTeam};
+use super::{
+    PartialTeam, PartialTournament, Tournament, TournamentStaff, TournamentStaffRole,
+    TournamentStage, TournamentTeam,
+};
 
 static COL: &str = "osu_tournaments";
+static TEAMS_COL: &str = "osu_tournament_teams";
+
+/// Collections whose documents belong to a single tournament through `tournament_id`
+static TOURNAMENT_COLS: [&str; 11] = [
+    TEAMS_COL,
+    super::group_ops::COL,
+    super::invite_code_ops::COL,
+    super::mappool_ops::COL,
+    super::matchup_ops::COL,
+    super::player_snapshot_ops::COL,
+    super::qualifier_lobby_ops::COL,
+    super::qualifier_score_ops::COL,
+    super::round_ops::COL,
+    super::substitution_ops::COL,
+    super::team_invite_ops::COL,
+];
 
 pub trait AbstractOsuTournament: Sync + Send {
     async fn fetch_osu_tournament(&self, id: &str) -> Result<Option<Tournament>>;
 
+    async fn fetch_osu_tournaments(
+        &self,
+        filter: Document,
+        sort: Document,
+        limit: i64,
+    ) -> Result<Vec<Tournament>>;
+
     async fn insert_osu_tournament(&self, tournament: &Tournament) -> Result<ObjectId>;
 
     async fn update_osu_tournament(
@@ -21,6 +50,14 @@ pub trait AbstractOsuTournament: Sync + Send {
         tournament: &PartialTournament,
     ) -> Result<Option<()>>;
 
+    async fn update_osu_tournament_stage(
+        &self,
+        id: &str,
+        from: TournamentStage,
+        to: TournamentStage,
+    ) -> Result<Option<()>>;
+
+    /// Deletes the tournament along with everything that belongs to it
     async fn delete_osu_tournament(&self, id: &str) -> Result<Option<()>>;
 
     async fn check_osu_tournament_exists(&self, filter: Document) -> Result<bool>;
@@ -40,20 +77,77 @@ pub trait AbstractOsuTournament: Sync + Send {
         id: &str,
         team_ids: Vec<&str>,
     ) -> Result<Option<u64>>;
+
+    async fn update_osu_tournament_team(
+        &self,
+        id: &str,
+        team_id: &ObjectId,
+        team: &PartialTeam,
+    ) -> Result<Option<u64>>;
+
+    /// Adds the player to the team unless they already play in a team of the tournament or
+    /// the team already holds `max_players`
+    async fn insert_osu_tournament_team_player(
+        &self,
+        id: &str,
+        team_id: &ObjectId,
+        player_id: i32,
+        max_players: usize,
+    ) -> Result<Option<u64>>;
+
+    async fn delete_osu_tournament_team_player(
+        &self,
+        id: &str,
+        team_id: &ObjectId,
+        player_id: i32,
+    ) -> Result<Option<u64>>;
+
+    async fn ensure_osu_tournament_team_indexes(&self) -> Result<()>;
+
+    /// Moves teams still embedded in their tournament into the teams collection, returning
+    /// how many were inserted and how many were already there from an earlier run. A
+    /// tournament keeps its embedded teams until every one of them is in the collection
+    async fn migrate_osu_embedded_teams(&self) -> Result<(u64, u64)>;
+
+    /// Whether a tournament still has teams embedded in it
+    async fn check_osu_embedded_teams_remaining(&self) -> Result<bool>;
+
+    /// Stores a stage on tournaments created before stages existed, derived from where `now`
+    /// falls relative to their registration, returning how many were updated
+    async fn backfill_osu_tournament_stages(&self, now: &DateTime<Utc>) -> Result<u64>;
+
+    async fn fetch_osu_tournament_staff(&self, id: &str) -> Result<Option<Vec<TournamentStaff>>>;
+
+    /// Every tournament with at least one staff member, staff included
+    async fn fetch_osu_staffed_tournaments(&self) -> Result<Vec<Tournament>>;
+
+    /// Adds the staff unless one of them is already on the roster
+    async fn insert_osu_tournament_staff(
+        &self,
+        id: &str,
+        staff: Vec<&TournamentStaff>,
+    ) -> Result<Option<u64>>;
+
+    async fn update_osu_tournament_staff_roles(
+        &self,
+        id: &str,
+        staff_id: i32,
+        roles: &[TournamentStaffRole],
+    ) -> Result<Option<u64>>;
+
+    async fn delete_osu_tournament_staff(
+        &self,
+        id: &str,
+        staff_ids: Vec<i32>,
+    ) -> Result<Option<u64>>;
 }
 
 impl AbstractOsuTournament for Database {
     async fn fetch_osu_tournament(&self, id: &str) -> Result<Option<Tournament>> {
         let oid = str_to_oid(id);
 
-        let opts = FindOneOptions::builder()
-            .projection(doc! {
-                "teams": 0,
-            })
-            .build();
-
         let tournament = self
-            .find_one_with_options(
+            .find_one(
                 COL,
                 doc! {
                     "$or": [
@@ -61,13 +155,29 @@ impl AbstractOsuTournament for Database {
                         { "slug": id }
                     ]
                 },
-                opts,
             )
             .await?;
 
         Ok(tournament)
     }
 
+    async fn fetch_osu_tournaments(
+        &self,
+        filter: Document,
+        sort: Document,
+        limit: i64,
+    ) -> Result<Vec<Tournament>> {
+        let opts = FindOptions::builder()
+            .projection(doc! {
+                "staff": 0,
+            })
+            .sort(sort)
+            .limit(limit)
+            .build();
+
+        self.find_with_options(COL, filter, opts).await
+    }
+
     async fn insert_osu_tournament(&self, tournament: &Tournament) -> Result<ObjectId> {
         let id = self
             .insert_one(COL, tournament)
@@ -107,6 +217,45 @@ impl AbstractOsuTournament for Database {
         Ok(Some(()))
     }
 
+    async fn update_osu_tournament_stage(
+        &self,
+        id: &str,
+        from: TournamentStage,
+        to: TournamentStage,
+    ) -> Result<Option<()>> {
+        let id = str_to_oid(id);
+
+        // Older tournaments have no stage stored, which deserializes as the default one
+        let from_filter = if from == TournamentStage::default() {
+            doc! { "$in": [to_bson(&from)?, null] }
+        } else {
+            doc! { "$eq": to_bson(&from)? }
+        };
+
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "stage": from_filter
+                },
+                doc! {
+                    "$set": {
+                        "stage": to_bson(&to)?
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(()))
+    }
+
     async fn delete_osu_tournament(&self, id: &str) -> Result<Option<()>> {
         let oid = str_to_oid(id);
 
@@ -125,6 +274,18 @@ impl AbstractOsuTournament for Database {
             return Ok(None);
         }
 
+        for collection in TOURNAMENT_COLS {
+            self.col::<Document>(collection)
+                .delete_many(
+                    doc! {
+                        "tournament_id": oid
+                    },
+                    None,
+                )
+                .await
+                .map_err(to_vocolo_error)?;
+        }
+
         Ok(Some(()))
     }
 
@@ -133,44 +294,371 @@ impl AbstractOsuTournament for Database {
     }
 
     async fn check_osu_tournament_players_exist(&self, id: &str, ids: &[i32]) -> Result<Vec<i32>> {
-        let id = str_to_oid(id);
-
-        // TODO: uhhh
-        let mut result = self.col::<Document>(COL).aggregate(vec![
-            doc! { "$match": { "_id": id } },
-            doc! { "$unwind": "$teams" },
-            doc! { "$unwind": "$teams.players" },
-            doc! { "$match": { "teams.players": { "$in": ids } } },
-            doc! { "$group": { "_id": null, "duplicatePlayers": { "$addToSet": "$teams.players" } } },
-            doc! { "$project": { "_id": 0, "duplicatePlayers": 1 } }
-        ], None).await.map_err(to_vocolo_error)?;
-
-        let duplicate_players = if let Some(result) = result.next().await {
-            match result {
-                Ok(doc) => doc
-                    .get_array("duplicatePlayers")
-                    .unwrap_or(&vec![])
-                    .iter()
-                    .filter_map(|bson| match bson {
-                        Bson::Int32(player_id) => Some(*player_id),
-                        _ => None,
-                    })
-                    .collect(),
-                Err(_) => vec![],
-            }
-        } else {
-            vec![]
+        let Some(oid) = self.osu_tournament_oid(id).await? else {
+            return Ok(vec![]);
         };
 
+        let teams: Vec<TournamentTeam> = self
+            .find(
+                TEAMS_COL,
+                doc! {
+                    "tournament_id": oid,
+                    "players": { "$in": ids }
+                },
+            )
+            .await?;
+
+        let mut duplicate_players = teams
+            .into_iter()
+            .flat_map(|team| team.players)
+            .filter(|player_id| ids.contains(player_id))
+            .collect::<Vec<_>>();
+        duplicate_players.sort_unstable();
+        duplicate_players.dedup();
+
         Ok(duplicate_players)
     }
 
     async fn fetch_osu_tournament_teams(&self, id: &str) -> Result<Option<Vec<TournamentTeam>>> {
+        let Some(oid) = self.osu_tournament_oid(id).await? else {
+            return Ok(None);
+        };
+
+        // Ids are generated at registration, so this keeps the registration order
+        let opts = FindOptions::builder().sort(doc! { "_id": 1 }).build();
+
+        let teams = self
+            .find_with_options(
+                TEAMS_COL,
+                doc! {
+                    "tournament_id": oid
+                },
+                opts,
+            )
+            .await?;
+
+        Ok(Some(teams))
+    }
+
+    async fn insert_osu_tournament_teams(
+        &self,
+        id: &str,
+        teams: Vec<&TournamentTeam>,
+    ) -> Result<Option<u64>> {
+        let id = str_to_oid(id);
+
+        if !self.exists(COL, doc! { "_id": id }).await? {
+            return Ok(None);
+        }
+
+        if teams.is_empty() {
+            return Ok(Some(0));
+        }
+
+        let teams = teams
+            .iter()
+            .map(|team| {
+                let mut document = to_document(team)?;
+                document.insert("tournament_id", id);
+                Ok(document)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let result = self
+            .col::<Document>(TEAMS_COL)
+            .insert_many(teams, None)
+            .await
+            .map_err(|error| {
+                if is_duplicate_key(&error) {
+                    Error::AlreadyRegistered
+                } else {
+                    to_vocolo_error(error)
+                }
+            })?;
+
+        Ok(Some(result.inserted_ids.len() as u64))
+    }
+
+    async fn delete_osu_tournament_teams(
+        &self,
+        id: &str,
+        team_ids: Vec<&str>,
+    ) -> Result<Option<u64>> {
+        let id = str_to_oid(id);
+        let team_ids = team_ids
+            .iter()
+            .map(|id| str_to_oid(id))
+            .collect::<Vec<ObjectId>>();
+
+        if !self.exists(COL, doc! { "_id": id }).await? {
+            return Ok(None);
+        }
+
+        let result = self
+            .col::<Document>(TEAMS_COL)
+            .delete_many(
+                doc! {
+                    "tournament_id": id,
+                    "_id": {
+                        "$in": team_ids
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(Some(result.deleted_count))
+    }
+
+    async fn update_osu_tournament_team(
+        &self,
+        id: &str,
+        team_id: &ObjectId,
+        partial: &PartialTeam,
+    ) -> Result<Option<u64>> {
+        let id = str_to_oid(id);
+
+        let result = self
+            .col::<Document>(TEAMS_COL)
+            .update_one(
+                doc! {
+                    "_id": team_id,
+                    "tournament_id": id
+                },
+                doc! {
+                    "$set": to_document(partial)?
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(result.modified_count))
+    }
+
+    async fn insert_osu_tournament_team_player(
+        &self,
+        id: &str,
+        team_id: &ObjectId,
+        player_id: i32,
+        max_players: usize,
+    ) -> Result<Option<u64>> {
+        let id = str_to_oid(id);
+
+        // The unique index on the players of a tournament rejects players of another team
+        let result = match self
+            .col::<Document>(TEAMS_COL)
+            .update_one(
+                doc! {
+                    "_id": team_id,
+                    "tournament_id": id,
+                    "players": { "$ne": player_id },
+                    format!("players.{}", max_players.saturating_sub(1)): { "$exists": false }
+                },
+                doc! {
+                    "$push": {
+                        "players": player_id
+                    }
+                },
+                None,
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(error) if is_duplicate_key(&error) => return Ok(None),
+            Err(error) => return Err(to_vocolo_error(error)),
+        };
+
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(result.modified_count))
+    }
+
+    async fn delete_osu_tournament_team_player(
+        &self,
+        id: &str,
+        team_id: &ObjectId,
+        player_id: i32,
+    ) -> Result<Option<u64>> {
+        let id = str_to_oid(id);
+
+        let result = self
+            .col::<Document>(TEAMS_COL)
+            .update_one(
+                doc! {
+                    "_id": team_id,
+                    "tournament_id": id
+                },
+                doc! {
+                    "$pull": {
+                        "players": player_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(result.modified_count))
+    }
+
+    async fn ensure_osu_tournament_team_indexes(&self) -> Result<()> {
+        let indexes = vec![
+            IndexModel::builder()
+                .keys(doc! { "tournament_id": 1, "players": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "tournament_id": 1, "captain": 1 })
+                .build(),
+        ];
+
+        self.col::<Document>(TEAMS_COL)
+            .create_indexes(indexes, None)
+            .await
+            .map_err(to_vocolo_error)?;
+
+        Ok(())
+    }
+
+    async fn migrate_osu_embedded_teams(&self) -> Result<(u64, u64)> {
+        let tournaments: Vec<Document> = self
+            .find(
+                COL,
+                doc! {
+                    "teams": { "$exists": true }
+                },
+            )
+            .await?;
+
+        let mut inserted = 0;
+        let mut matched = 0;
+
+        for tournament in tournaments {
+            let Ok(tournament_id) = tournament.get_object_id("_id") else {
+                continue;
+            };
+
+            let teams = tournament
+                .get_array("teams")
+                .map(|teams| teams.to_vec())
+                .unwrap_or_default();
+
+            let mut complete = true;
+
+            for team in teams {
+                // Embedded teams kept their id under `id`
+                let Some((team_id, mut team)) = (match team {
+                    Bson::Document(mut team) => team.remove("id").map(|team_id| (team_id, team)),
+                    _ => None,
+                }) else {
+                    tracing::warn!(
+                        "Tournament {} has a malformed team, fix it to migrate the tournament",
+                        tournament_id
+                    );
+                    complete = false;
+                    continue;
+                };
+                team.insert("tournament_id", tournament_id);
+
+                let opts = UpdateOptions::builder().upsert(true).build();
+
+                // Upserting by id makes reruns match the teams an earlier run already moved
+                match self
+                    .col::<Document>(TEAMS_COL)
+                    .update_one(doc! { "_id": team_id }, doc! { "$setOnInsert": team }, opts)
+                    .await
+                {
+                    Ok(result) if result.upserted_id.is_some() => inserted += 1,
+                    Ok(_) => matched += 1,
+                    // Keep the embedded teams around for players registered in several teams
+                    Err(error) if is_duplicate_key(&error) => {
+                        tracing::warn!(
+                            "Tournament {} has players in several teams, fix them to migrate it",
+                            tournament_id
+                        );
+                        complete = false;
+                    }
+                    Err(error) => return Err(to_vocolo_error(error)),
+                }
+            }
+
+            if complete {
+                self.col::<Document>(COL)
+                    .update_one(
+                        doc! { "_id": tournament_id },
+                        doc! { "$unset": { "teams": "" } },
+                        None,
+                    )
+                    .await
+                    .map_err(to_vocolo_error)?;
+            }
+        }
+
+        Ok((inserted, matched))
+    }
+
+    async fn check_osu_embedded_teams_remaining(&self) -> Result<bool> {
+        self.exists(COL, doc! { "teams": { "$exists": true } })
+            .await
+    }
+
+    async fn backfill_osu_tournament_stages(&self, now: &DateTime<Utc>) -> Result<u64> {
+        let now = to_bson(now)?;
+
+        let stages = [
+            (
+                TournamentStage::Announced,
+                doc! { "registration_start_date": { "$gt": &now } },
+            ),
+            (
+                TournamentStage::Registration,
+                doc! {
+                    "registration_start_date": { "$lte": &now },
+                    "registration_end_date": { "$gte": &now }
+                },
+            ),
+            (
+                TournamentStage::Screening,
+                doc! { "registration_end_date": { "$lt": &now } },
+            ),
+        ];
+
+        let mut updated = 0;
+
+        for (stage, mut filter) in stages {
+            // Matches both a missing and a null stage
+            filter.insert("stage", Bson::Null);
+
+            let result = self
+                .col::<Document>(COL)
+                .update_many(filter, doc! { "$set": { "stage": to_bson(&stage)? } }, None)
+                .await
+                .map_err(to_vocolo_error)?;
+
+            updated += result.modified_count;
+        }
+
+        Ok(updated)
+    }
+
+    async fn fetch_osu_tournament_staff(&self, id: &str) -> Result<Option<Vec<TournamentStaff>>> {
         let oid = str_to_oid(id);
 
         let opts = FindOneOptions::builder()
             .projection(doc! {
-                "teams": 1,
+                "staff": 1,
             })
             .build();
 
@@ -188,30 +676,39 @@ impl AbstractOsuTournament for Database {
             .await
             .map_err(to_vocolo_error)?;
 
-        Ok(tournament.map(|t| t.teams.unwrap_or_default()))
+        Ok(tournament.map(|t| t.staff.unwrap_or_default()))
     }
 
-    async fn insert_osu_tournament_teams(
+    async fn fetch_osu_staffed_tournaments(&self) -> Result<Vec<Tournament>> {
+        self.find(
+            COL,
+            doc! {
+                "staff.0": { "$exists": true }
+            },
+        )
+        .await
+    }
+
+    async fn insert_osu_tournament_staff(
         &self,
         id: &str,
-        teams: Vec<&TournamentTeam>,
+        staff: Vec<&TournamentStaff>,
     ) -> Result<Option<u64>> {
         let id = str_to_oid(id);
-        let teams = teams
-            .iter()
-            .map(|v| to_document(v).unwrap())
-            .collect::<Vec<_>>();
+        let staff_ids = staff.iter().map(|v| v.id).collect::<Vec<_>>();
+        let staff = bson::to_bson(&staff)?;
 
         let result = self
             .col::<Tournament>(COL)
             .update_one(
                 doc! {
-                    "_id": id
+                    "_id": id,
+                    "staff.id": { "$nin": staff_ids }
                 },
                 doc! {
                     "$push": {
-                        "teams": {
-                            "$each": teams
+                        "staff": {
+                            "$each": staff
                         }
                     }
                 },
@@ -227,16 +724,45 @@ impl AbstractOsuTournament for Database {
         Ok(Some(result.modified_count))
     }
 
-    async fn delete_osu_tournament_teams(
+    async fn update_osu_tournament_staff_roles(
         &self,
         id: &str,
-        team_ids: Vec<&str>,
+        staff_id: i32,
+        roles: &[TournamentStaffRole],
+    ) -> Result<Option<u64>> {
+        let id = str_to_oid(id);
+        let roles = bson::to_bson(roles)?;
+
+        let result = self
+            .col::<Tournament>(COL)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "staff.id": staff_id
+                },
+                doc! {
+                    "$set": {
+                        "staff.$.roles": roles
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(to_vocolo_error)?;
+
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(result.modified_count))
+    }
+
+    async fn delete_osu_tournament_staff(
+        &self,
+        id: &str,
+        staff_ids: Vec<i32>,
     ) -> Result<Option<u64>> {
         let id = str_to_oid(id);
-        let team_ids = team_ids
-            .iter()
-            .map(|id| str_to_oid(id))
-            .collect::<Vec<ObjectId>>();
 
         let result = self
             .col::<Tournament>(COL)
@@ -246,9 +772,9 @@ impl AbstractOsuTournament for Database {
                 },
                 doc! {
                     "$pull": {
-                        "teams": {
-                            "_id": {
-                                "$in": team_ids
+                        "staff": {
+                            "id": {
+                                "$in": staff_ids
                             }
                         }
                     }
@@ -265,3 +791,29 @@ impl AbstractOsuTournament for Database {
         Ok(Some(result.modified_count))
     }
 }
+
+impl Database {
+    /// Resolves a tournament id or slug to its id
+    async fn osu_tournament_oid(&self, id: &str) -> Result<Option<ObjectId>> {
+        let oid = str_to_oid(id);
+
+        let opts = FindOneOptions::builder()
+            .projection(doc! { "_id": 1 })
+            .build();
+
+        let tournament: Option<Document> = self
+            .find_one_with_options(
+                COL,
+                doc! {
+                    "$or": [
+                        { "_id": oid },
+                        { "slug": id }
+                    ]
+                },
+                opts,
+            )
+            .await?;
+
+        Ok(tournament.and_then(|tournament| tournament.get_object_id("_id").ok()))
+    }
+}
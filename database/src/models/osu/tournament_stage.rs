@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle of a tournament, variants are declared in the order they are progressed through
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum TournamentStage {
+    #[default]
+    Draft,
+    Announced,
+    Registration,
+    Screening,
+    Qualifiers,
+    Bracket,
+    Finished,
+    Cancelled,
+}
+
+impl TournamentStage {
+    pub fn next_stages(&self) -> &'static [TournamentStage] {
+        use TournamentStage::*;
+
+        match self {
+            Draft => &[Announced, Cancelled],
+            Announced => &[Registration, Cancelled],
+            Registration => &[Screening, Qualifiers, Bracket, Cancelled],
+            Screening => &[Qualifiers, Bracket, Cancelled],
+            Qualifiers => &[Bracket, Cancelled],
+            Bracket => &[Finished, Cancelled],
+            Finished | Cancelled => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: TournamentStage) -> bool {
+        self.next_stages().contains(&next)
+    }
+
+    pub fn allows_registration(&self) -> bool {
+        matches!(self, TournamentStage::Registration)
+    }
+
+    pub fn allows_roster_changes(&self) -> bool {
+        *self < TournamentStage::Bracket
+    }
+
+    pub fn is_concluded(&self) -> bool {
+        matches!(self, TournamentStage::Finished | TournamentStage::Cancelled)
+    }
+}
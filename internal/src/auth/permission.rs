@@ -1,3 +1,489 @@
-pub async fn permission_check() {
-    todo!()
+use std::{
+    collections::HashSet,
+    env,
+    sync::{Arc, RwLock},
+};
+
+use ory_keto_client::apis::{configuration::Configuration, permission_api, relationship_api};
+use ory_keto_client::models::{CreateRelationshipBody, Relationship};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result, UserConnections};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Namespace {
+    Tournament,
+    Mappool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    Organizer,
+
+    Mappooler,
+    Mapper,
+    Testplayer,
+
+    Referee,
+    Streamer,
+    Commentator,
+
+    Staff,
+    Designer,
+    Developer,
+}
+
+/// A single `namespace:object#relation@subject` tuple, the subject being an osu! user id
+#[derive(Deserialize, Serialize, Debug, Clone, Hash, Eq, PartialEq)]
+pub struct RelationTuple {
+    pub namespace: Namespace,
+    pub object: String,
+    pub relation: Relation,
+    pub subject_id: String,
+}
+
+/// Filter for listing relation tuples, `None` fields match anything
+#[derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct RelationQuery {
+    pub namespace: Option<Namespace>,
+    pub object: Option<String>,
+    pub relation: Option<Relation>,
+    pub subject_id: Option<String>,
+}
+
+#[derive(Clone)]
+pub enum Permissions {
+    Keto(KetoPermissions),
+    Local(LocalPermissions),
+}
+
+#[derive(Clone)]
+pub struct KetoPermissions {
+    read: Arc<Configuration>,
+    write: Arc<Configuration>,
+}
+
+/// In-process relation tuple store for development and tests, grants are lost on restart
+#[derive(Clone, Default)]
+pub struct LocalPermissions(Arc<RwLock<HashSet<RelationTuple>>>);
+
+impl Namespace {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Namespace::Tournament => "Tournament",
+            Namespace::Mappool => "Mappool",
+        }
+    }
+
+    pub fn parse(namespace: &str) -> Option<Namespace> {
+        match namespace {
+            "Tournament" => Some(Namespace::Tournament),
+            "Mappool" => Some(Namespace::Mappool),
+            _ => None,
+        }
+    }
+}
+
+impl Relation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Relation::Organizer => "organizer",
+            Relation::Mappooler => "mappooler",
+            Relation::Mapper => "mapper",
+            Relation::Testplayer => "testplayer",
+            Relation::Referee => "referee",
+            Relation::Streamer => "streamer",
+            Relation::Commentator => "commentator",
+            Relation::Staff => "staff",
+            Relation::Designer => "designer",
+            Relation::Developer => "developer",
+        }
+    }
+
+    pub fn parse(relation: &str) -> Option<Relation> {
+        match relation {
+            "organizer" => Some(Relation::Organizer),
+            "mappooler" => Some(Relation::Mappooler),
+            "mapper" => Some(Relation::Mapper),
+            "testplayer" => Some(Relation::Testplayer),
+            "referee" => Some(Relation::Referee),
+            "streamer" => Some(Relation::Streamer),
+            "commentator" => Some(Relation::Commentator),
+            "staff" => Some(Relation::Staff),
+            "designer" => Some(Relation::Designer),
+            "developer" => Some(Relation::Developer),
+            _ => None,
+        }
+    }
+}
+
+impl RelationTuple {
+    pub fn new(namespace: Namespace, object: &str, relation: Relation, subject_id: i32) -> Self {
+        Self {
+            namespace,
+            object: object.to_string(),
+            relation,
+            subject_id: subject_id.to_string(),
+        }
+    }
+}
+
+impl RelationQuery {
+    fn matches(&self, tuple: &RelationTuple) -> bool {
+        self.namespace.map_or(true, |v| v == tuple.namespace)
+            && self.object.as_ref().map_or(true, |v| v == &tuple.object)
+            && self.relation.map_or(true, |v| v == tuple.relation)
+            && self
+                .subject_id
+                .as_ref()
+                .map_or(true, |v| v == &tuple.subject_id)
+    }
+}
+
+impl Permissions {
+    /// Picks the backend from `PERMISSIONS_BACKEND`, `keto` by default which requires
+    /// `KETO_READ_URL` and `KETO_WRITE_URL`. The local store has to be asked for explicitly
+    pub fn from_env() -> Result<Permissions> {
+        let backend = env::var("PERMISSIONS_BACKEND").unwrap_or("keto".to_owned());
+
+        match backend.as_str() {
+            "keto" => match (env::var("KETO_READ_URL"), env::var("KETO_WRITE_URL")) {
+                (Ok(read_url), Ok(write_url)) => Ok(Permissions::Keto(KetoPermissions::new(
+                    &read_url, &write_url,
+                ))),
+                _ => Err(Error::Internal(
+                    "`KETO_READ_URL` and `KETO_WRITE_URL` must be set".to_string(),
+                )),
+            },
+            "local" => {
+                log::warn!("Using local permissions, grants are lost on restart");
+                Ok(Permissions::Local(LocalPermissions::default()))
+            }
+            backend => Err(Error::Internal(format!(
+                "unknown permissions backend `{backend}`"
+            ))),
+        }
+    }
+
+    pub async fn check(&self, tuple: &RelationTuple) -> Result<bool> {
+        match self {
+            Permissions::Keto(keto) => keto.check(tuple).await,
+            Permissions::Local(local) => local.check(tuple),
+        }
+    }
+
+    /// Succeeds if any of the given tuples holds
+    pub async fn require_any(&self, tuples: &[RelationTuple]) -> Result<()> {
+        for tuple in tuples {
+            if self.check(tuple).await? {
+                return Ok(());
+            }
+        }
+
+        Err(Error::MissingPermission)
+    }
+
+    pub async fn grant(&self, tuple: &RelationTuple) -> Result<()> {
+        match self {
+            Permissions::Keto(keto) => keto.grant(tuple).await,
+            Permissions::Local(local) => local.grant(tuple),
+        }
+    }
+
+    pub async fn revoke(&self, tuple: &RelationTuple) -> Result<()> {
+        match self {
+            Permissions::Keto(keto) => keto.revoke(tuple).await,
+            Permissions::Local(local) => local.revoke(tuple),
+        }
+    }
+
+    pub async fn list(&self, query: &RelationQuery) -> Result<Vec<RelationTuple>> {
+        match self {
+            Permissions::Keto(keto) => keto.list(query).await,
+            Permissions::Local(local) => local.list(query),
+        }
+    }
+}
+
+impl KetoPermissions {
+    pub fn new(read_url: &str, write_url: &str) -> Self {
+        Self {
+            read: Arc::new(Configuration {
+                base_path: read_url.trim_end_matches('/').to_string(),
+                ..Default::default()
+            }),
+            write: Arc::new(Configuration {
+                base_path: write_url.trim_end_matches('/').to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    async fn check(&self, tuple: &RelationTuple) -> Result<bool> {
+        let result = permission_api::check_permission(
+            &self.read,
+            Some(tuple.namespace.as_str()),
+            Some(&tuple.object),
+            Some(tuple.relation.as_str()),
+            Some(&tuple.subject_id),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .map_err(to_keto_error)?;
+
+        Ok(result.allowed)
+    }
+
+    async fn grant(&self, tuple: &RelationTuple) -> Result<()> {
+        let body = CreateRelationshipBody {
+            namespace: Some(tuple.namespace.as_str().to_string()),
+            object: Some(tuple.object.clone()),
+            relation: Some(tuple.relation.as_str().to_string()),
+            subject_id: Some(tuple.subject_id.clone()),
+            subject_set: None,
+        };
+
+        relationship_api::create_relationship(&self.write, Some(&body))
+            .await
+            .map_err(to_keto_error)?;
+
+        Ok(())
+    }
+
+    async fn revoke(&self, tuple: &RelationTuple) -> Result<()> {
+        relationship_api::delete_relationships(
+            &self.write,
+            Some(tuple.namespace.as_str()),
+            Some(&tuple.object),
+            Some(tuple.relation.as_str()),
+            Some(&tuple.subject_id),
+            None,
+            None,
+            None,
+        )
+        .await
+        .map_err(to_keto_error)?;
+
+        Ok(())
+    }
+
+    async fn list(&self, query: &RelationQuery) -> Result<Vec<RelationTuple>> {
+        let mut tuples = vec![];
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let page = relationship_api::get_relationships(
+                &self.read,
+                page_token.as_deref(),
+                None,
+                query.namespace.map(|v| v.as_str()),
+                query.object.as_deref(),
+                query.relation.map(|v| v.as_str()),
+                query.subject_id.as_deref(),
+                None,
+                None,
+                None,
+            )
+            .await
+            .map_err(to_keto_error)?;
+
+            tuples.extend(
+                page.relation_tuples
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(from_keto_relationship),
+            );
+
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(tuples)
+    }
+}
+
+impl LocalPermissions {
+    fn check(&self, tuple: &RelationTuple) -> Result<bool> {
+        Ok(self.0.read().unwrap().contains(tuple))
+    }
+
+    fn grant(&self, tuple: &RelationTuple) -> Result<()> {
+        self.0.write().unwrap().insert(tuple.clone());
+        Ok(())
+    }
+
+    fn revoke(&self, tuple: &RelationTuple) -> Result<()> {
+        self.0.write().unwrap().remove(tuple);
+        Ok(())
+    }
+
+    fn list(&self, query: &RelationQuery) -> Result<Vec<RelationTuple>> {
+        Ok(self
+            .0
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|tuple| query.matches(tuple))
+            .cloned()
+            .collect())
+    }
+}
+
+fn to_keto_error<T>(source: ory_keto_client::apis::Error<T>) -> Error {
+    Error::Internal(format!("keto: {}", source))
+}
+
+fn from_keto_relationship(relationship: Relationship) -> Option<RelationTuple> {
+    Some(RelationTuple {
+        namespace: Namespace::parse(&relationship.namespace)?,
+        object: relationship.object,
+        relation: Relation::parse(&relationship.relation)?,
+        subject_id: relationship.subject_id?,
+    })
+}
+
+/// Logged in user along with the permission backend, checks are made against their osu! id
+pub struct Authorization {
+    pub connections: UserConnections,
+    pub permissions: Permissions,
+}
+
+impl Authorization {
+    pub fn tuple(&self, namespace: Namespace, object: &str, relation: Relation) -> RelationTuple {
+        RelationTuple::new(namespace, object, relation, self.connections.osu.id)
+    }
+
+    /// Succeeds if the user holds any of `relations` on the given object
+    pub async fn require(
+        &self,
+        namespace: Namespace,
+        object: &str,
+        relations: &[Relation],
+    ) -> Result<()> {
+        let tuples = relations
+            .iter()
+            .map(|relation| self.tuple(namespace, object, *relation))
+            .collect::<Vec<_>>();
+
+        self.permissions.require_any(&tuples).await
+    }
+}
+
+#[cfg(feature = "axum")]
+#[axum::async_trait]
+impl<S> axum::extract::FromRequestParts<S> for Authorization
+where
+    Permissions: axum::extract::FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        use axum::extract::FromRef;
+
+        let connections = UserConnections::from_request_parts(parts, state).await?;
+
+        Ok(Authorization {
+            connections,
+            permissions: Permissions::from_ref(state),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local() -> Permissions {
+        Permissions::Local(LocalPermissions::default())
+    }
+
+    #[tokio::test]
+    async fn local_grant_and_revoke() {
+        let permissions = local();
+        let tuple = RelationTuple::new(Namespace::Tournament, "t1", Relation::Organizer, 1);
+
+        assert!(!permissions.check(&tuple).await.unwrap());
+
+        permissions.grant(&tuple).await.unwrap();
+        assert!(permissions.check(&tuple).await.unwrap());
+
+        permissions.revoke(&tuple).await.unwrap();
+        assert!(!permissions.check(&tuple).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn local_clones_share_grants() {
+        let permissions = local();
+        let tuple = RelationTuple::new(Namespace::Mappool, "m1", Relation::Mapper, 2);
+
+        permissions.clone().grant(&tuple).await.unwrap();
+
+        assert!(permissions.check(&tuple).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn require_any_needs_one_tuple() {
+        let permissions = local();
+        let organizer = RelationTuple::new(Namespace::Tournament, "t1", Relation::Organizer, 1);
+        let referee = RelationTuple::new(Namespace::Tournament, "t1", Relation::Referee, 1);
+
+        assert!(matches!(
+            permissions
+                .require_any(&[organizer.clone(), referee.clone()])
+                .await,
+            Err(Error::MissingPermission)
+        ));
+
+        permissions.grant(&referee).await.unwrap();
+        assert!(permissions.require_any(&[organizer, referee]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn local_list_filters_by_query() {
+        let permissions = local();
+        let tuples = [
+            RelationTuple::new(Namespace::Tournament, "t1", Relation::Organizer, 1),
+            RelationTuple::new(Namespace::Tournament, "t1", Relation::Referee, 2),
+            RelationTuple::new(Namespace::Tournament, "t2", Relation::Referee, 2),
+        ];
+        for tuple in &tuples {
+            permissions.grant(tuple).await.unwrap();
+        }
+
+        let all = permissions.list(&RelationQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+
+        let t1 = permissions
+            .list(&RelationQuery {
+                namespace: Some(Namespace::Tournament),
+                object: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(t1.len(), 2);
+
+        let referees = permissions
+            .list(&RelationQuery {
+                relation: Some(Relation::Referee),
+                subject_id: Some("2".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(referees.len(), 2);
+        assert!(referees
+            .iter()
+            .all(|tuple| tuple.relation == Relation::Referee));
+    }
 }
@@ -6,6 +6,12 @@ pub enum Error {
     #[error("Unknown Tournament")]
     UnknownTournament,
 
+    #[error("Unknown Tournament Staff")]
+    UnknownTournamentStaff,
+
+    #[error("Tournament must keep at least one organizer")]
+    LastOrganizer,
+
     #[error("Unknown Mappool")]
     UnknownMappool,
 
@@ -15,6 +21,15 @@ pub enum Error {
     #[error("Unknown Match")]
     UnknownMatch,
 
+    #[error("Unknown Round")]
+    UnknownRound,
+
+    #[error("Round still has matches, a mappool or groups")]
+    RoundInUse,
+
+    #[error("Unknown Match Map")]
+    UnknownMatchupMap,
+
     #[error("Tournament is not yet open for registration")]
     RegistrationNotOpen,
 
@@ -24,9 +39,162 @@ pub enum Error {
     #[error("Player(s) already registered")]
     AlreadyRegistered,
 
+    #[error("Players register individually in this tournament")]
+    SoloRegistrationRequired,
+
+    #[error("Players register as teams in this tournament")]
+    TeamRegistrationRequired,
+
+    #[error("You are not the captain of a team in this tournament")]
+    NotTeamCaptain,
+
+    #[error("Invite was already answered or revoked")]
+    InviteNotPending,
+
+    #[error("Tournament is invite-only, an invite code is required")]
+    InviteCodeRequired,
+
+    #[error("Invite code is unknown or already used")]
+    InvalidInviteCode,
+
+    #[error("Team has fewer players than the minimum team size")]
+    TeamTooSmall,
+
+    #[error("Team has more players than the maximum team size")]
+    TeamTooLarge,
+
+    #[error("Minimum team size cannot exceed the maximum team size")]
+    InvalidTeamSizes,
+
+    #[error("Team(s) below the minimum team size: {0}")]
+    TeamsBelowMinimumSize(String),
+
+    #[error("Unknown Player")]
+    UnknownPlayer,
+
+    #[error("Unknown osu! Match")]
+    UnknownOsuMatch,
+
+    #[error("Ineligible player(s): {0}")]
+    IneligiblePlayers(String),
+
+    #[error("Rosters are locked, request a substitution instead")]
+    RosterLocked,
+
+    #[error("Unknown Substitution")]
+    UnknownSubstitution,
+
+    #[error("Substitution was already resolved")]
+    SubstitutionNotPending,
+
+    #[error("A substitution needs a player to swap out or in")]
+    InvalidSubstitution,
+
+    #[error("The captain cannot leave their team")]
+    CaptainCannotLeave,
+
+    #[error("Player(s) are not part of the team")]
+    NotInTeam,
+
+    #[error("Qualifier lobby is full")]
+    LobbyFull,
+
+    #[error("Qualifier lobby sign-ups are closed")]
+    LobbySignupClosed,
+
+    #[error("Team is not signed up for this qualifier lobby")]
+    NotSignedUp,
+
+    #[error("Lobby sign-ups must close before the lobby starts")]
+    InvalidLobbySchedule,
+
+    #[error("Referee must be tournament staff allowed to referee")]
+    InvalidReferee,
+
+    #[error("Player(s) hold a staff role that does not allow playing")]
+    StaffNotAllowedToPlay,
+
+    #[error("Player is registered and cannot hold this staff role")]
+    PlayerNotAllowedAsStaff,
+
+    #[error("Mappools only grant the mappooler role")]
+    InvalidMappoolRole,
+
+    #[error("Tournament cannot move to this stage from its current one")]
+    InvalidStageTransition,
+
+    #[error("Not allowed in the current tournament stage")]
+    NotAllowedInStage,
+
+    #[error("Unknown Group")]
+    UnknownGroup,
+
+    #[error("Unknown Qualifier Lobby")]
+    UnknownQualifierLobby,
+
+    #[error("Unknown Team")]
+    UnknownTeam,
+
+    #[error("Unknown Invite")]
+    UnknownInvite,
+
+    #[error("Round must start before it ends")]
+    InvalidRoundSchedule,
+
+    #[error("Match is outside of its round's schedule")]
+    OutsideRoundSchedule,
+
+    #[error("Not allowed for this kind of round")]
+    InvalidRoundKind,
+
+    #[error("Invalid group options")]
+    InvalidGroupOptions,
+
+    #[error("Previous matches are not decided yet")]
+    PendingMatches,
+
+    #[error("Unknown tiebreaker")]
+    InvalidTiebreaker,
+
+    #[error("Match already has a result")]
+    MatchAlreadyDecided,
+
+    #[error("Match changed while this request was handled, try again")]
+    MatchChanged,
+
+    #[error("Match does not have its teams yet")]
+    MatchNotReady,
+
+    #[error("Winner of a played match must have the higher score")]
+    InvalidMatchScore,
+
+    #[error("Pick or ban breaks the round's sequence: {0}")]
+    InvalidPickBan(String),
+
+    #[error("Pick/ban sequence must give each team the round's ban count")]
+    InvalidPickBanSequence,
+
+    #[error("Only picked maps are played")]
+    MapNotPicked,
+
+    #[error("Seeds must be distinct teams of the tournament")]
+    InvalidSeeding,
+
+    #[error("Not enough teams")]
+    NotEnoughTeams,
+
+    #[error("Bracket needs {0} bracket rounds")]
+    NotEnoughBracketRounds(usize),
+
+    #[error("Invalid pagination cursor")]
+    InvalidCursor,
+
     #[error("You are not permitted")]
     Unauthorized,
 
+    #[error("You are missing the permission to do this")]
+    MissingPermission,
+
     // Internal
     #[error("{0}")]
     Internal(String),
@@ -42,6 +210,9 @@ pub enum Error {
 
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
+
+    #[error("osu! API error: {0}")]
+    OsuApi(String),
 }
 
 #[cfg(feature = "axum")]
@@ -53,15 +224,82 @@ impl axum::response::IntoResponse for Error {
         let (status_code, error_message) = match self {
             Error::AlreadyExists(_) => (StatusCode::BAD_REQUEST, "already_exists"),
             Error::UnknownTournament => (StatusCode::NOT_FOUND, "unknown_tournament"),
+            Error::UnknownTournamentStaff => (StatusCode::NOT_FOUND, "unknown_tournament_staff"),
+            Error::LastOrganizer => (StatusCode::BAD_REQUEST, "last_organizer"),
             Error::UnknownMappool => (StatusCode::NOT_FOUND, "unknown_mappool"),
             Error::UnknownMappoolMap => (StatusCode::NOT_FOUND, "unknown_mappool_map"),
             Error::UnknownMatch => (StatusCode::NOT_FOUND, "unknown_match"),
+            Error::UnknownRound => (StatusCode::NOT_FOUND, "unknown_round"),
+            Error::RoundInUse => (StatusCode::BAD_REQUEST, "round_in_use"),
+            Error::UnknownMatchupMap => (StatusCode::NOT_FOUND, "unknown_matchup_map"),
+            Error::UnknownGroup => (StatusCode::NOT_FOUND, "unknown_group"),
+            Error::UnknownQualifierLobby => (StatusCode::NOT_FOUND, "unknown_qualifier_lobby"),
+            Error::UnknownTeam => (StatusCode::NOT_FOUND, "unknown_team"),
+            Error::UnknownInvite => (StatusCode::NOT_FOUND, "unknown_invite"),
 
             Error::RegistrationNotOpen => (StatusCode::BAD_REQUEST, "registration_not_open"),
             Error::RegistrationClosed => (StatusCode::BAD_REQUEST, "registration_closed"),
             Error::AlreadyRegistered => (StatusCode::BAD_REQUEST, "already_registered"),
+            Error::SoloRegistrationRequired => {
+                (StatusCode::BAD_REQUEST, "solo_registration_required")
+            }
+            Error::TeamRegistrationRequired => {
+                (StatusCode::BAD_REQUEST, "team_registration_required")
+            }
+            Error::NotTeamCaptain => (StatusCode::FORBIDDEN, "not_team_captain"),
+            Error::InviteNotPending => (StatusCode::BAD_REQUEST, "invite_not_pending"),
+            Error::InviteCodeRequired => (StatusCode::BAD_REQUEST, "invite_code_required"),
+            Error::InvalidInviteCode => (StatusCode::BAD_REQUEST, "invalid_invite_code"),
+            Error::TeamTooSmall => (StatusCode::BAD_REQUEST, "team_too_small"),
+            Error::TeamTooLarge => (StatusCode::BAD_REQUEST, "team_too_large"),
+            Error::InvalidTeamSizes => (StatusCode::BAD_REQUEST, "invalid_team_sizes"),
+            Error::TeamsBelowMinimumSize(_) => {
+                (StatusCode::BAD_REQUEST, "teams_below_minimum_size")
+            }
+            Error::UnknownPlayer => (StatusCode::NOT_FOUND, "unknown_player"),
+            Error::UnknownOsuMatch => (StatusCode::NOT_FOUND, "unknown_osu_match"),
+            Error::IneligiblePlayers(_) => (StatusCode::BAD_REQUEST, "ineligible_players"),
+            Error::RosterLocked => (StatusCode::BAD_REQUEST, "roster_locked"),
+            Error::UnknownSubstitution => (StatusCode::NOT_FOUND, "unknown_substitution"),
+            Error::SubstitutionNotPending => (StatusCode::BAD_REQUEST, "substitution_not_pending"),
+            Error::InvalidSubstitution => (StatusCode::BAD_REQUEST, "invalid_substitution"),
+            Error::CaptainCannotLeave => (StatusCode::BAD_REQUEST, "captain_cannot_leave"),
+            Error::NotInTeam => (StatusCode::BAD_REQUEST, "not_in_team"),
+            Error::LobbyFull => (StatusCode::BAD_REQUEST, "lobby_full"),
+            Error::LobbySignupClosed => (StatusCode::BAD_REQUEST, "lobby_signup_closed"),
+            Error::NotSignedUp => (StatusCode::BAD_REQUEST, "not_signed_up"),
+            Error::InvalidLobbySchedule => (StatusCode::BAD_REQUEST, "invalid_lobby_schedule"),
+            Error::InvalidReferee => (StatusCode::BAD_REQUEST, "invalid_referee"),
+            Error::StaffNotAllowedToPlay => (StatusCode::BAD_REQUEST, "staff_not_allowed_to_play"),
+            Error::PlayerNotAllowedAsStaff => {
+                (StatusCode::BAD_REQUEST, "player_not_allowed_as_staff")
+            }
+            Error::InvalidMappoolRole => (StatusCode::BAD_REQUEST, "invalid_mappool_role"),
+
+            Error::InvalidStageTransition => (StatusCode::BAD_REQUEST, "invalid_stage_transition"),
+            Error::NotAllowedInStage => (StatusCode::BAD_REQUEST, "not_allowed_in_stage"),
+            Error::InvalidRoundSchedule => (StatusCode::BAD_REQUEST, "invalid_round_schedule"),
+            Error::OutsideRoundSchedule => (StatusCode::BAD_REQUEST, "outside_round_schedule"),
+            Error::InvalidRoundKind => (StatusCode::BAD_REQUEST, "invalid_round_kind"),
+            Error::InvalidGroupOptions => (StatusCode::BAD_REQUEST, "invalid_group_options"),
+            Error::PendingMatches => (StatusCode::BAD_REQUEST, "pending_matches"),
+            Error::InvalidTiebreaker => (StatusCode::BAD_REQUEST, "invalid_tiebreaker"),
+            Error::MatchAlreadyDecided => (StatusCode::BAD_REQUEST, "match_already_decided"),
+            Error::MatchChanged => (StatusCode::CONFLICT, "match_changed"),
+            Error::MatchNotReady => (StatusCode::BAD_REQUEST, "match_not_ready"),
+            Error::InvalidMatchScore => (StatusCode::BAD_REQUEST, "invalid_match_score"),
+            Error::InvalidPickBan(_) => (StatusCode::BAD_REQUEST, "invalid_pick_ban"),
+            Error::InvalidPickBanSequence => (StatusCode::BAD_REQUEST, "invalid_pick_ban_sequence"),
+            Error::MapNotPicked => (StatusCode::BAD_REQUEST, "map_not_picked"),
+            Error::InvalidSeeding => (StatusCode::BAD_REQUEST, "invalid_seeding"),
+            Error::NotEnoughTeams => (StatusCode::BAD_REQUEST, "not_enough_teams"),
+            Error::NotEnoughBracketRounds(_) => {
+                (StatusCode::BAD_REQUEST, "not_enough_bracket_rounds")
+            }
+            Error::InvalidCursor => (StatusCode::BAD_REQUEST, "invalid_cursor"),
 
             Error::Unauthorized => (StatusCode::UNAUTHORIZED, "unauthorized"),
+            Error::MissingPermission => (StatusCode::FORBIDDEN, "missing_permission"),
 
             // Internal
             Error::Internal(_)
@@ -69,6 +307,7 @@ impl axum::response::IntoResponse for Error {
             | Error::BsonDe(_)
             | Error::BsonSer(_)
             | Error::Reqwest(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal"),
+            Error::OsuApi(_) => (StatusCode::BAD_GATEWAY, "osu_api"),
         };
 
         (
@@ -1,6 +1,9 @@
+#![allow(async_fn_in_trait)]
 mod auth;
 pub use auth::*;
 mod error;
 pub use error::*;
+mod osu_api;
+pub use osu_api::*;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,243 @@
+use std::{
+    env,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+const DEFAULT_OSU_API_URL: &str = "https://osu.ppy.sh";
+
+/// Refresh the token a bit before osu! expires it
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+pub trait AbstractOsuApi: Sync + Send {
+    /// Fetches the user with their statistics in `mode` (`osu`, `mania`, ...), `None` if
+    /// they do not exist
+    async fn fetch_osu_user(&self, user_id: i32, mode: &str) -> Result<Option<OsuUser>>;
+
+    /// Fetches the multiplayer match with all of its events, `None` if it does not exist
+    async fn fetch_osu_match(&self, match_id: i64) -> Result<Option<OsuMatch>>;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsuUser {
+    pub id: i32,
+    pub username: String,
+    pub country_code: String,
+    #[serde(default)]
+    pub badges: Vec<OsuBadge>,
+    pub statistics: Option<OsuUserStatistics>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsuBadge {
+    pub description: String,
+    pub image_url: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsuUserStatistics {
+    pub global_rank: Option<i32>,
+    pub country_rank: Option<i32>,
+    pub pp: f64,
+}
+
+/// Multiplayer match as returned by `/matches/{id}`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsuMatch {
+    #[serde(rename = "match")]
+    pub info: OsuMatchInfo,
+    pub events: Vec<OsuMatchEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_event_id: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsuMatchInfo {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsuMatchEvent {
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game: Option<OsuMatchGame>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsuMatchGame {
+    pub id: i64,
+    pub beatmap_id: i32,
+    /// `score`, `accuracy`, `combo` or `scorev2`
+    pub scoring_type: String,
+    #[serde(default)]
+    pub mods: Vec<String>,
+    #[serde(default)]
+    pub scores: Vec<OsuMatchScore>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsuMatchScore {
+    pub user_id: i32,
+    pub score: u32,
+    pub accuracy: f64,
+    pub max_combo: u32,
+    #[serde(default)]
+    pub mods: Vec<String>,
+    pub statistics: OsuScoreStatistics,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct OsuScoreStatistics {
+    #[serde(default)]
+    pub count_geki: u32,
+    #[serde(default)]
+    pub count_300: u32,
+    #[serde(default)]
+    pub count_katu: u32,
+    #[serde(default)]
+    pub count_100: u32,
+    #[serde(default)]
+    pub count_50: u32,
+    #[serde(default)]
+    pub count_miss: u32,
+}
+
+#[derive(Deserialize)]
+struct OsuToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// osu! API v2 over HTTP, `base_url` can point at a mock server
+#[derive(Clone)]
+pub struct OsuApiClient {
+    base_url: String,
+    credentials: Option<(String, String)>,
+    http: reqwest::Client,
+    token: Arc<Mutex<Option<(String, Instant)>>>,
+}
+
+impl OsuApiClient {
+    pub fn new(base_url: &str, credentials: Option<(String, String)>) -> OsuApiClient {
+        OsuApiClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            credentials,
+            http: reqwest::Client::new(),
+            token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Uses `OSU_API_URL`, `OSU_CLIENT_ID` and `OSU_CLIENT_SECRET`, requests are sent
+    /// without a token when the credentials are not set
+    pub fn from_env() -> OsuApiClient {
+        let base_url = env::var("OSU_API_URL").unwrap_or(DEFAULT_OSU_API_URL.to_owned());
+
+        let credentials = match (env::var("OSU_CLIENT_ID"), env::var("OSU_CLIENT_SECRET")) {
+            (Ok(id), Ok(secret)) if !id.is_empty() && !secret.is_empty() => Some((id, secret)),
+            _ => {
+                log::warn!("osu! API credentials are not configured, sending anonymous requests");
+                None
+            }
+        };
+
+        OsuApiClient::new(&base_url, credentials)
+    }
+
+    /// Client credentials token, cached until it expires
+    async fn token(&self) -> Result<Option<String>> {
+        let Some((client_id, client_secret)) = &self.credentials else {
+            return Ok(None);
+        };
+
+        if let Some((token, expires_at)) = self.token.lock().unwrap().as_ref() {
+            if *expires_at > Instant::now() {
+                return Ok(Some(token.clone()));
+            }
+        }
+
+        let response = self
+            .http
+            .post(format!("{}/oauth/token", self.base_url))
+            .json(&serde_json::json!({
+                "client_id": client_id,
+                "client_secret": client_secret,
+                "grant_type": "client_credentials",
+                "scope": "public",
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Error::OsuApi(format!(
+                "token request failed with {}",
+                response.status()
+            )));
+        }
+
+        let token = response.json::<OsuToken>().await?;
+        let expires_at = Instant::now()
+            + Duration::from_secs(token.expires_in).saturating_sub(TOKEN_EXPIRY_MARGIN);
+
+        *self.token.lock().unwrap() = Some((token.access_token.clone(), expires_at));
+
+        Ok(Some(token.access_token))
+    }
+
+    async fn get(&self, path: &str) -> Result<reqwest::Response> {
+        let mut request = self.http.get(format!("{}/api/v2{}", self.base_url, path));
+
+        if let Some(token) = self.token().await? {
+            request = request.bearer_auth(token);
+        }
+
+        Ok(request.send().await?)
+    }
+}
+
+impl AbstractOsuApi for OsuApiClient {
+    async fn fetch_osu_user(&self, user_id: i32, mode: &str) -> Result<Option<OsuUser>> {
+        let response = self.get(&format!("/users/{user_id}/{mode}?key=id")).await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response.json::<OsuUser>().await?)),
+            status => Err(Error::OsuApi(format!("user request failed with {status}"))),
+        }
+    }
+
+    async fn fetch_osu_match(&self, match_id: i64) -> Result<Option<OsuMatch>> {
+        let mut path = format!("/matches/{match_id}?limit=100");
+        let mut events = vec![];
+
+        // Events come newest first in pages of 100, walk back until the first one
+        loop {
+            let response = self.get(&path).await?;
+
+            let mut page = match response.status() {
+                StatusCode::NOT_FOUND => return Ok(None),
+                status if status.is_success() => response.json::<OsuMatch>().await?,
+                status => return Err(Error::OsuApi(format!("match request failed with {status}"))),
+            };
+
+            let oldest = page.events.first().map(|event| event.id);
+            page.events.append(&mut events);
+            events = page.events;
+
+            match (oldest, page.first_event_id) {
+                (Some(oldest), Some(first)) if oldest > first => {
+                    path = format!("/matches/{match_id}?limit=100&before={oldest}");
+                }
+                _ => {
+                    page.events = events;
+                    return Ok(Some(page));
+                }
+            }
+        }
+    }
+}
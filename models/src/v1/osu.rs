@@ -1,11 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+pub use eligibility::*;
+pub use group::*;
+pub use invite::*;
 pub use mappool::*;
 pub use matchup::*;
+pub use permission::*;
+pub use player::*;
+pub use qualifier::*;
+pub use round::*;
+pub use substitution::*;
 pub use tournament::*;
 
+mod eligibility;
+mod group;
+mod invite;
 mod mappool;
 mod matchup;
+mod permission;
+mod player;
+mod qualifier;
+mod round;
+mod substitution;
 mod tournament;
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
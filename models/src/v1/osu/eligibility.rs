@@ -0,0 +1,38 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct EligibilityRules {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_rank: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_rank: Option<i32>,
+    #[serde(skip_serializing_if = "crate::if_false", default)]
+    pub badge_weighted: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub countries: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub enum IneligibilityReason {
+    MissingProfile,
+    Unranked,
+    RankAboveRange { rank: i32, min_rank: i32 },
+    RankBelowRange { rank: i32, max_rank: i32 },
+    CountryNotAllowed { country: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PlayerEligibility {
+    pub player_id: i32,
+    pub eligible: bool,
+    pub reasons: Vec<IneligibilityReason>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct TeamScreening {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub team_id: ObjectId,
+    pub eligible: bool,
+    pub players: Vec<PlayerEligibility>,
+}
@@ -0,0 +1,94 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use super::Matchup;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct Group {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub tournament_id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub round_id: ObjectId,
+
+    pub name: String,
+    pub format: GroupFormat,
+    pub teams: Vec<ObjectId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swiss_rounds: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GroupFormat {
+    RoundRobin,
+    Swiss,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct Standing {
+    pub team_id: ObjectId,
+    pub position: i32,
+
+    pub played: i32,
+    pub wins: i32,
+    pub losses: i32,
+
+    pub map_wins: i32,
+    pub map_losses: i32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Tiebreaker {
+    MatchWins,
+    MapDifferential,
+    HeadToHead,
+}
+
+impl Tiebreaker {
+    pub fn parse(tiebreaker: &str) -> Option<Tiebreaker> {
+        match tiebreaker {
+            "match_wins" => Some(Tiebreaker::MatchWins),
+            "map_differential" => Some(Tiebreaker::MapDifferential),
+            "head_to_head" => Some(Tiebreaker::HeadToHead),
+            _ => None,
+        }
+    }
+}
+
+// DTO
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct GenerateGroupsRequest {
+    pub format: GroupFormat,
+    #[garde(range(min = 1, max = 32))]
+    pub group_count: usize,
+    /// Team ids from the best seed down, defaults to registration order
+    pub seeds: Option<Vec<String>>,
+    #[garde(range(min = 1))]
+    pub swiss_rounds: Option<i32>,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct GenerateGroupsResponse {
+    pub groups: Vec<Group>,
+    pub matches: Vec<Matchup>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StandingsQuery {
+    /// Comma separated tiebreakers applied in order, e.g. `match_wins,head_to_head`
+    pub tiebreakers: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GroupStandings {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub group_id: ObjectId,
+    pub name: String,
+    pub standings: Vec<Standing>,
+}
@@ -0,0 +1,52 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct TeamInvite {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub tournament_id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub team_id: ObjectId,
+
+    pub player_id: i32,
+    pub invited_by: i32,
+
+    pub status: InviteStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InviteStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Revoked,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct InviteCode {
+    pub code: String,
+    pub created_by: i32,
+    pub created_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_by: Option<ObjectId>,
+}
+
+// DTO
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct CreateTeamInviteRequest {
+    pub player_id: i32,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct GenerateInviteCodesRequest {
+    #[garde(range(min = 1, max = 100))]
+    pub count: Option<usize>,
+}
@@ -5,6 +5,10 @@ use serde::{Deserialize, Serialize};
 pub struct Mappool {
     #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
     pub id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub tournament_id: ObjectId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_id: Option<ObjectId>,
 
     pub mappack_link: String,
 
@@ -23,6 +27,7 @@ pub struct MappoolMap {
 #[garde(allow_unvalidated)]
 pub struct CreateMappoolRequest {
     pub tournament_id: String,
+    pub round_id: Option<String>,
     pub private: Option<bool>,
 }
 
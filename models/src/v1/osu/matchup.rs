@@ -2,22 +2,117 @@ use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+use super::SeedingMethod;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Matchup {
     #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
     pub id: ObjectId,
     #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
     pub tournament_id: ObjectId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_id: Option<ObjectId>,
 
     pub date: DateTime<Utc>,
 
     pub team_red: Option<ObjectId>,
     pub team_blue: Option<ObjectId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_team: Option<MatchupTeam>,
 
     pub maps: Vec<MatchupMap>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bracket: Option<MatchupBracket>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<MatchupGroup>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner_to: Option<MatchupSlot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loser_to: Option<MatchupSlot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub red_from: Option<MatchupFeeder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blue_from: Option<MatchupFeeder>,
+
+    /// Maps won by each team so far
+    pub team_red_score: i16,
+    pub team_blue_score: i16,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<MatchupResult>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupBracket {
+    pub side: BracketSide,
+    pub round: i32,
+    pub position: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupGroup {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub group_id: ObjectId,
+    pub round: i32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum BracketSide {
+    Winners,
+    Losers,
+    GrandFinals,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupSlot {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub matchup_id: ObjectId,
+    pub team: MatchupTeam,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MatchupTeam {
+    Red,
+    Blue,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupFeeder {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub matchup_id: ObjectId,
+    pub outcome: MatchupOutcome,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MatchupOutcome {
+    Winner,
+    Loser,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MatchupResult {
+    pub winner: MatchupTeam,
+    pub kind: MatchupResultKind,
+
+    pub team_red_score: i16,
+    pub team_blue_score: i16,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MatchupResultKind {
+    Played,
+    Walkover,
+    Forfeit,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BracketFormat {
+    SingleElimination,
+    DoubleElimination,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct MatchupMap {
     pub map_id: i32,
     pub map_type: MatchupMapType,
@@ -29,6 +124,8 @@ pub struct MatchupMap {
     pub team_red_scores: Option<Vec<MatchupMapScore>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub team_blue_scores: Option<Vec<MatchupMapScore>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner: Option<MatchupTeam>,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -38,7 +135,7 @@ pub enum MatchupMapType {
     Protect,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct MatchupMapScore {
     pub player: i32,
 
@@ -46,12 +143,65 @@ pub struct MatchupMapScore {
     pub mods: Option<String>,
 
     pub score: u32,
+
+    /// Between 0 and 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_combo: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub misses: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub judgements: Option<ScoreJudgements>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ScoreJudgements {
+    #[serde(default)]
+    pub count_geki: u32,
+    #[serde(default)]
+    pub count_300: u32,
+    #[serde(default)]
+    pub count_katu: u32,
+    #[serde(default)]
+    pub count_100: u32,
+    #[serde(default)]
+    pub count_50: u32,
+    #[serde(default)]
+    pub count_miss: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ScoringRules {
+    /// Defaults to `Score`
+    #[serde(default)]
+    pub win_condition: WinCondition,
+    /// Defaults to `ScoreV2`
+    #[serde(default)]
+    pub version: ScoringVersion,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum WinCondition {
+    #[default]
+    Score,
+    Accuracy,
+    Combo,
+    Misses,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ScoringVersion {
+    ScoreV1,
+    #[default]
+    ScoreV2,
 }
 
 #[derive(garde::Validate, Deserialize, Debug)]
 #[garde(allow_unvalidated)]
 pub struct CreateMatchupRequest {
     pub tournament_id: ObjectId,
+    pub round_id: Option<ObjectId>,
     pub date: DateTime<Utc>,
     pub team_red: Option<ObjectId>,
     pub team_blue: Option<ObjectId>,
@@ -66,5 +216,84 @@ pub struct CreateMatchupResponse {
 #[derive(garde::Validate, Deserialize, Debug)]
 #[garde(allow_unvalidated)]
 pub struct UpdateMatchupRequest {
+    pub round_id: Option<ObjectId>,
     pub date: Option<DateTime<Utc>>,
+    /// Team acting as `A` in the pick/ban sequence, e.g. the roll winner
+    pub first_team: Option<MatchupTeam>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct GenerateBracketRequest {
+    pub format: BracketFormat,
+    /// Team ids from the best seed down, defaults to registration order
+    pub seeds: Option<Vec<String>>,
+    /// Seeds the teams from qualifier results instead of `seeds`
+    pub seeding: Option<QualifierSeeding>,
+    pub grand_finals_reset: Option<bool>,
+    /// Date of the matches, those of a round not scheduled then start with their round
+    pub date: DateTime<Utc>,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QualifierSeeding {
+    pub round_id: String,
+    pub method: SeedingMethod,
+}
+
+#[derive(Serialize)]
+pub struct GenerateBracketResponse {
+    pub matches: Vec<Matchup>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct RecordMatchupResultRequest {
+    pub winner: MatchupTeam,
+    /// Defaults to `Played`
+    pub kind: Option<MatchupResultKind>,
+
+    /// Required for played matches, where the winner must have the higher score
+    #[garde(range(min = 0))]
+    pub team_red_score: Option<i16>,
+    #[garde(range(min = 0))]
+    pub team_blue_score: Option<i16>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct RecordMatchupMapRequest {
+    pub map_id: i32,
+    pub map_type: MatchupMapType,
+    /// Team making the pick, ban or protect
+    pub team: MatchupTeam,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct UpdateMatchupMapRequest {
+    pub map_id: Option<i32>,
+    pub map_type: Option<MatchupMapType>,
+    pub team: Option<MatchupTeam>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct RecordMatchupMapScoresRequest {
+    pub team_red_scores: Vec<MatchupMapScore>,
+    pub team_blue_scores: Vec<MatchupMapScore>,
+}
+
+#[derive(Serialize)]
+pub struct ImportMatchupScoresResponse {
+    pub matchup: Matchup,
+    /// Picks that received scores
+    pub imported_maps: Vec<i32>,
+    /// Beatmaps played in the lobby that are not picks of the matchup
+    pub unknown_maps: Vec<i32>,
+    /// Players who set scores without being on either team
+    pub unknown_players: Vec<i32>,
+    /// Beatmaps played with another scoring version than the matchup's rules
+    pub mismatched_scoring: Vec<i32>,
 }
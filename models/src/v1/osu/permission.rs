@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use crate::v1::osu::TournamentStaffRole;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PermissionGrant {
+    pub user_id: i32,
+    pub role: TournamentStaffRole,
+}
+
+// DTO
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct GrantPermissionRequest {
+    pub user_id: i32,
+    pub role: TournamentStaffRole,
+}
@@ -0,0 +1,35 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::v1::osu::GameMode;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Player {
+    pub id: i32,
+    pub username: String,
+
+    pub country: String,
+    pub badges: i32,
+    pub stats: Vec<PlayerStats>,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub mode: GameMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_rank: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_rank: Option<i32>,
+    pub pp: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PlayerSnapshot {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub tournament_id: ObjectId,
+    pub player: Player,
+    pub taken_at: DateTime<Utc>,
+}
@@ -0,0 +1,111 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::MatchupMapScore;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct QualifierLobby {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub tournament_id: ObjectId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_id: Option<ObjectId>,
+
+    pub name: String,
+    pub date: DateTime<Utc>,
+    pub signup_deadline: DateTime<Utc>,
+
+    pub capacity: i32,
+    pub teams: Vec<ObjectId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referee: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct QualifierScore {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub team_id: ObjectId,
+    pub map_id: i32,
+
+    pub scores: Vec<MatchupMapScore>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedingMethod {
+    SumOfScores,
+    ZSum,
+    Percentile,
+    RankSum,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Seed {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub team_id: ObjectId,
+    pub seed: i32,
+    pub value: f64,
+}
+
+// DTO
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct CreateQualifierLobbyRequest {
+    pub round_id: Option<ObjectId>,
+    #[garde(length(min = 1, max = crate::MAX_NAME_LENGTH))]
+    pub name: String,
+    pub date: DateTime<Utc>,
+    /// Defaults to the lobby date
+    pub signup_deadline: Option<DateTime<Utc>>,
+    #[garde(range(min = 1, max = 64))]
+    pub capacity: i32,
+}
+
+#[derive(Serialize)]
+pub struct CreateQualifierLobbyResponse {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub id: ObjectId,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct UpdateQualifierLobbyRequest {
+    #[garde(length(min = 1, max = crate::MAX_NAME_LENGTH))]
+    pub name: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    pub signup_deadline: Option<DateTime<Utc>>,
+    #[garde(range(min = 1, max = 64))]
+    pub capacity: Option<i32>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct AssignRefereeRequest {
+    /// osu! id of the referee, `null` to unassign
+    pub referee: Option<i32>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct SubmitQualifierScoreRequest {
+    pub team_id: ObjectId,
+    pub map_id: i32,
+    #[garde(length(min = 1))]
+    pub scores: Vec<MatchupMapScore>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SeedingQuery {
+    /// Defaults to `z_sum`
+    pub method: Option<SeedingMethod>,
+}
+
+#[derive(Serialize)]
+pub struct SeedingResponse {
+    pub method: SeedingMethod,
+    pub seeds: Vec<Seed>,
+}
@@ -0,0 +1,94 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{MatchupMapType, ScoringRules};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct Round {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub tournament_id: ObjectId,
+
+    pub name: String,
+    pub kind: RoundKind,
+    pub order: i32,
+
+    pub best_of: i16,
+    pub ban_count: i16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pick_ban_sequence: Option<PickBanSequence>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<ScoringRules>,
+
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum RoundKind {
+    Qualifiers,
+    Groups,
+    Bracket,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PickBanSequence {
+    pub steps: Vec<PickBanStep>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PickBanStep {
+    pub action: MatchupMapType,
+    pub team: SequenceTeam,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum SequenceTeam {
+    A,
+    B,
+}
+
+// DTO
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct CreateRoundRequest {
+    pub tournament_id: String,
+    #[garde(length(min = 1, max = crate::MAX_NAME_LENGTH))]
+    pub name: String,
+    pub kind: RoundKind,
+    pub order: i32,
+    #[garde(range(min = 1, max = 25))]
+    pub best_of: i16,
+    #[garde(range(min = 0, max = 10))]
+    pub ban_count: i16,
+    pub pick_ban_sequence: Option<PickBanSequence>,
+    pub scoring: Option<ScoringRules>,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct CreateRoundResponse {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub id: ObjectId,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct UpdateRoundRequest {
+    #[garde(length(min = 1, max = crate::MAX_NAME_LENGTH))]
+    pub name: Option<String>,
+    pub kind: Option<RoundKind>,
+    pub order: Option<i32>,
+    #[garde(range(min = 1, max = 25))]
+    pub best_of: Option<i16>,
+    #[garde(range(min = 0, max = 10))]
+    pub ban_count: Option<i16>,
+    pub pick_ban_sequence: Option<PickBanSequence>,
+    pub scoring: Option<ScoringRules>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+}
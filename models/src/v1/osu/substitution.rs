@@ -0,0 +1,45 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct Substitution {
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub tournament_id: ObjectId,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    pub team_id: ObjectId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_out: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_in: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    pub requested_by: i32,
+    pub status: SubstitutionStatus,
+    pub created_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SubstitutionStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+// DTO
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct CreateSubstitutionRequest {
+    pub player_out: Option<i32>,
+    pub player_in: Option<i32>,
+    #[garde(length(max = 500))]
+    pub reason: Option<String>,
+}
@@ -2,7 +2,7 @@ use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::v1::osu::GameMode;
+use crate::v1::osu::{EligibilityRules, GameMode, ScoringRules};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 pub struct Tournament {
@@ -12,6 +12,7 @@ pub struct Tournament {
     pub name: String,
 
     pub mode: GameMode,
+    pub stage: TournamentStage,
 
     #[serde(skip_serializing_if = "crate::if_false", default)]
     pub invite_only: bool,
@@ -20,6 +21,12 @@ pub struct Tournament {
 
     pub registration_start_date: DateTime<Utc>,
     pub registration_end_date: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roster_lock_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eligibility: Option<EligibilityRules>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<ScoringRules>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
@@ -29,12 +36,15 @@ pub struct TournamentTeam {
 
     pub captain: i32,
     pub players: Vec<i32>,
+
+    #[serde(skip_serializing_if = "crate::if_false", default)]
+    pub disqualified: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 pub struct TournamentStaff {
     pub id: i32,
-    pub role: TournamentStaffRole,
+    pub roles: Vec<TournamentStaffRole>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
@@ -54,8 +64,57 @@ pub enum TournamentStaffRole {
     Developer,
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TournamentStage {
+    Draft,
+    Announced,
+    Registration,
+    Screening,
+    Qualifiers,
+    Bracket,
+    Finished,
+    Cancelled,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TournamentRegistrationStatus {
+    Upcoming,
+    Open,
+    Closed,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TournamentSort {
+    #[default]
+    Newest,
+    Oldest,
+    Name,
+    RegistrationStart,
+    RegistrationEnd,
+}
+
 // DTO
 
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct ListTournamentQuery {
+    pub mode: Option<GameMode>,
+    pub registration: Option<TournamentRegistrationStatus>,
+    #[garde(length(max = crate::MAX_NAME_LENGTH))]
+    pub search: Option<String>,
+    pub sort: Option<TournamentSort>,
+    pub cursor: Option<String>,
+    #[garde(range(min = 1, max = crate::MAX_PAGE_SIZE))]
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct ListTournamentResponse {
+    pub tournaments: Vec<Tournament>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
 #[derive(garde::Validate, Deserialize, Debug)]
 #[garde(allow_unvalidated)]
 pub struct CreateTournamentRequest {
@@ -71,6 +130,10 @@ pub struct CreateTournamentRequest {
     pub max_team_size: i16,
     pub registration_start_date: Option<DateTime<Utc>>,
     pub registration_end_date: Option<DateTime<Utc>>,
+    pub roster_lock_date: Option<DateTime<Utc>>,
+    pub eligibility: Option<EligibilityRules>,
+    /// Win condition and scoring version, rounds may override them
+    pub scoring: Option<ScoringRules>,
 }
 
 #[derive(Serialize)]
@@ -95,6 +158,22 @@ pub struct UpdateTournamentRequest {
     pub max_team_size: Option<i16>,
     pub registration_start_date: Option<DateTime<Utc>>,
     pub registration_end_date: Option<DateTime<Utc>>,
+    pub roster_lock_date: Option<DateTime<Utc>>,
+    pub eligibility: Option<EligibilityRules>,
+    /// Win condition and scoring version, rounds may override them
+    pub scoring: Option<ScoringRules>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct TransitionTournamentRequest {
+    pub stage: TournamentStage,
+}
+
+#[derive(Serialize)]
+pub struct TournamentTransitionsResponse {
+    pub stage: TournamentStage,
+    pub next_stages: Vec<TournamentStage>,
 }
 
 #[derive(garde::Validate, Deserialize, Debug)]
@@ -102,7 +181,17 @@ pub struct UpdateTournamentRequest {
 pub struct RegisterTournamentTeamRequest {
     #[garde(length(min = crate::MIN_NAME_LENGTH, max = crate::MAX_NAME_LENGTH))]
     pub name: String,
+    /// Players invited to join the team, they are added once they accept
     pub players: Option<Vec<i32>>,
+    /// Required by invite-only tournaments
+    pub invite_code: Option<String>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct RegisterTournamentPlayerRequest {
+    /// Required by invite-only tournaments
+    pub invite_code: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -111,7 +200,42 @@ pub struct RegisterTournamentTeamResponse {
     pub id: ObjectId,
 }
 
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct UpdateTournamentTeamRequest {
+    #[garde(length(min = crate::MIN_NAME_LENGTH, max = crate::MAX_NAME_LENGTH))]
+    pub name: String,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct TransferCaptainRequest {
+    pub player_id: i32,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct DisqualifyTournamentTeamRequest {
+    /// `false` reinstates the team
+    pub disqualified: Option<bool>,
+}
+
 #[derive(Serialize)]
 pub struct ListPlayerResponse {
     pub players: Vec<i32>,
 }
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct AddTournamentStaffRequest {
+    pub id: i32,
+    #[garde(length(min = 1))]
+    pub roles: Vec<TournamentStaffRole>,
+}
+
+#[derive(garde::Validate, Deserialize, Debug)]
+#[garde(allow_unvalidated)]
+pub struct UpdateTournamentStaffRequest {
+    #[garde(length(min = 1))]
+    pub roles: Vec<TournamentStaffRole>,
+}